@@ -0,0 +1,75 @@
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{AppHandle, Emitter, Manager, Runtime, Window, WindowEvent};
+
+use crate::session::VaultSession;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+// Écart entre horloge murale et horloge monotone au-delà duquel on considère que le système a dormi
+const SLEEP_DETECTION_GAP: Duration = Duration::from_secs(30);
+
+pub const VAULT_LOCKED_EVENT: &str = "vault-locked";
+
+// Verrouille le coffre et prévient le frontend avec la raison du verrouillage
+pub fn lock_and_notify<R: Runtime>(app_handle: &AppHandle<R>, reason: &str) -> Result<(), String> {
+    let session = app_handle.state::<VaultSession>();
+    if !session.is_unlocked()? {
+        return Ok(());
+    }
+
+    session.lock()?;
+    app_handle
+        .emit(VAULT_LOCKED_EVENT, reason)
+        .map_err(|e| format!("Erreur lors de l'envoi de l'événement: {}", e))
+}
+
+// Surveille l'inactivité et la mise en veille du système dans un thread dédié
+pub fn spawn_watcher<R: Runtime>(app_handle: AppHandle<R>) {
+    std::thread::spawn(move || {
+        let mut last_tick = Instant::now();
+        let mut last_wall = SystemTime::now();
+
+        loop {
+            std::thread::sleep(CHECK_INTERVAL);
+
+            let session = app_handle.state::<VaultSession>();
+            let now = Instant::now();
+            let wall = SystemTime::now();
+
+            // L'horloge monotone ne compte pas le temps passé en veille, contrairement à l'horloge murale
+            let wall_elapsed = wall.duration_since(last_wall).unwrap_or_default();
+            let resumed_from_sleep = wall_elapsed > now.duration_since(last_tick) + SLEEP_DETECTION_GAP;
+            last_tick = now;
+            last_wall = wall;
+
+            let lock_on_sleep = session.auto_lock_settings().map(|s| s.lock_on_sleep).unwrap_or(true);
+            if resumed_from_sleep && lock_on_sleep {
+                let _ = lock_and_notify(&app_handle, "sleep");
+                continue;
+            }
+
+            if let Ok(true) = session.lock_if_idle(now) {
+                let _ = app_handle.emit(VAULT_LOCKED_EVENT, "idle");
+            }
+        }
+    });
+}
+
+// Verrouillage à la réduction ou à la fermeture de la fenêtre selon les réglages
+pub fn handle_window_event<R: Runtime>(window: &Window<R>, event: &WindowEvent) {
+    let app_handle = window.app_handle();
+    let Some(Ok(settings)) = app_handle.try_state::<VaultSession>().map(|s| s.auto_lock_settings()) else {
+        return;
+    };
+
+    match event {
+        WindowEvent::CloseRequested { .. } if settings.lock_on_close => {
+            let _ = lock_and_notify(app_handle, "close");
+        }
+        WindowEvent::Resized(_) | WindowEvent::Focused(false)
+            if settings.lock_on_minimize && window.is_minimized().unwrap_or(false) =>
+        {
+            let _ = lock_and_notify(app_handle, "minimize");
+        }
+        _ => {}
+    }
+}
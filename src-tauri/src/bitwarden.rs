@@ -0,0 +1,751 @@
+use aes::cipher::{block_padding::Pkcs7, BlockDecryptMut, KeyIvInit};
+use aes::Aes256;
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use zeroize::Zeroizing;
+
+use crate::import_export::{extract_domain_from_url, ImportedPassword, ParsedImport};
+use crate::vault::{CustomField, EntryType, EntryUri, PasswordHistoryEntry, UriMatch};
+
+// Import et export au format JSON Bitwarden : « items » référence les dossiers (export
+// personnel) et les collections (export d'organisation) par leur identifiant
+
+const TYPE_LOGIN: u8 = 1;
+const TYPE_SECURE_NOTE: u8 = 2;
+const TYPE_CARD: u8 = 3;
+const TYPE_IDENTITY: u8 = 4;
+const TYPE_SSH_KEY: u8 = 5;
+
+const KDF_PBKDF2: u8 = 0;
+const KDF_ARGON2ID: u8 = 1;
+
+// Bornes des paramètres de dérivation acceptés par Bitwarden
+const PBKDF2_ITERATIONS: std::ops::RangeInclusive<u32> = 5_000..=2_000_000;
+const ARGON2_MEMORY_MIB: std::ops::RangeInclusive<u32> = 16..=1024;
+const ARGON2_ITERATIONS: std::ops::RangeInclusive<u32> = 2..=10;
+const ARGON2_PARALLELISM: std::ops::RangeInclusive<u32> = 1..=16;
+
+// Type d'EncString « 2 » : AES-256-CBC, authentifié par HMAC-SHA256
+const ENC_TYPE_AES_CBC_HMAC: &str = "2";
+
+type HmacSha256 = Hmac<Sha256>;
+
+const FIELD_TEXT: u8 = 0;
+const FIELD_HIDDEN: u8 = 1;
+// Champ personnalisé lié à un champ natif (identifiant, mot de passe) : sans valeur propre
+const FIELD_LINKED: u8 = 3;
+
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct Export {
+    encrypted: bool,
+    password_protected: bool,
+    folders: Vec<Group>,
+    collections: Vec<Group>,
+    items: Vec<Item>,
+}
+
+// Export protégé par un mot de passe de fichier : « data » chiffre l'export en clair
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProtectedExport {
+    salt: String,
+    kdf_type: u8,
+    kdf_iterations: u32,
+    kdf_memory: Option<u32>,
+    kdf_parallelism: Option<u32>,
+    #[serde(rename = "encKeyValidation_DO_NOT_EDIT")]
+    enc_key_validation: String,
+    data: String,
+}
+
+// Clés de chiffrement et d'authentification dérivées du mot de passe de l'export
+struct ExportKeys {
+    enc_key: Zeroizing<[u8; 32]>,
+    mac_key: Zeroizing<[u8; 32]>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Group {
+    id: String,
+    name: String,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct Item {
+    #[serde(rename = "type")]
+    item_type: u8,
+    name: String,
+    notes: Option<String>,
+    favorite: bool,
+    folder_id: Option<String>,
+    collection_ids: Option<Vec<String>>,
+    fields: Option<Vec<Field>>,
+    login: Option<Login>,
+    card: Option<HashMap<String, Value>>,
+    identity: Option<HashMap<String, Value>>,
+    password_history: Option<Vec<HistoryEntry>>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Login {
+    username: Option<String>,
+    password: Option<String>,
+    totp: Option<String>,
+    uris: Option<Vec<Value>>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Field {
+    name: Option<String>,
+    value: Option<String>,
+    #[serde(rename = "type")]
+    field_type: u8,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct HistoryEntry {
+    password: Option<String>,
+    last_used_date: Option<String>,
+}
+
+// Libellés des propriétés d'une carte, dans l'ordre d'affichage
+const CARD_FIELDS: &[(&str, &str)] = &[
+    ("brand", "Marque"),
+    ("number", "Numéro"),
+    ("expMonth", "Mois d'expiration"),
+    ("expYear", "Année d'expiration"),
+    ("code", "Code de sécurité"),
+];
+
+const IDENTITY_FIELDS: &[(&str, &str)] = &[
+    ("title", "Civilité"),
+    ("firstName", "Prénom"),
+    ("middleName", "Deuxième prénom"),
+    ("lastName", "Nom"),
+    ("company", "Société"),
+    ("email", "E-mail"),
+    ("phone", "Téléphone"),
+    ("address1", "Adresse"),
+    ("address2", "Adresse (suite)"),
+    ("address3", "Adresse (suite 2)"),
+    ("postalCode", "Code postal"),
+    ("city", "Ville"),
+    ("state", "Région"),
+    ("country", "Pays"),
+    ("ssn", "Numéro de sécurité sociale"),
+    ("passportNumber", "Numéro de passeport"),
+    ("licenseNumber", "Numéro de permis"),
+];
+
+pub fn parse_bitwarden_json(content: &str, export_password: Option<&str>) -> Result<ParsedImport, String> {
+    let export: Export = serde_json::from_str(content).map_err(|e| format!("JSON invalide: {}", e))?;
+    if export.password_protected {
+        let export_password = export_password.ok_or("Mot de passe requis pour ouvrir l'export Bitwarden chiffré")?;
+        let protected: ProtectedExport = serde_json::from_str(content)
+            .map_err(|e| format!("Export Bitwarden chiffré invalide: {}", e))?;
+        let decrypted = decrypt_protected_export(&protected, export_password)?;
+        return parse_bitwarden_json(&decrypted, None);
+    }
+    if export.encrypted {
+        // Sans mot de passe de fichier, l'export est chiffré avec la clé du compte Bitwarden
+        return Err(
+            "Cet export Bitwarden est chiffré avec la clé du compte : exportez-le avec un mot de passe de fichier"
+                .to_string(),
+        );
+    }
+
+    let folders: HashMap<&str, &str> = group_names(&export.folders);
+    let collections: HashMap<&str, &str> = group_names(&export.collections);
+
+    let mut parsed = ParsedImport::default();
+    for item in export.items {
+        let title = item.name.trim().to_string();
+
+        // Un élément personnel est rangé dans son dossier, un élément d'organisation dans
+        // sa première collection
+        let mut groups = item
+            .folder_id
+            .as_deref()
+            .and_then(|id| folders.get(id))
+            .into_iter()
+            .chain(item.collection_ids.iter().flatten().filter_map(|id| collections.get(id.as_str())))
+            .map(|name| name.to_string());
+        let folder = groups.next();
+        let other_groups = groups.count();
+
+        let mut password = ImportedPassword {
+            notes: item.notes.filter(|notes| !notes.trim().is_empty()),
+            folder,
+            favorite: item.favorite,
+            custom_fields: custom_fields(item.fields.unwrap_or_default()),
+            password_history: password_history(item.password_history.unwrap_or_default()),
+            ..ImportedPassword::default()
+        };
+
+        match (item.item_type, item.login, item.card, item.identity) {
+            (TYPE_LOGIN, Some(login), _, _) => {
+                password.uris = login.uris.iter().flatten().filter_map(bitwarden_uri).collect();
+                password.normalize_uris();
+                password.site = Some(title)
+                    .filter(|title| !title.is_empty())
+                    .or_else(|| password.url.as_deref().and_then(extract_domain_from_url))
+                    .unwrap_or_default();
+                password.username = login.username.unwrap_or_default();
+                password.password = login.password.unwrap_or_default();
+                password.totp = login.totp.filter(|totp| !totp.trim().is_empty());
+                if password.password.is_empty() {
+                    parsed.warnings.push(format!("{}: entrée sans mot de passe ignorée", password.site));
+                    continue;
+                }
+            }
+            (TYPE_SECURE_NOTE, _, _, _) => {
+                password.entry_type = EntryType::SecureNote;
+                password.site = title;
+            }
+            (TYPE_CARD, _, Some(card), _) => {
+                password.entry_type = EntryType::Card;
+                password.username = string_value(card.get("cardholderName")).unwrap_or_default();
+                password.custom_fields.splice(0..0, labelled_fields(&card, CARD_FIELDS));
+                password.site = title;
+            }
+            (TYPE_IDENTITY, _, _, Some(identity)) => {
+                password.entry_type = EntryType::Identity;
+                password.username = string_value(identity.get("username")).unwrap_or_default();
+                password.custom_fields.splice(0..0, labelled_fields(&identity, IDENTITY_FIELDS));
+                password.site = title;
+            }
+            (item_type, _, _, _) => {
+                parsed.warnings.push(format!(
+                    "{}: élément de type « {} » non importé",
+                    title,
+                    type_name(item_type)
+                ));
+                continue;
+            }
+        }
+
+        if other_groups > 0 {
+            parsed.warnings.push(format!(
+                "{}: {} collection(s) supplémentaire(s) non importée(s)",
+                password.site, other_groups
+            ));
+        }
+        parsed.passwords.push(password);
+    }
+
+    Ok(parsed)
+}
+
+fn decrypt_protected_export(protected: &ProtectedExport, export_password: &str) -> Result<Zeroizing<String>, String> {
+    let keys = derive_export_keys(protected, export_password)?;
+    // La valeur de validation ne se déchiffre qu'avec le bon mot de passe (HMAC vérifié)
+    decrypt_enc_string(&protected.enc_key_validation, &keys)
+        .map_err(|_| "Mot de passe de l'export Bitwarden incorrect".to_string())?;
+
+    let data = decrypt_enc_string(&protected.data, &keys)?;
+    String::from_utf8(data.to_vec())
+        .map(Zeroizing::new)
+        .map_err(|_| "Export Bitwarden déchiffré invalide".to_string())
+}
+
+fn derive_export_keys(protected: &ProtectedExport, export_password: &str) -> Result<ExportKeys, String> {
+    let mut master_key = Zeroizing::new([0u8; 32]);
+    match protected.kdf_type {
+        KDF_PBKDF2 => {
+            if !PBKDF2_ITERATIONS.contains(&protected.kdf_iterations) {
+                return Err(format!("Itérations PBKDF2 invalides: {}", protected.kdf_iterations));
+            }
+            pbkdf2::pbkdf2_hmac::<Sha256>(
+                export_password.as_bytes(),
+                protected.salt.as_bytes(),
+                protected.kdf_iterations,
+                &mut master_key[..],
+            );
+        }
+        KDF_ARGON2ID => {
+            let memory = protected.kdf_memory.unwrap_or_default();
+            let parallelism = protected.kdf_parallelism.unwrap_or_default();
+            if !ARGON2_MEMORY_MIB.contains(&memory)
+                || !ARGON2_ITERATIONS.contains(&protected.kdf_iterations)
+                || !ARGON2_PARALLELISM.contains(&parallelism)
+            {
+                return Err("Paramètres Argon2id de l'export Bitwarden invalides".to_string());
+            }
+            let params = Params::new(memory * 1024, protected.kdf_iterations, parallelism, Some(32))
+                .map_err(|e| format!("Paramètres Argon2id invalides: {}", e))?;
+            // Bitwarden condense le sel avec SHA-256 avant Argon2id
+            let salt = Sha256::digest(protected.salt.as_bytes());
+            Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+                .hash_password_into(export_password.as_bytes(), &salt, &mut master_key[..])
+                .map_err(|e| format!("Erreur lors de la dérivation de la clé: {}", e))?;
+        }
+        other => return Err(format!("Dérivation de clé Bitwarden inconnue: {}", other)),
+    }
+
+    // Extension HKDF de la clé maîtresse en clés de chiffrement et de MAC
+    let hkdf = Hkdf::<Sha256>::from_prk(&master_key[..]).map_err(|_| "Clé dérivée invalide".to_string())?;
+    let mut keys = ExportKeys { enc_key: Zeroizing::new([0; 32]), mac_key: Zeroizing::new([0; 32]) };
+    hkdf.expand(b"enc", &mut keys.enc_key[..])
+        .and_then(|_| hkdf.expand(b"mac", &mut keys.mac_key[..]))
+        .map_err(|_| "Erreur lors de l'extension de la clé".to_string())?;
+    Ok(keys)
+}
+
+// EncString Bitwarden « 2.iv|données|mac », chaque partie en base64
+fn decrypt_enc_string(value: &str, keys: &ExportKeys) -> Result<Zeroizing<Vec<u8>>, String> {
+    let (enc_type, parts) = value.split_once('.').ok_or("Valeur chiffrée Bitwarden invalide")?;
+    if enc_type != ENC_TYPE_AES_CBC_HMAC {
+        return Err(format!("Type de chiffrement Bitwarden non pris en charge: {}", enc_type));
+    }
+    let decoded: Vec<Vec<u8>> = parts
+        .split('|')
+        .map(|part| STANDARD.decode(part))
+        .collect::<Result<_, _>>()
+        .map_err(|_| "Valeur chiffrée Bitwarden invalide".to_string())?;
+    let [iv, data, mac] = decoded.as_slice() else {
+        return Err("Valeur chiffrée Bitwarden invalide".to_string());
+    };
+
+    let mut hmac = <HmacSha256 as Mac>::new_from_slice(&keys.mac_key[..]).expect("HMAC accepte toute taille de clé");
+    hmac.update(iv);
+    hmac.update(data);
+    hmac.verify_slice(mac)
+        .map_err(|_| "Valeur chiffrée Bitwarden altérée ou mot de passe incorrect".to_string())?;
+
+    cbc::Decryptor::<Aes256>::new_from_slices(&keys.enc_key[..], iv)
+        .map_err(|_| "Vecteur d'initialisation invalide".to_string())?
+        .decrypt_padded_vec_mut::<Pkcs7>(data)
+        .map(Zeroizing::new)
+        .map_err(|_| "Erreur lors du déchiffrement de l'export Bitwarden".to_string())
+}
+
+// Export en clair lisible par Bitwarden et les gestionnaires qui importent son format
+pub fn export_bitwarden_json(passwords: &[ImportedPassword]) -> Result<String, String> {
+    // Un identifiant par dossier distinct, dans l'ordre alphabétique
+    let folder_ids: BTreeMap<&str, String> = passwords
+        .iter()
+        .filter_map(|p| p.folder.as_deref().map(str::trim).filter(|f| !f.is_empty()))
+        .map(|folder| (folder, random_uuid()))
+        .collect();
+    let folders: Vec<Value> = folder_ids
+        .iter()
+        .map(|(name, id)| json!({ "id": id, "name": name }))
+        .collect();
+
+    let items: Vec<Value> = passwords
+        .iter()
+        .map(|password| {
+            let folder_id = password.folder.as_deref().and_then(|folder| folder_ids.get(folder.trim()));
+            let mut item = json!({
+                "id": random_uuid(),
+                "organizationId": null,
+                "folderId": folder_id,
+                "type": TYPE_LOGIN,
+                "reprompt": 0,
+                "name": password.site,
+                "notes": password.notes,
+                "favorite": password.favorite,
+                "collectionIds": null,
+            });
+
+            let mut fields = password.custom_fields.clone();
+            match password.entry_type {
+                EntryType::Login => {
+                    let uris: Vec<Value> = password
+                        .uris
+                        .iter()
+                        .map(|uri| json!({ "match": uri.match_type.map(match_code), "uri": uri.uri }))
+                        .collect();
+                    // Les entrées sans liste d'URIs n'ont que leur url
+                    let uris = if uris.is_empty() {
+                        password.url.iter().map(|url| json!({ "match": null, "uri": url })).collect()
+                    } else {
+                        uris
+                    };
+                    item["login"] = json!({
+                        "uris": uris,
+                        "username": password.username,
+                        "password": password.password,
+                        "totp": password.totp,
+                    });
+                }
+                EntryType::SecureNote => {
+                    item["type"] = json!(TYPE_SECURE_NOTE);
+                    item["secureNote"] = json!({ "type": 0 });
+                }
+                EntryType::Card => {
+                    let mut card = take_labelled_fields(&mut fields, CARD_FIELDS);
+                    card.insert("cardholderName".to_string(), json!(password.username));
+                    item["type"] = json!(TYPE_CARD);
+                    item["card"] = Value::Object(card);
+                }
+                EntryType::Identity => {
+                    let mut identity = take_labelled_fields(&mut fields, IDENTITY_FIELDS);
+                    identity.insert("username".to_string(), json!(password.username));
+                    item["type"] = json!(TYPE_IDENTITY);
+                    item["identity"] = Value::Object(identity);
+                }
+            }
+
+            item["fields"] = fields
+                .iter()
+                .map(|field| {
+                    let field_type = if field.hidden { FIELD_HIDDEN } else { FIELD_TEXT };
+                    json!({ "name": field.name, "value": field.value, "type": field_type, "linkedId": null })
+                })
+                .collect();
+            item["passwordHistory"] = password
+                .password_history
+                .iter()
+                .map(|entry| json!({ "lastUsedDate": entry.changed_at, "password": entry.password }))
+                .collect();
+            item
+        })
+        .collect();
+
+    serde_json::to_string_pretty(&json!({ "encrypted": false, "folders": folders, "items": items }))
+        .map_err(|e| format!("Erreur lors de la sérialisation JSON: {}", e))
+}
+
+// Retire des champs personnalisés ceux qui correspondent à une propriété native (carte, identité)
+fn take_labelled_fields(fields: &mut Vec<CustomField>, labels: &[(&str, &str)]) -> Map<String, Value> {
+    let mut values = Map::new();
+    fields.retain(|field| match labels.iter().find(|(_, label)| *label == field.name) {
+        Some((key, _)) if !values.contains_key(*key) => {
+            values.insert(key.to_string(), json!(field.value));
+            false
+        }
+        _ => true,
+    });
+    values
+}
+
+// UUID v4, format des identifiants Bitwarden
+fn random_uuid() -> String {
+    let mut bytes = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+}
+
+fn group_names(groups: &[Group]) -> HashMap<&str, &str> {
+    groups
+        .iter()
+        .filter(|group| !group.name.trim().is_empty())
+        .map(|group| (group.id.as_str(), group.name.trim()))
+        .collect()
+}
+
+fn custom_fields(fields: Vec<Field>) -> Vec<CustomField> {
+    fields
+        .into_iter()
+        .filter(|field| field.field_type != FIELD_LINKED)
+        .filter_map(|field| {
+            let value = field.value.filter(|value| !value.is_empty())?;
+            let name = field.name.filter(|name| !name.trim().is_empty()).unwrap_or_default();
+            Some(CustomField { name, value, hidden: field.field_type == FIELD_HIDDEN })
+        })
+        .collect()
+}
+
+fn password_history(history: Vec<HistoryEntry>) -> Vec<PasswordHistoryEntry> {
+    history
+        .into_iter()
+        .filter_map(|entry| {
+            Some(PasswordHistoryEntry {
+                password: entry.password.filter(|password| !password.is_empty())?,
+                changed_at: entry.last_used_date,
+            })
+        })
+        .collect()
+}
+
+fn labelled_fields(values: &HashMap<String, Value>, labels: &[(&str, &str)]) -> Vec<CustomField> {
+    labels
+        .iter()
+        .filter_map(|(key, label)| {
+            Some(CustomField { name: label.to_string(), value: string_value(values.get(*key))?, hidden: false })
+        })
+        .collect()
+}
+
+// Valeur texte non vide ; les nombres (mois d'expiration...) sont convertis
+fn string_value(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::String(s) => Some(s.trim().to_string()).filter(|s| !s.is_empty()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+// URI Bitwarden : { "match": 0..5 ou null, "uri": "..." }
+fn bitwarden_uri(value: &Value) -> Option<EntryUri> {
+    let uri = value.get("uri")?.as_str()?.trim();
+    if uri.is_empty() {
+        return None;
+    }
+    let match_type = match value.get("match").and_then(|m| m.as_u64()) {
+        Some(0) => Some(UriMatch::Domain),
+        Some(1) => Some(UriMatch::Host),
+        Some(2) => Some(UriMatch::StartsWith),
+        Some(3) => Some(UriMatch::Exact),
+        Some(4) => Some(UriMatch::Regex),
+        Some(5) => Some(UriMatch::Never),
+        _ => None,
+    };
+    Some(EntryUri { uri: uri.to_string(), match_type })
+}
+
+fn match_code(match_type: UriMatch) -> u8 {
+    match match_type {
+        UriMatch::Domain => 0,
+        UriMatch::Host => 1,
+        UriMatch::StartsWith => 2,
+        UriMatch::Exact => 3,
+        UriMatch::Regex => 4,
+        UriMatch::Never => 5,
+    }
+}
+
+fn type_name(item_type: u8) -> String {
+    match item_type {
+        TYPE_LOGIN => "Identifiant".to_string(),
+        TYPE_SSH_KEY => "Clé SSH".to_string(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bitwarden_json() {
+        let json_content = r#"{
+            "encrypted": false,
+            "folders": [{"id": "f-1", "name": "Work/Infra"}],
+            "collections": [{"id": "c-1", "organizationId": "o-1", "name": "Équipe"}],
+            "items": [
+                {
+                    "type": 1,
+                    "name": "Example Site",
+                    "folderId": "f-1",
+                    "favorite": true,
+                    "login": {
+                        "username": "user@test.com",
+                        "password": "password123",
+                        "totp": "otpauth://totp/Example?secret=JBSWY3DPEHPK3PXP",
+                        "uris": [
+                            {"match": null, "uri": "https://example.com"},
+                            {"match": 3, "uri": "https://login.example.com/sso"},
+                            {"match": 4, "uri": "^https://.*\\.example\\.org/"},
+                            {"match": 5, "uri": ""}
+                        ]
+                    },
+                    "fields": [
+                        {"name": "PIN", "value": "1234", "type": 1, "linkedId": null},
+                        {"name": "Identifiant", "value": null, "type": 3, "linkedId": 100}
+                    ],
+                    "passwordHistory": [{"lastUsedDate": "2024-01-01T00:00:00.000Z", "password": "ancien"}],
+                    "notes": "Test notes"
+                },
+                {
+                    "type": 1,
+                    "name": "Sans identifiant",
+                    "collectionIds": ["c-1"],
+                    "login": {"username": null, "password": "hunter22"}
+                },
+                {"type": 1, "name": "Vide", "login": {"username": "user", "password": null}},
+                {"type": 2, "name": "Note", "notes": "secret", "secureNote": {"type": 0}},
+                {
+                    "type": 3,
+                    "name": "Carte",
+                    "card": {"cardholderName": "Jean Dupont", "brand": "Visa", "number": "4111111111111111", "expMonth": "4", "expYear": "2030", "code": "123"}
+                },
+                {"type": 4, "name": "Identité", "identity": {"firstName": "Jean", "lastName": "Dupont", "username": "jdupont"}},
+                {"type": 5, "name": "Serveur", "sshKey": {}}
+            ]
+        }"#;
+
+        let parsed = parse_bitwarden_json(json_content, None).unwrap();
+        assert_eq!(parsed.passwords.len(), 5);
+
+        let password = &parsed.passwords[0];
+        assert_eq!(password.site, "Example Site");
+        assert_eq!(password.username, "user@test.com");
+        assert_eq!(password.password, "password123");
+        assert_eq!(password.notes.as_deref(), Some("Test notes"));
+        assert_eq!(password.folder.as_deref(), Some("Work/Infra"));
+        assert!(password.favorite);
+        assert!(password.totp.is_some());
+        assert_eq!(password.url.as_deref(), Some("https://example.com"));
+        assert_eq!(password.uris.len(), 3);
+        assert_eq!(password.uris[1].match_type, Some(UriMatch::Exact));
+        assert_eq!(password.uris[2].match_type, Some(UriMatch::Regex));
+        assert_eq!(password.custom_fields, vec![CustomField { name: "PIN".to_string(), value: "1234".to_string(), hidden: true }]);
+        assert_eq!(password.password_history[0].password, "ancien");
+
+        assert_eq!(parsed.passwords[1].username, "");
+        assert_eq!(parsed.passwords[1].folder.as_deref(), Some("Équipe"));
+
+        assert_eq!(parsed.passwords[2].entry_type, EntryType::SecureNote);
+        assert_eq!(parsed.passwords[2].notes.as_deref(), Some("secret"));
+
+        let card = &parsed.passwords[3];
+        assert_eq!(card.entry_type, EntryType::Card);
+        assert_eq!(card.username, "Jean Dupont");
+        assert_eq!(card.custom_fields.len(), 5);
+        assert_eq!(card.custom_fields[1].value, "4111111111111111");
+
+        let identity = &parsed.passwords[4];
+        assert_eq!(identity.entry_type, EntryType::Identity);
+        assert_eq!(identity.username, "jdupont");
+        assert_eq!(identity.custom_fields.len(), 2);
+
+        assert_eq!(parsed.warnings, vec![
+            "Vide: entrée sans mot de passe ignorée".to_string(),
+            "Serveur: élément de type « Clé SSH » non importé".to_string(),
+        ]);
+
+        assert!(parse_bitwarden_json(r#"{"encrypted": true, "items": []}"#, None).is_err());
+    }
+
+    #[test]
+    fn test_export_bitwarden_json_round_trip() {
+        let passwords = vec![
+            ImportedPassword {
+                site: "Example".to_string(),
+                username: "user".to_string(),
+                password: "password123".to_string(),
+                notes: Some("Notes".to_string()),
+                url: Some("https://example.com".to_string()),
+                uris: vec![
+                    EntryUri { uri: "https://example.com".to_string(), match_type: None },
+                    EntryUri { uri: "https://sso.example.com".to_string(), match_type: Some(UriMatch::Host) },
+                ],
+                folder: Some("Work/Infra".to_string()),
+                custom_fields: vec![
+                    CustomField { name: "PIN".to_string(), value: "1234".to_string(), hidden: true },
+                    CustomField { name: "Question".to_string(), value: "Paris".to_string(), hidden: false },
+                ],
+                totp: Some("JBSWY3DPEHPK3PXP".to_string()),
+                favorite: true,
+                ..ImportedPassword::default()
+            },
+            ImportedPassword {
+                site: "Carte".to_string(),
+                username: "Jean Dupont".to_string(),
+                entry_type: EntryType::Card,
+                folder: Some("Work/Infra".to_string()),
+                custom_fields: vec![
+                    CustomField { name: "Numéro".to_string(), value: "4111111111111111".to_string(), hidden: false },
+                    CustomField { name: "Agence".to_string(), value: "Paris".to_string(), hidden: false },
+                ],
+                ..ImportedPassword::default()
+            },
+        ];
+
+        let json = export_bitwarden_json(&passwords).unwrap();
+        let export: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(export["folders"].as_array().unwrap().len(), 1);
+        assert_eq!(export["items"][0]["folderId"], export["folders"][0]["id"]);
+        assert_eq!(export["items"][0]["login"]["uris"][1]["match"], 1);
+        assert_eq!(export["items"][0]["fields"][0]["type"], FIELD_HIDDEN);
+        assert_eq!(export["items"][0]["fields"][1]["type"], FIELD_TEXT);
+        assert_eq!(export["items"][1]["card"]["number"], "4111111111111111");
+        assert_eq!(export["items"][1]["fields"].as_array().unwrap().len(), 1);
+
+        let parsed = parse_bitwarden_json(&json, None).unwrap();
+        assert!(parsed.warnings.is_empty());
+        let login = &parsed.passwords[0];
+        assert_eq!(login.folder.as_deref(), Some("Work/Infra"));
+        assert_eq!(login.uris, passwords[0].uris);
+        assert_eq!(login.custom_fields, passwords[0].custom_fields);
+        assert_eq!(login.totp, passwords[0].totp);
+        assert!(login.favorite);
+
+        let card = &parsed.passwords[1];
+        assert_eq!(card.entry_type, EntryType::Card);
+        assert_eq!(card.username, "Jean Dupont");
+        assert_eq!(card.custom_fields, passwords[1].custom_fields);
+    }
+
+    const PLAIN_EXPORT: &str = r#"{"encrypted": false, "folders": [], "items": [
+        {"type": 1, "name": "Example", "login": {"username": "user", "password": "password123"}}
+    ]}"#;
+
+    fn enc_string(keys: &ExportKeys, plaintext: &[u8]) -> String {
+        use aes::cipher::BlockEncryptMut;
+
+        let iv = [7u8; 16];
+        let data = cbc::Encryptor::<Aes256>::new_from_slices(&keys.enc_key[..], &iv)
+            .unwrap()
+            .encrypt_padded_vec_mut::<Pkcs7>(plaintext);
+        let mut hmac = <HmacSha256 as Mac>::new_from_slice(&keys.mac_key[..]).unwrap();
+        hmac.update(&iv);
+        hmac.update(&data);
+        let mac = hmac.finalize().into_bytes();
+        format!("2.{}|{}|{}", STANDARD.encode(iv), STANDARD.encode(data), STANDARD.encode(mac))
+    }
+
+    fn protected_export(kdf_type: u8, kdf_iterations: u32, password: &str) -> String {
+        let mut protected = ProtectedExport {
+            salt: "c2FsdC1iaXR3YXJkZW4=".to_string(),
+            kdf_type,
+            kdf_iterations,
+            kdf_memory: Some(16),
+            kdf_parallelism: Some(1),
+            enc_key_validation: String::new(),
+            data: String::new(),
+        };
+        let keys = derive_export_keys(&protected, password).unwrap();
+        protected.enc_key_validation = enc_string(&keys, b"9f1c0c3e-5a7b-4c2e-9d8f-0123456789ab");
+        protected.data = enc_string(&keys, PLAIN_EXPORT.as_bytes());
+
+        serde_json::json!({
+            "encrypted": true,
+            "passwordProtected": true,
+            "salt": protected.salt,
+            "kdfType": protected.kdf_type,
+            "kdfIterations": protected.kdf_iterations,
+            "kdfMemory": protected.kdf_memory,
+            "kdfParallelism": protected.kdf_parallelism,
+            "encKeyValidation_DO_NOT_EDIT": protected.enc_key_validation,
+            "data": protected.data,
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_parse_bitwarden_password_protected() {
+        for (kdf_type, iterations) in [(KDF_PBKDF2, 5_000), (KDF_ARGON2ID, 2)] {
+            let content = protected_export(kdf_type, iterations, "export-password");
+
+            let parsed = parse_bitwarden_json(&content, Some("export-password")).unwrap();
+            assert_eq!(parsed.passwords.len(), 1);
+            assert_eq!(parsed.passwords[0].password, "password123");
+
+            let err = parse_bitwarden_json(&content, Some("mauvais")).unwrap_err();
+            assert!(err.contains("incorrect"));
+            assert!(parse_bitwarden_json(&content, None).unwrap_err().contains("requis"));
+        }
+
+        let content = protected_export(KDF_PBKDF2, 5_000, "export-password").replace("\"kdfIterations\":5000", "\"kdfIterations\":1");
+        assert!(parse_bitwarden_json(&content, Some("export-password")).is_err());
+    }
+}
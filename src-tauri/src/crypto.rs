@@ -0,0 +1,104 @@
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    XChaCha20Poly1305, XNonce,
+};
+use rand::RngCore;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::kdf::KdfParams;
+
+pub const KEY_LEN: usize = 32;
+pub const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+
+// Clé symétrique du coffre, effacée de la mémoire à la destruction
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct VaultKey([u8; KEY_LEN]);
+
+impl VaultKey {
+    pub fn as_bytes(&self) -> &[u8; KEY_LEN] {
+        &self.0
+    }
+}
+
+pub fn generate_salt() -> [u8; SALT_LEN] {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    salt
+}
+
+// Dérivation de la clé à partir du mot de passe maître (Argon2id)
+pub fn derive_key(master_password: &str, salt: &[u8], params: &KdfParams) -> Result<VaultKey, String> {
+    let mut key = [0u8; KEY_LEN];
+    params
+        .argon2()?
+        .hash_password_into(master_password.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Erreur lors de la dérivation de la clé: {}", e))?;
+
+    let vault_key = VaultKey(key);
+    key.zeroize();
+    Ok(vault_key)
+}
+
+// Chiffrement AEAD : le résultat contient le nonce suivi du texte chiffré
+pub fn encrypt(key: &VaultKey, plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>, String> {
+    let cipher = XChaCha20Poly1305::new(key.as_bytes().into());
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+    let ciphertext = cipher
+        .encrypt(&nonce, Payload { msg: plaintext, aad })
+        .map_err(|_| "Erreur lors du chiffrement".to_string())?;
+
+    let mut output = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+    output.extend_from_slice(&nonce);
+    output.extend_from_slice(&ciphertext);
+    Ok(output)
+}
+
+pub fn decrypt(key: &VaultKey, data: &[u8], aad: &[u8]) -> Result<Vec<u8>, String> {
+    if data.len() < NONCE_LEN {
+        return Err("Données chiffrées tronquées".to_string());
+    }
+
+    let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+    let cipher = XChaCha20Poly1305::new(key.as_bytes().into());
+
+    cipher
+        .decrypt(XNonce::from_slice(nonce), Payload { msg: ciphertext, aad })
+        .map_err(|_| "Déchiffrement impossible: clé invalide ou données corrompues".to_string())
+}
+
+pub fn encrypt_string(key: &VaultKey, value: &str, aad: &[u8]) -> Result<Vec<u8>, String> {
+    encrypt(key, value.as_bytes(), aad)
+}
+
+pub fn decrypt_string(key: &VaultKey, data: &[u8], aad: &[u8]) -> Result<String, String> {
+    let plaintext = decrypt(key, data, aad)?;
+    String::from_utf8(plaintext).map_err(|e| format!("Données déchiffrées invalides: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_decrypt_roundtrip() {
+        let key = derive_key("master", &generate_salt(), &KdfParams::default()).unwrap();
+        let encrypted = encrypt_string(&key, "secret", b"password").unwrap();
+
+        assert_ne!(&encrypted[NONCE_LEN..], b"secret");
+        assert_eq!(decrypt_string(&key, &encrypted, b"password").unwrap(), "secret");
+    }
+
+    #[test]
+    fn test_decrypt_rejects_wrong_key_or_aad() {
+        let salt = generate_salt();
+        let key = derive_key("master", &salt, &KdfParams::default()).unwrap();
+        let other_key = derive_key("autre", &salt, &KdfParams::default()).unwrap();
+        let encrypted = encrypt_string(&key, "secret", b"password").unwrap();
+
+        assert!(decrypt_string(&other_key, &encrypted, b"password").is_err());
+        assert!(decrypt_string(&key, &encrypted, b"username").is_err());
+        assert!(decrypt_string(&key, &encrypted[..10], b"password").is_err());
+    }
+}
@@ -0,0 +1,393 @@
+use csv::ReaderBuilder;
+use serde::{Deserialize, Serialize};
+
+use crate::import_export::{
+    extract_domain_from_url, split_uri_list, ImportIssue, ImportIssueKind, ImportedPassword, ParsedImport,
+};
+use crate::vault::CustomField;
+
+// Import CSV piloté par un mapping défini par l'utilisateur, pour les tableurs qui ne
+// correspondent à aucun gestionnaire connu
+
+// Nombre de lignes renvoyées par défaut par l'aperçu
+pub const DEFAULT_PREVIEW_ROWS: usize = 20;
+
+// Champ de l'entrée alimenté par une colonne
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CsvField {
+    Site,
+    Username,
+    Password,
+    Url,
+    Notes,
+    Folder,
+    Tags,
+    Custom,
+}
+
+// Colonne désignée par sa position (à partir de 0) ou par son nom d'en-tête
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CsvColumn {
+    Index(usize),
+    Name(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsvColumnMapping {
+    pub column: CsvColumn,
+    pub field: CsvField,
+    // Nom du champ personnalisé ; à défaut, l'en-tête de la colonne
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvMapping {
+    #[serde(default = "default_delimiter")]
+    pub delimiter: char,
+    #[serde(default = "default_quote")]
+    pub quote: char,
+    #[serde(default = "default_has_header")]
+    pub has_header: bool,
+    // Libellé WHATWG (utf-8, windows-1252, iso-8859-15, utf-16le...)
+    #[serde(default = "default_encoding")]
+    pub encoding: String,
+    pub columns: Vec<CsvColumnMapping>,
+}
+
+fn default_delimiter() -> char {
+    ','
+}
+
+fn default_quote() -> char {
+    '"'
+}
+
+fn default_has_header() -> bool {
+    true
+}
+
+fn default_encoding() -> String {
+    "utf-8".to_string()
+}
+
+// Premières lignes converties, pour valider le mapping avant l'import
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvPreview {
+    pub headers: Vec<String>,
+    pub passwords: Vec<ImportedPassword>,
+    pub warnings: Vec<String>,
+    pub issues: Vec<ImportIssue>,
+    pub total_rows: usize,
+}
+
+// Colonne résolue en position dans l'enregistrement
+struct ResolvedColumn {
+    index: usize,
+    field: CsvField,
+    name: String,
+}
+
+pub fn parse_mapped_csv(content: &[u8], mapping: &CsvMapping) -> Result<ParsedImport, String> {
+    let preview = preview_mapped_csv(content, mapping, usize::MAX)?;
+    Ok(ParsedImport { passwords: preview.passwords, warnings: preview.warnings, issues: preview.issues })
+}
+
+pub fn preview_mapped_csv(content: &[u8], mapping: &CsvMapping, limit: usize) -> Result<CsvPreview, String> {
+    let encoding = encoding_rs::Encoding::for_label(mapping.encoding.trim().as_bytes())
+        .ok_or_else(|| format!("Encodage non pris en charge: {}", mapping.encoding))?;
+    // Un BOM éventuel l'emporte sur l'encodage choisi
+    let (text, _, had_errors) = encoding.decode(content);
+
+    let mut preview = CsvPreview {
+        headers: Vec::new(),
+        passwords: Vec::new(),
+        warnings: Vec::new(),
+        issues: Vec::new(),
+        total_rows: 0,
+    };
+    if had_errors {
+        preview
+            .warnings
+            .push(format!("Caractères invalides en {} remplacés", encoding.name()));
+    }
+
+    let mut reader = ReaderBuilder::new()
+        .delimiter(ascii_byte(mapping.delimiter, "Séparateur")?)
+        .quote(ascii_byte(mapping.quote, "Caractère de citation")?)
+        .has_headers(mapping.has_header)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+
+    if mapping.has_header {
+        preview.headers = reader
+            .headers()
+            .map_err(|e| format!("Erreur lecture headers: {}", e))?
+            .iter()
+            .map(|h| h.trim().to_string())
+            .collect();
+    }
+    let columns = resolve_columns(&preview.headers, mapping)?;
+
+    // Numéro de ligne affiché, en-tête compris, si le lecteur ne donne pas la position
+    let first_line = if mapping.has_header { 2 } else { 1 };
+    for (index, result) in reader.records().enumerate() {
+        let record = result.map_err(|e| {
+            let line = e.position().map_or(first_line + index, |p| p.line() as usize);
+            format!("Erreur lecture ligne {}: {}", line, e)
+        })?;
+        // Début de l'enregistrement : les lignes vides et les champs multilignes décalent l'index
+        let line = record.position().map_or(first_line + index, |p| record_line(&text, p));
+        if record.iter().all(|value| value.trim().is_empty()) {
+            continue;
+        }
+        preview.total_rows += 1;
+        if preview.passwords.len() >= limit {
+            continue;
+        }
+
+        match map_record(&record, &columns) {
+            Ok(password) => preview.passwords.push(ImportedPassword { source_line: Some(line), ..password }),
+            Err((field, reason)) => preview.issues.push(ImportIssue {
+                line,
+                // Colonne qui aurait dû fournir la valeur, si le mapping en désigne une
+                column: columns.iter().find(|c| c.field == field).map(|c| c.name.clone()),
+                kind: ImportIssueKind::MissingField,
+                message: format!("{}, ligne ignorée", reason),
+            }),
+        }
+    }
+
+    Ok(preview)
+}
+
+// Le lecteur situe l'enregistrement avant les lignes vides qu'il a sautées pour l'atteindre
+fn record_line(text: &str, position: &csv::Position) -> usize {
+    let skipped = text.as_bytes()[position.byte() as usize..]
+        .iter()
+        .take_while(|b| matches!(b, b'\n' | b'\r'))
+        .filter(|b| **b == b'\n')
+        .count();
+    position.line() as usize + skipped
+}
+
+fn resolve_columns(headers: &[String], mapping: &CsvMapping) -> Result<Vec<ResolvedColumn>, String> {
+    if !mapping.columns.iter().any(|c| c.field == CsvField::Password) {
+        return Err("Le mapping doit associer une colonne au mot de passe".to_string());
+    }
+
+    mapping
+        .columns
+        .iter()
+        .map(|column| {
+            let index = match &column.column {
+                CsvColumn::Index(index) => *index,
+                CsvColumn::Name(name) => headers
+                    .iter()
+                    .position(|h| h.eq_ignore_ascii_case(name.trim()))
+                    .ok_or_else(|| format!("Colonne introuvable: {}", name))?,
+            };
+            let name = column
+                .name
+                .clone()
+                .filter(|n| !n.trim().is_empty())
+                .or_else(|| headers.get(index).cloned())
+                .unwrap_or_else(|| format!("Colonne {}", index + 1));
+            Ok(ResolvedColumn { index, field: column.field, name })
+        })
+        .collect()
+}
+
+fn map_record(
+    record: &csv::StringRecord,
+    columns: &[ResolvedColumn],
+) -> Result<ImportedPassword, (CsvField, &'static str)> {
+    let mut password = ImportedPassword::default();
+    let mut notes = Vec::new();
+    let mut folder = Vec::new();
+    let mut urls = Vec::new();
+
+    for column in columns {
+        let Some(value) = record.get(column.index).map(str::trim).filter(|v| !v.is_empty()) else {
+            continue;
+        };
+        // Plusieurs colonnes peuvent alimenter le même champ : notes, dossiers et URLs sont
+        // cumulés, les autres champs gardent la première valeur non vide
+        match column.field {
+            CsvField::Site if password.site.is_empty() => password.site = value.to_string(),
+            CsvField::Username if password.username.is_empty() => password.username = value.to_string(),
+            CsvField::Password if password.password.is_empty() => password.password = value.to_string(),
+            CsvField::Url => urls.extend(split_uri_list(Some(value.to_string()))),
+            CsvField::Notes => notes.push(value),
+            CsvField::Folder => folder.extend(value.split(['/', '\\']).map(str::trim).filter(|p| !p.is_empty())),
+            CsvField::Tags => {
+                for tag in value.split([',', ';']).map(str::trim).filter(|t| !t.is_empty()) {
+                    if !password.tags.iter().any(|t| t == tag) {
+                        password.tags.push(tag.to_string());
+                    }
+                }
+            }
+            CsvField::Custom => password.custom_fields.push(CustomField {
+                name: column.name.clone(),
+                value: value.to_string(),
+                hidden: false,
+            }),
+            _ => {}
+        }
+    }
+
+    if password.password.is_empty() {
+        return Err((CsvField::Password, "Mot de passe manquant"));
+    }
+    let mut password = password.with_uris(urls);
+    if password.site.is_empty() {
+        password.site = password
+            .url
+            .as_deref()
+            .and_then(extract_domain_from_url)
+            .ok_or((CsvField::Site, "Site manquant"))?;
+    }
+    password.notes = (!notes.is_empty()).then(|| notes.join("\n"));
+    password.folder = (!folder.is_empty()).then(|| folder.join("/"));
+
+    Ok(password)
+}
+
+fn ascii_byte(c: char, label: &str) -> Result<u8, String> {
+    u8::try_from(c)
+        .ok()
+        .filter(u8::is_ascii)
+        .ok_or_else(|| format!("{} invalide: « {} » (caractère ASCII attendu)", label, c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(column: CsvColumn, field: CsvField) -> CsvColumnMapping {
+        CsvColumnMapping { column, field, name: None }
+    }
+
+    #[test]
+    fn test_preview_mapped_csv() {
+        let csv_content = "Service;Compte;Secret;Équipe;Sous-dossier;Étiquettes;PIN;Commentaire\n\
+            Console AWS;ops;hunter22;Infra;Prod;cloud, critique;1234;Accès root\n\
+            ;ops;sans-site;Infra;;;;\n\
+            VPN;ops;;Infra;;;;\n\
+            Grafana;admin;grafana-pass;Supervision;;;;";
+        let mapping = CsvMapping {
+            delimiter: ';',
+            quote: '"',
+            has_header: true,
+            encoding: "utf-8".to_string(),
+            columns: vec![
+                column(CsvColumn::Name("service".to_string()), CsvField::Site),
+                column(CsvColumn::Index(1), CsvField::Username),
+                column(CsvColumn::Name("Secret".to_string()), CsvField::Password),
+                column(CsvColumn::Index(3), CsvField::Folder),
+                column(CsvColumn::Index(4), CsvField::Folder),
+                column(CsvColumn::Index(5), CsvField::Tags),
+                column(CsvColumn::Index(6), CsvField::Custom),
+                column(CsvColumn::Index(7), CsvField::Notes),
+            ],
+        };
+
+        let preview = preview_mapped_csv(csv_content.as_bytes(), &mapping, 1).unwrap();
+        assert_eq!(preview.headers.len(), 8);
+        assert_eq!(preview.total_rows, 4);
+        assert_eq!(preview.passwords.len(), 1);
+
+        let password = &preview.passwords[0];
+        assert_eq!(password.site, "Console AWS");
+        assert_eq!(password.username, "ops");
+        assert_eq!(password.password, "hunter22");
+        assert_eq!(password.folder.as_deref(), Some("Infra/Prod"));
+        assert_eq!(password.tags, vec!["cloud", "critique"]);
+        assert_eq!(password.custom_fields, vec![CustomField { name: "PIN".to_string(), value: "1234".to_string(), hidden: false }]);
+        assert_eq!(password.notes.as_deref(), Some("Accès root"));
+
+        let parsed = parse_mapped_csv(csv_content.as_bytes(), &mapping).unwrap();
+        assert_eq!(parsed.passwords.len(), 2);
+        assert_eq!(parsed.passwords[1].folder.as_deref(), Some("Supervision"));
+        assert!(parsed.warnings.is_empty());
+        assert_eq!(parsed.issues, vec![
+            ImportIssue {
+                line: 3,
+                column: Some("Service".to_string()),
+                kind: ImportIssueKind::MissingField,
+                message: "Site manquant, ligne ignorée".to_string(),
+            },
+            ImportIssue {
+                line: 4,
+                column: Some("Secret".to_string()),
+                kind: ImportIssueKind::MissingField,
+                message: "Mot de passe manquant, ligne ignorée".to_string(),
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_mapped_csv_issue_lines() {
+        // Une ligne vide et une note sur deux lignes précèdent la ligne rejetée
+        let csv_content = "site,user,password,notes\n\
+            \n\
+            GitHub,dev,gh-pass,\"première ligne\nseconde ligne\"\n\
+            GitLab,dev,,\n";
+        let mapping = CsvMapping {
+            delimiter: ',',
+            quote: '"',
+            has_header: true,
+            encoding: "utf-8".to_string(),
+            columns: vec![
+                column(CsvColumn::Index(0), CsvField::Site),
+                column(CsvColumn::Index(1), CsvField::Username),
+                column(CsvColumn::Index(2), CsvField::Password),
+                column(CsvColumn::Index(3), CsvField::Notes),
+            ],
+        };
+
+        let preview = preview_mapped_csv(csv_content.as_bytes(), &mapping, 10).unwrap();
+        assert_eq!(preview.passwords[0].source_line, Some(3));
+        assert_eq!(preview.issues.len(), 1);
+        assert_eq!(preview.issues[0].line, 5);
+        assert_eq!(preview.issues[0].column.as_deref(), Some("password"));
+    }
+
+    #[test]
+    fn test_mapped_csv_encoding_and_errors() {
+        // « Société » encodé en windows-1252, sans en-tête
+        let content = b"Soci\xe9t\xe9\tjean\tmotdepasse\n";
+        let mut mapping = CsvMapping {
+            delimiter: '\t',
+            quote: '"',
+            has_header: false,
+            encoding: "windows-1252".to_string(),
+            columns: vec![
+                column(CsvColumn::Index(0), CsvField::Site),
+                column(CsvColumn::Index(1), CsvField::Username),
+                column(CsvColumn::Index(2), CsvField::Password),
+            ],
+        };
+
+        let parsed = parse_mapped_csv(content, &mapping).unwrap();
+        assert_eq!(parsed.passwords[0].site, "Société");
+        assert!(parsed.warnings.is_empty());
+
+        mapping.encoding = "klingon".to_string();
+        assert!(parse_mapped_csv(content, &mapping).is_err());
+
+        mapping.encoding = "utf-8".to_string();
+        mapping.columns.pop();
+        assert!(parse_mapped_csv(content, &mapping).unwrap_err().contains("mot de passe"));
+
+        mapping.columns.push(column(CsvColumn::Name("Secret".to_string()), CsvField::Password));
+        assert!(parse_mapped_csv(content, &mapping).unwrap_err().contains("Colonne introuvable"));
+
+        mapping.delimiter = '→';
+        assert!(parse_mapped_csv(content, &mapping).is_err());
+    }
+}
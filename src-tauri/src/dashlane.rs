@@ -0,0 +1,166 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::import_export::{extract_domain_from_url, ImportedPassword, ParsedImport};
+
+// Import de l'export JSON Dashlane : un objet dont chaque clé regroupe une catégorie
+// d'éléments (AUTHENTIFIANT pour les identifiants, SECURENOTE, PAYMENTMEANS_CREDITCARD...)
+const CATEGORY_CREDENTIAL: &str = "AUTHENTIFIANT";
+
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct Credential {
+    title: Option<String>,
+    domain: Option<String>,
+    email: Option<String>,
+    login: Option<String>,
+    secondary_login: Option<String>,
+    password: Option<String>,
+    note: Option<String>,
+}
+
+pub fn parse_dashlane_json(content: &str) -> Result<ParsedImport, String> {
+    let export: BTreeMap<String, Value> = serde_json::from_str(content)
+        .map_err(|_| "Le fichier n'est pas un export JSON Dashlane".to_string())?;
+    if !export.contains_key(CATEGORY_CREDENTIAL) {
+        return Err("Export JSON Dashlane sans identifiants (AUTHENTIFIANT)".to_string());
+    }
+
+    let mut parsed = ParsedImport::default();
+    for (category, items) in &export {
+        let items = items.as_array().map(Vec::as_slice).unwrap_or(&[]);
+        if category != CATEGORY_CREDENTIAL {
+            if !items.is_empty() {
+                parsed.warnings.push(format!(
+                    "{} élément(s) de type « {} » non importé(s)",
+                    items.len(),
+                    category_name(category)
+                ));
+            }
+            continue;
+        }
+
+        for item in items {
+            let credential: Credential = serde_json::from_value(item.clone()).unwrap_or_default();
+            push_credential(&mut parsed, credential);
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn push_credential(parsed: &mut ParsedImport, credential: Credential) {
+    let url = non_empty(credential.domain).map(|domain| {
+        if domain.contains("://") {
+            domain
+        } else {
+            format!("https://{}", domain)
+        }
+    });
+    let site = non_empty(credential.title)
+        .or_else(|| url.as_deref().and_then(extract_domain_from_url))
+        .unwrap_or_default();
+
+    let Some(password) = non_empty(credential.password) else {
+        parsed.warnings.push(format!("{}: entrée sans mot de passe ignorée", site));
+        return;
+    };
+
+    // L'identifiant Dashlane est le login, à défaut l'adresse e-mail
+    let email = non_empty(credential.email);
+    let username = non_empty(credential.login).or_else(|| email.clone()).unwrap_or_default();
+    if let Some(secondary) = non_empty(credential.secondary_login) {
+        if secondary != username {
+            parsed.warnings.push(format!("{}: identifiant secondaire non importé", site));
+        }
+    }
+    if email.as_ref().is_some_and(|email| *email != username) {
+        parsed.warnings.push(format!("{}: champ « email » non importé", site));
+    }
+
+    parsed.passwords.push(ImportedPassword {
+        site,
+        username,
+        password,
+        notes: non_empty(credential.note),
+        url,
+        folder: None,
+        ..ImportedPassword::default()
+    });
+}
+
+fn category_name(category: &str) -> &str {
+    match category {
+        "SECURENOTE" => "Note sécurisée",
+        "PAYMENTMEANS_CREDITCARD" => "Carte bancaire",
+        "PAYMENTMEAN_PAYPAL" => "Compte PayPal",
+        "BANKSTATEMENT" => "Compte bancaire",
+        "IDENTITY" => "Identité",
+        "ADDRESS" => "Adresse",
+        "EMAIL" => "E-mail",
+        "PHONE" => "Téléphone",
+        "COMPANY" => "Entreprise",
+        "WEBSITE" => "Site web",
+        "IDCARD" => "Carte d'identité",
+        "PASSPORT" => "Passeport",
+        "DRIVERLICENCE" => "Permis de conduire",
+        "SOCIALSECURITYSTATEMENT" => "Numéro de sécurité sociale",
+        "FISCALSTATEMENT" => "Numéro fiscal",
+        other => other,
+    }
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|s| !s.trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dashlane_json() {
+        let json_content = r#"{
+            "AUTHENTIFIANT": [
+                {
+                    "domain": "example.com",
+                    "email": "user@test.com",
+                    "login": "",
+                    "note": "Test notes",
+                    "password": "password123",
+                    "secondaryLogin": "",
+                    "title": "Example Site"
+                },
+                {
+                    "domain": "github.com",
+                    "email": "user@test.com",
+                    "login": "octocat",
+                    "note": "",
+                    "password": "",
+                    "secondaryLogin": "",
+                    "title": ""
+                }
+            ],
+            "SECURENOTE": [{"title": "Note", "content": "secret"}],
+            "ADDRESS": []
+        }"#;
+
+        let parsed = parse_dashlane_json(json_content).unwrap();
+        assert_eq!(parsed.passwords.len(), 1);
+
+        let password = &parsed.passwords[0];
+        assert_eq!(password.site, "Example Site");
+        assert_eq!(password.username, "user@test.com");
+        assert_eq!(password.password, "password123");
+        assert_eq!(password.url.as_deref(), Some("https://example.com"));
+        assert_eq!(password.notes.as_deref(), Some("Test notes"));
+
+        assert_eq!(parsed.warnings, vec![
+            "github.com: entrée sans mot de passe ignorée".to_string(),
+            "1 élément(s) de type « Note sécurisée » non importé(s)".to_string(),
+        ]);
+
+        assert!(parse_dashlane_json(r#"{"items": []}"#).is_err());
+    }
+}
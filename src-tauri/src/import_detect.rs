@@ -0,0 +1,241 @@
+use csv::ReaderBuilder;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::io::Cursor;
+
+use crate::kdbx;
+use crate::onepassword;
+use crate::pass_store;
+use crate::protonpass;
+use crate::vwx;
+
+// Détection de la source d'un fichier d'import à partir de son contenu : signatures des
+// formats binaires, forme des documents JSON/XML et jeu de colonnes des en-têtes CSV
+
+// Source demandée par l'appelant pour déléguer le choix à la détection
+pub const AUTO_SOURCE: &str = "auto";
+
+// Les candidats sous ce seuil ne sont pas proposés
+const MIN_CONFIDENCE: f32 = 0.4;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceCandidate {
+    pub source: String,
+    pub confidence: f32,
+}
+
+// En-têtes CSV exportés par chaque gestionnaire, en minuscules
+const CSV_FINGERPRINTS: &[(&str, &[&str])] = &[
+    ("bitwarden", &["folder", "favorite", "type", "name", "notes", "fields", "reprompt", "login_uri", "login_username", "login_password", "login_totp"]),
+    ("lastpass", &["url", "username", "password", "totp", "extra", "name", "grouping", "fav"]),
+    ("1password", &["title", "website", "username", "password", "otpauth", "favorite", "archived", "tags", "notes"]),
+    // Edge, Opera, Brave et Vivaldi exportent les mêmes colonnes et sont reconnus comme Chrome
+    ("chrome", &["name", "url", "username", "password", "note"]),
+    ("firefox", &["url", "username", "password", "httprealm", "formactionorigin", "guid", "timecreated", "timelastused", "timepasswordchanged"]),
+    ("safari", &["title", "url", "username", "password", "notes", "otpauth"]),
+    ("keepass", &["group", "title", "username", "password", "url", "notes", "totp", "icon", "last modified", "created"]),
+    ("keepass", &["account", "login name", "password", "web site", "comments"]),
+    ("dashlane", &["username", "username2", "username3", "title", "password", "note", "url", "category", "otpsecret"]),
+    ("nordpass", &["name", "url", "additional_urls", "username", "password", "note", "cardholdername", "cardnumber", "cvc", "pin", "expirydate", "zipcode", "folder", "full_name", "phone_number", "email", "address1", "address2", "city", "country", "state", "type", "custom_fields"]),
+    ("protonpass", &["type", "name", "url", "email", "username", "password", "note", "totp", "createtime", "modifytime", "vault"]),
+];
+
+// Colonnes reconnues par le parseur CSV générique
+const GENERIC_PASSWORD_COLUMNS: &[&str] = &["password", "pass", "pwd"];
+
+// Candidats classés par confiance décroissante ; vide si aucun format n'est reconnu
+pub fn detect_import_source(content: &[u8], file_extension: &str) -> Vec<SourceCandidate> {
+    let mut candidates = if content.starts_with(&kdbx::SIGNATURE) {
+        vec![candidate("keepass", 1.0)]
+    } else if content.starts_with(b"PK\x03\x04") {
+        detect_zip(content)
+    } else {
+        match std::str::from_utf8(content) {
+            Ok(text) => detect_text(text.trim_start_matches('\u{feff}'), &file_extension.to_lowercase()),
+            Err(_) => Vec::new(),
+        }
+    };
+
+    candidates.retain(|c| c.confidence >= MIN_CONFIDENCE);
+    candidates.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    // Une même source n'apparaît qu'une fois, avec sa meilleure confiance
+    let mut seen = HashSet::new();
+    candidates.retain(|c| seen.insert(c.source.clone()));
+    candidates
+}
+
+fn detect_zip(content: &[u8]) -> Vec<SourceCandidate> {
+    let Ok(archive) = zip::ZipArchive::new(Cursor::new(content)) else {
+        return Vec::new();
+    };
+    let names: HashSet<&str> = archive.file_names().collect();
+
+    let mut candidates = Vec::new();
+    if names.contains(onepassword::EXPORT_DATA_PATH) {
+        candidates.push(candidate("1password", 0.95));
+    }
+    if names.contains(protonpass::EXPORT_DATA_PATH) {
+        candidates.push(candidate("protonpass", 0.95));
+    }
+    candidates
+}
+
+fn detect_text(text: &str, file_extension: &str) -> Vec<SourceCandidate> {
+    let trimmed = text.trim_start();
+    if trimmed.contains(onepassword::PIF_SEPARATOR) {
+        return vec![candidate("1password", 0.95)];
+    }
+    if trimmed.starts_with('<') {
+        return detect_xml(trimmed);
+    }
+    if trimmed.lines().next().and_then(pass_store::dump_marker).is_some() {
+        return vec![candidate("pass", 0.9)];
+    }
+    if trimmed.starts_with('{') || trimmed.starts_with('[') {
+        if let Ok(json) = serde_json::from_str::<Value>(trimmed) {
+            return detect_json(&json);
+        }
+        if file_extension == "json" {
+            return Vec::new();
+        }
+    }
+    detect_csv(text)
+}
+
+fn detect_xml(text: &str) -> Vec<SourceCandidate> {
+    // L'élément racine suffit : le prologue et les commentaires éventuels le précèdent
+    let Ok(document) = roxmltree::Document::parse(text) else {
+        return Vec::new();
+    };
+    match document.root_element().tag_name().name() {
+        "KeePassFile" => vec![candidate("keepass", 0.95)],
+        _ => Vec::new(),
+    }
+}
+
+fn detect_json(json: &Value) -> Vec<SourceCandidate> {
+    let mut candidates = Vec::new();
+    let has = |key: &str| json.get(key).is_some();
+
+    if json.get("header").and_then(|h| h.get("format")).and_then(Value::as_str) == Some(vwx::FORMAT) {
+        candidates.push(candidate("vaultword", 1.0));
+    }
+    // Export JSON VaultWord non chiffré : tableau d'entrées site/identifiant/mot de passe
+    if let Some(first) = json.as_array().and_then(|entries| entries.first()) {
+        if first.get("site").is_some() && first.get("password").is_some() {
+            candidates.push(candidate("vaultword", 0.9));
+        }
+    }
+    if json.get("items").is_some_and(Value::is_array) {
+        let confidence = if has("folders") || has("collections") || has("encrypted") { 0.95 } else { 0.6 };
+        candidates.push(candidate("bitwarden", confidence));
+    }
+    if json.get("vaults").is_some_and(Value::is_object) {
+        let confidence = if has("userId") || has("encrypted") { 0.95 } else { 0.6 };
+        candidates.push(candidate("protonpass", confidence));
+    }
+    if json.get("records").is_some_and(Value::is_array) {
+        let confidence = if has("shared_folders") { 0.95 } else { 0.8 };
+        candidates.push(candidate("keeper", confidence));
+    }
+    if has("AUTHENTIFIANT") {
+        candidates.push(candidate("dashlane", 0.95));
+    }
+    candidates
+}
+
+fn detect_csv(text: &str) -> Vec<SourceCandidate> {
+    let mut reader = ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    let Some(Ok(first_row)) = reader.records().next() else {
+        return Vec::new();
+    };
+    let headers: HashSet<String> = first_row.iter().map(|h| h.trim().to_lowercase()).collect();
+
+    // Similarité de Jaccard entre les en-têtes du fichier et ceux de chaque gestionnaire
+    let mut candidates: Vec<SourceCandidate> = CSV_FINGERPRINTS
+        .iter()
+        .map(|(source, fingerprint)| {
+            let shared = fingerprint.iter().filter(|column| headers.contains(**column)).count();
+            let union = headers.len() + fingerprint.len() - shared;
+            candidate(source, shared as f32 / union as f32)
+        })
+        .collect();
+
+    let has_password_column = GENERIC_PASSWORD_COLUMNS.iter().any(|column| headers.contains(*column));
+    if has_password_column {
+        if candidates.iter().all(|c| c.confidence < MIN_CONFIDENCE) {
+            candidates.push(candidate("generic", MIN_CONFIDENCE));
+        }
+    } else if first_row.len() >= 6 {
+        // Les exports Keeper n'ont pas d'en-tête : dossier, titre, identifiant, mot de passe, URL, notes
+        let url = first_row.get(4).unwrap_or("").trim();
+        let confidence = if url.is_empty() || url.contains("://") { 0.5 } else { MIN_CONFIDENCE };
+        candidates.push(candidate("keeper", confidence));
+    }
+    candidates
+}
+
+fn candidate(source: &str, confidence: f32) -> SourceCandidate {
+    // Arrondi au centième pour un affichage stable
+    SourceCandidate { source: source.to_string(), confidence: (confidence * 100.0).round() / 100.0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn best(content: &str, extension: &str) -> Option<String> {
+        detect_import_source(content.as_bytes(), extension)
+            .into_iter()
+            .next()
+            .map(|c| c.source)
+    }
+
+    #[test]
+    fn test_detect_csv_sources() {
+        let cases = [
+            ("url,username,password,extra,name,grouping,fav\nhttps://example.com,user,pass,,Example,,0", "lastpass"),
+            ("name,url,username,password\nExample,https://example.com,user,pass", "chrome"),
+            ("url,username,password,httpRealm,formActionOrigin,guid,timeCreated,timeLastUsed,timePasswordChanged\n", "firefox"),
+            ("folder,favorite,type,name,notes,fields,reprompt,login_uri,login_username,login_password,login_totp\n", "bitwarden"),
+            ("Title,URL,Username,Password,Notes,OTPAuth\n", "safari"),
+            ("username,username2,username3,title,password,note,url,category,otpSecret\n", "dashlane"),
+            ("type,name,url,email,username,password,note,totp,createTime,modifyTime,vault\n", "protonpass"),
+            ("\"Group\",\"Title\",\"Username\",\"Password\",\"URL\",\"Notes\",\"TOTP\",\"Icon\",\"Last Modified\",\"Created\"\n", "keepass"),
+            ("Work,Example,user,hunter2,https://example.com,notes,\n", "keeper"),
+            ("service,login,password\nExample,user,pass", "generic"),
+        ];
+        for (content, expected) in cases {
+            assert_eq!(best(content, "csv").as_deref(), Some(expected), "{}", content);
+        }
+
+        assert!(detect_import_source(b"a,b\n1,2", "csv").is_empty());
+    }
+
+    #[test]
+    fn test_detect_structured_sources() {
+        assert_eq!(best(r#"{"encrypted": false, "folders": [], "items": []}"#, "json").as_deref(), Some("bitwarden"));
+        assert_eq!(best(r#"{"version": "1.0", "userId": "u", "vaults": {}}"#, "json").as_deref(), Some("protonpass"));
+        assert_eq!(best(r#"{"shared_folders": [], "records": []}"#, "json").as_deref(), Some("keeper"));
+        assert_eq!(best(r#"{"AUTHENTIFIANT": [], "SECURENOTE": []}"#, "json").as_deref(), Some("dashlane"));
+        assert_eq!(best(r#"{"header": {"format": "vaultword-export"}, "data": ""}"#, "vwx").as_deref(), Some("vaultword"));
+        assert_eq!(best(r#"[{"site": "Example", "username": "u", "password": "p"}]"#, "json").as_deref(), Some("vaultword"));
+        assert_eq!(best(r#"{"unknown": true}"#, "json"), None);
+
+        let xml = "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<KeePassFile><Root/></KeePassFile>";
+        assert_eq!(best(xml, "xml").as_deref(), Some("keepass"));
+        assert_eq!(best("=== Perso/github.com ===\nsecret\nlogin: alice\n", "txt").as_deref(), Some("pass"));
+
+        let pif = format!("{{\"typeName\":\"webforms.WebForm\"}}\n{}\n", onepassword::PIF_SEPARATOR);
+        assert_eq!(best(&pif, "1pif").as_deref(), Some("1password"));
+
+        let mut kdbx = kdbx::SIGNATURE.to_vec();
+        kdbx.extend_from_slice(&[0; 16]);
+        let candidates = detect_import_source(&kdbx, "kdbx");
+        assert_eq!(candidates, vec![SourceCandidate { source: "keepass".to_string(), confidence: 1.0 }]);
+    }
+}
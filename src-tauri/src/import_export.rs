@@ -1,16 +1,115 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use csv::ReaderBuilder;
-use serde_json;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+use crate::bitwarden;
+use crate::dashlane;
+use crate::import_detect::{detect_import_source, AUTO_SOURCE};
+use crate::kdbx;
+use crate::keepass_xml;
+use crate::keeper;
+use crate::onepassword;
+use crate::pass_store;
+use crate::password_strength::estimate_password_strength;
+use crate::protonpass;
+use crate::vault::{CustomField, EntryType, EntryUri, PasswordHistoryEntry, UriMatch, VaultEntry, VaultEntryInput};
+use crate::vwx;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct ImportedPassword {
     pub site: String,
     pub username: String,
     pub password: String,
     pub notes: Option<String>,
     pub url: Option<String>,
+    // Toutes les URIs, la principale (url) en premier
+    #[serde(default)]
+    pub uris: Vec<EntryUri>,
     pub folder: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub custom_fields: Vec<CustomField>,
+    #[serde(default)]
+    pub entry_type: EntryType,
+    #[serde(default)]
+    pub totp: Option<String>,
+    #[serde(default)]
+    pub favorite: bool,
+    #[serde(default)]
+    pub password_history: Vec<PasswordHistoryEntry>,
+    // Dates connues du gestionnaire d'origine, au format du coffre (« AAAA-MM-JJ HH:MM:SS », UTC)
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+    #[serde(default)]
+    pub last_used_at: Option<String>,
+    // Ligne du fichier (en-tête compris) dont provient l'entrée, pour les formats lus ligne à ligne
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_line: Option<usize>,
+}
+
+impl ImportedPassword {
+    // Aligne url et uris : les parseurs peuvent ne renseigner que l'un des deux
+    pub fn normalize_uris(&mut self) {
+        self.uris.retain(|uri| !uri.uri.trim().is_empty());
+        match self.url.as_deref().filter(|url| !url.trim().is_empty()) {
+            Some(url) => {
+                if !self.uris.iter().any(|uri| uri.uri == url) {
+                    self.uris.insert(0, EntryUri { uri: url.to_string(), match_type: None });
+                }
+            }
+            None => self.url = self.primary_uri(),
+        }
+    }
+
+    // Première URI utilisable comme adresse du site : une expression régulière n'en est pas une
+    fn primary_uri(&self) -> Option<String> {
+        self.uris
+            .iter()
+            .find(|uri| uri.match_type != Some(UriMatch::Regex))
+            .map(|uri| uri.uri.clone())
+    }
+
+    pub fn with_uris(mut self, uris: impl IntoIterator<Item = String>) -> Self {
+        self.uris.extend(uris.into_iter().map(|uri| EntryUri { uri, match_type: None }));
+        self.normalize_uris();
+        self
+    }
+}
+
+// Nature d'un problème rencontré sur une ligne du fichier importé
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportIssueKind {
+    // Ligne illisible (nombre de colonnes incohérent, encodage invalide)
+    MalformedRow,
+    // Champ obligatoire vide ou absent
+    MissingField,
+    // Élément d'un type que VaultWord n'importe pas depuis ce format
+    UnsupportedType,
+    // Doublon d'une entrée du coffre ou d'une ligne précédente, écarté par les options d'import
+    Duplicate,
+}
+
+// Ligne écartée à l'import : line est le numéro de ligne dans le fichier, en-tête compris
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportIssue {
+    pub line: usize,
+    pub column: Option<String>,
+    pub kind: ImportIssueKind,
+    pub message: String,
+}
+
+// Entrées lues dans un fichier, avec les avertissements propres au format source et les
+// lignes écartées
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ParsedImport {
+    pub passwords: Vec<ImportedPassword>,
+    pub warnings: Vec<String>,
+    #[serde(default)]
+    pub issues: Vec<ImportIssue>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -20,10 +119,12 @@ pub struct ImportResult {
     pub skipped: usize,
     pub errors: Vec<String>,
     pub warnings: Vec<String>,
+    pub issues: Vec<ImportIssue>,
     pub duplicates: Vec<ImportedPassword>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ImportOptions {
     pub skip_duplicates: bool,
     pub update_existing: bool,
@@ -31,229 +132,747 @@ pub struct ImportOptions {
     pub import_notes: bool,
 }
 
+// Opérations à appliquer au coffre pour un import, avec le résultat à renvoyer au frontend
+pub struct ImportPlan {
+    pub inserts: Vec<VaultEntryInput>,
+    pub updates: Vec<(i64, VaultEntryInput)>,
+    pub result: ImportResult,
+}
+
+impl From<&ImportedPassword> for VaultEntryInput {
+    fn from(password: &ImportedPassword) -> Self {
+        VaultEntryInput {
+            site: password.site.clone(),
+            username: password.username.clone(),
+            password: password.password.clone(),
+            url: password.url.clone(),
+            uris: password.uris.clone(),
+            notes: password.notes.clone(),
+            folder: password.folder.clone(),
+            tags: password.tags.clone(),
+            custom_fields: password.custom_fields.clone(),
+            entry_type: password.entry_type,
+            totp: password.totp.clone(),
+            password_history: password.password_history.clone(),
+            favorite: password.favorite,
+            created_at: password.created_at.clone(),
+            updated_at: password.updated_at.clone(),
+            last_used_at: password.last_used_at.clone(),
+        }
+    }
+}
+
+// Point d'entrée commun : choisit le parseur selon l'extension et la source
+pub fn parse_import_content(content: &str, source: &str, file_extension: &str) -> Result<ParsedImport, String> {
+    let passwords = match file_extension.to_lowercase().as_str() {
+        "csv" => return parse_csv_content(content, source),
+        "json" => match source {
+            "bitwarden" => return bitwarden::parse_bitwarden_json(content, None),
+            "dashlane" => return dashlane::parse_dashlane_json(content),
+            "protonpass" => return protonpass::parse_protonpass_json(content),
+            "keeper" => return keeper::parse_keeper_json(content),
+            "vaultword" => serde_json::from_str(content)
+                .map_err(|e| format!("Export JSON VaultWord invalide: {}", e))?,
+            _ => return Err("Format JSON non supporté pour cette source".to_string()),
+        },
+        "xml" => match source {
+            "keepass" => return keepass_xml::parse_keepass_xml(content, None),
+            _ => return Err("Format XML non supporté pour cette source".to_string()),
+        },
+        "1pif" => return onepassword::parse_1pif(content),
+        "txt" => match source {
+            "pass" => return pass_store::parse_pass_dump(content),
+            _ => return Err("Format texte non supporté pour cette source".to_string()),
+        },
+        _ => return Err(format!("Extension de fichier non supportée: {}", file_extension))
+    };
+    Ok(ParsedImport { passwords, ..ParsedImport::default() })
+}
+
+// Point d'entrée de tous les imports : les fichiers binaires ou protégés (bases KeePass,
+// exports .vwx) sont traités ici, les formats texte délégués à parse_import_content
+pub fn parse_import_bytes(
+    content: &[u8],
+    source: &str,
+    file_extension: &str,
+    file_password: Option<&str>,
+    key_file: Option<&[u8]>,
+) -> Result<ParsedImport, String> {
+    // « auto » retient le candidat le plus probable plutôt que le parseur générique
+    let detected;
+    let source = if source == AUTO_SOURCE {
+        detected = detect_import_source(content, file_extension)
+            .into_iter()
+            .next()
+            .ok_or("Impossible de reconnaître la source du fichier")?;
+        detected.source.as_str()
+    } else {
+        source
+    };
+
+    let mut parsed = match file_extension.to_lowercase().as_str() {
+        "kdbx" => kdbx::parse_kdbx(content, file_password, key_file)?,
+        "1pux" => onepassword::parse_1pux(content)?,
+        "zip" if source == "protonpass" => protonpass::parse_protonpass_zip(content)?,
+        extension => {
+            let text = std::str::from_utf8(content)
+                .map_err(|_| "Le fichier n'est pas un texte UTF-8 valide".to_string())?;
+            if extension == "vwx" {
+                let file_password = file_password.ok_or("Mot de passe requis pour ouvrir l'export chiffré")?;
+                ParsedImport { passwords: vwx::decrypt_export(text, file_password)?, ..ParsedImport::default() }
+            } else if extension == "json" && source == "bitwarden" {
+                // Les exports Bitwarden protégés par mot de passe se déchiffrent en mémoire
+                bitwarden::parse_bitwarden_json(text, file_password)?
+            } else {
+                parse_import_content(text, source, file_extension)?
+            }
+        }
+    };
+
+    parsed.passwords.iter_mut().for_each(ImportedPassword::normalize_uris);
+    Ok(parsed)
+}
+
+// Applique les options d'import aux entrées parsées, face aux entrées déjà présentes dans le coffre
+pub fn plan_import(parsed: ParsedImport, existing: &[VaultEntry], options: &ImportOptions) -> ImportPlan {
+    let mut plan = ImportPlan {
+        inserts: Vec::new(),
+        updates: Vec::new(),
+        result: ImportResult {
+            success: true,
+            imported: 0,
+            skipped: 0,
+            errors: Vec::new(),
+            warnings: parsed.warnings,
+            issues: parsed.issues,
+            duplicates: Vec::new(),
+        },
+    };
+
+    let existing_ids: HashMap<(String, String), &VaultEntry> = existing
+        .iter()
+        .map(|entry| (duplicate_key(&entry.site, &entry.username), entry))
+        .collect();
+    let mut seen_in_file: HashMap<(String, String), usize> = HashMap::new();
+
+    for (index, mut password) in parsed.passwords.into_iter().enumerate() {
+        // Ligne du fichier quand le format la fournit, rang de l'entrée sinon
+        let line = password.source_line.unwrap_or(index + 1);
+
+        // Seuls les identifiants exigent un mot de passe : notes, cartes et identités s'en passent
+        let login = password.entry_type == EntryType::Login;
+        if password.site.trim().is_empty() {
+            plan.result.issues.push(SkippedRow::missing("site").at(line));
+            continue;
+        }
+        if login && password.password.is_empty() {
+            plan.result.issues.push(SkippedRow::missing("password").at(line));
+            continue;
+        }
+        if login && password.username.trim().is_empty() {
+            plan.result.warnings.push(format!("Ligne {}: Nom d'utilisateur manquant", line));
+        }
+        if let Some(warning) = login.then(|| weak_password_warning(&password, line)).flatten() {
+            plan.result.warnings.push(warning);
+        }
+
+        password.normalize_uris();
+        if options.validate_urls {
+            // Les expressions régulières ne sont pas des URLs et sont conservées telles quelles
+            let warnings = &mut plan.result.warnings;
+            password.uris.retain(|uri| {
+                let valid = uri.match_type == Some(UriMatch::Regex) || url::Url::parse(&uri.uri).is_ok();
+                if !valid {
+                    warnings.push(format!("Ligne {}: URL invalide ignorée: {}", line, uri.uri));
+                }
+                valid
+            });
+            password.url = password.primary_uri();
+        }
+        if !options.import_notes {
+            password.notes = None;
+        }
+
+        let key = duplicate_key(&password.site, &password.username);
+
+        if let Some(entry) = existing_ids.get(&key) {
+            if options.update_existing {
+                // Les tags, champs personnalisés, TOTP, historique et favoris propres au coffre
+                // sont conservés quand le fichier n'en apporte pas
+                let mut input = VaultEntryInput::from(&password);
+                let mut tags = entry.tags.clone();
+                tags.extend(input.tags.into_iter().filter(|tag| !entry.tags.contains(tag)));
+                input.tags = tags;
+                if input.custom_fields.is_empty() {
+                    input.custom_fields = entry.custom_fields.clone();
+                }
+                if input.totp.is_none() {
+                    input.totp = entry.totp.clone();
+                }
+                if input.password_history.is_empty() {
+                    input.password_history = entry.password_history.clone();
+                }
+                input.favorite |= entry.favorite;
+                plan.updates.push((entry.id, input));
+                plan.result.imported += 1;
+            } else if options.skip_duplicates {
+                let message = format!("Doublon d'une entrée existante ({}), ligne ignorée", password.site);
+                plan.result.issues.push(SkippedRow::duplicate(message).at(line));
+                plan.result.duplicates.push(password);
+            } else {
+                plan.result.warnings.push(format!("Ligne {}: Doublon d'une entrée existante ({})", line, password.site));
+                plan.inserts.push(VaultEntryInput::from(&password));
+                plan.result.imported += 1;
+            }
+            continue;
+        }
+
+        if let Some(first_line) = seen_in_file.get(&key) {
+            if options.skip_duplicates {
+                let message = format!("Doublon de la ligne {}, ligne ignorée", first_line);
+                plan.result.issues.push(SkippedRow::duplicate(message).at(line));
+                plan.result.duplicates.push(password);
+                continue;
+            }
+            plan.result.warnings.push(format!("Ligne {}: Doublon de la ligne {}", line, first_line));
+        } else {
+            seen_in_file.insert(key, line);
+        }
+
+        plan.inserts.push(VaultEntryInput::from(&password));
+        plan.result.imported += 1;
+    }
+
+    // Les lignes écartées à la lecture du fichier comme à l'import comptent parmi les entrées ignorées
+    plan.result.skipped = plan.result.issues.len();
+    plan
+}
+
+fn duplicate_key(site: &str, username: &str) -> (String, String) {
+    (site.trim().to_lowercase(), username.trim().to_lowercase())
+}
+
 // Parseur générique pour CSV
-pub fn parse_csv_content(content: &str, source: &str) -> Result<Vec<ImportedPassword>, String> {
-    let mut passwords = Vec::new();
+pub fn parse_csv_content(content: &str, source: &str) -> Result<ParsedImport, String> {
+    parse_csv_reader(content.as_bytes(), source, |_, _| Ok(()))
+}
+
+// Lecture au fil de l'eau : on_record reçoit le nombre de lignes et d'octets lus après chaque
+// ligne, et interrompt la lecture en renvoyant une erreur. Les lignes mal formées ou
+// incomplètes sont écartées et signalées dans issues, sans arrêter l'import
+pub fn parse_csv_reader<R: std::io::Read>(
+    input: R,
+    source: &str,
+    mut on_record: impl FnMut(usize, u64) -> Result<(), String>,
+) -> Result<ParsedImport, String> {
+    let mut parsed = ParsedImport::default();
+    // Les exports CSV Keeper n'ont pas de ligne d'en-tête et un nombre de colonnes variable
+    let keeper = source == "keeper";
     let mut reader = ReaderBuilder::new()
-        .has_headers(true)
-        .from_reader(content.as_bytes());
+        .has_headers(!keeper)
+        .flexible(keeper)
+        .from_reader(input);
 
     // Récupérer les headers pour identifier le format
     let headers = reader.headers()
         .map_err(|e| format!("Erreur lecture headers: {}", e))?
         .clone();
 
-    for result in reader.records() {
-        let record = result.map_err(|e| format!("Erreur lecture ligne: {}", e))?;
-        
-        match source {
-            "lastpass" => {
-                if let Some(password) = parse_lastpass_record(&headers, &record) {
-                    passwords.push(password);
-                }
-            },
-            "chrome" => {
-                if let Some(password) = parse_chrome_record(&headers, &record) {
-                    passwords.push(password);
-                }
-            },
-            "firefox" => {
-                if let Some(password) = parse_firefox_record(&headers, &record) {
-                    passwords.push(password);
-                }
-            },
-            "bitwarden" => {
-                if let Some(password) = parse_bitwarden_csv_record(&headers, &record) {
-                    passwords.push(password);
-                }
-            },
-            _ => {
-                // Format générique CSV
-                if let Some(password) = parse_generic_csv_record(&headers, &record) {
-                    passwords.push(password);
-                }
+    let mut record = csv::StringRecord::new();
+    let mut rows = 0;
+    loop {
+        let result = reader.read_record(&mut record);
+        match &result {
+            Ok(false) => break,
+            // Seule une erreur d'entrée/sortie empêche de lire la suite du fichier
+            Err(e) if matches!(e.kind(), csv::ErrorKind::Io(_)) => {
+                return Err(format!("Erreur lecture ligne: {}", e));
             }
+            _ => {}
+        }
+        rows += 1;
+        on_record(rows, reader.position().byte())?;
+
+        let position = match &result {
+            Ok(_) => record.position(),
+            Err(e) => e.position(),
+        };
+        // Numéro de ligne du début de l'enregistrement, un champ entre guillemets pouvant
+        // s'étendre sur plusieurs lignes
+        let line = position.map_or(rows + usize::from(!keeper), |p| p.line() as usize);
+
+        let row = match result {
+            Ok(_) => parse_csv_record(source, &headers, &record),
+            Err(e) => Err(SkippedRow::malformed(&e)),
+        };
+        match row {
+            Ok(password) => parsed.passwords.push(ImportedPassword { source_line: Some(line), ..password }),
+            Err(skipped) => parsed.issues.push(skipped.at(line)),
+        }
+    }
+
+    Ok(parsed)
+}
+
+// Raison pour laquelle une ligne est écartée, avant d'y associer son numéro
+struct SkippedRow {
+    kind: ImportIssueKind,
+    column: Option<String>,
+    message: String,
+}
+
+impl SkippedRow {
+    fn malformed(error: &csv::Error) -> Self {
+        let message = match error.kind() {
+            csv::ErrorKind::UnequalLengths { expected_len, len, .. } => {
+                format!("{} colonne(s) au lieu de {}, ligne ignorée", len, expected_len)
+            }
+            csv::ErrorKind::Utf8 { .. } => "Texte UTF-8 invalide, ligne ignorée".to_string(),
+            _ => format!("Ligne illisible ({}), ligne ignorée", error),
+        };
+        SkippedRow { kind: ImportIssueKind::MalformedRow, column: None, message }
+    }
+
+    fn missing(column: &str) -> Self {
+        SkippedRow {
+            kind: ImportIssueKind::MissingField,
+            column: Some(column.to_string()),
+            message: format!("Champ « {} » manquant, ligne ignorée", column),
+        }
+    }
+
+    fn unsupported_type(item_type: &str) -> Self {
+        SkippedRow {
+            kind: ImportIssueKind::UnsupportedType,
+            column: Some("type".to_string()),
+            message: format!("Élément de type « {} » non importé", item_type),
         }
     }
 
-    Ok(passwords)
+    fn duplicate(message: String) -> Self {
+        SkippedRow { kind: ImportIssueKind::Duplicate, column: None, message }
+    }
+
+    fn at(self, line: usize) -> ImportIssue {
+        ImportIssue { line, column: self.column, kind: self.kind, message: self.message }
+    }
+}
+
+fn parse_csv_record(
+    source: &str,
+    headers: &csv::StringRecord,
+    record: &csv::StringRecord,
+) -> Result<ImportedPassword, SkippedRow> {
+    match source {
+        "lastpass" => parse_lastpass_record(headers, record),
+        // Edge, Opera, Brave et Vivaldi reprennent l'export CSV de Chromium
+        "chrome" | "edge" | "opera" | "brave" | "vivaldi" => parse_chrome_record(headers, record),
+        "safari" => parse_apple_record(headers, record),
+        "firefox" => parse_firefox_record(headers, record),
+        "bitwarden" => parse_bitwarden_csv_record(headers, record),
+        "dashlane" => parse_dashlane_record(headers, record),
+        "nordpass" => parse_nordpass_record(headers, record),
+        "protonpass" => parse_protonpass_record(headers, record),
+        "keeper" => parse_keeper_record(record),
+        "keepass" => parse_keepass_record(headers, record),
+        // Format générique CSV
+        _ => parse_generic_csv_record(headers, record),
+    }
 }
 
 // Parseur spécifique LastPass
-fn parse_lastpass_record(headers: &csv::StringRecord, record: &csv::StringRecord) -> Option<ImportedPassword> {
+fn parse_lastpass_record(headers: &csv::StringRecord, record: &csv::StringRecord) -> Result<ImportedPassword, SkippedRow> {
     let header_map: HashMap<String, usize> = headers
         .iter()
         .enumerate()
         .map(|(i, h)| (h.to_lowercase(), i))
         .collect();
 
-    let site = get_field_value(&header_map, record, &["name", "title", "site"])?;
-    let username = get_field_value(&header_map, record, &["username", "login", "email"])?;
-    let password = get_field_value(&header_map, record, &["password", "pass"])?;
+    let site = required_field_value(&header_map, record, &["name", "title", "site"])?;
+    let username = required_field_value(&header_map, record, &["username", "login", "email"])?;
+    let password = required_field_value(&header_map, record, &["password", "pass"])?;
     let url = get_field_value(&header_map, record, &["url", "website", "link"]);
     let notes = get_field_value(&header_map, record, &["notes", "note", "comment"]);
     let folder = get_field_value(&header_map, record, &["folder", "group", "category"]);
 
-    Some(ImportedPassword {
+    Ok(ImportedPassword {
         site,
         username,
         password,
         url,
         notes,
         folder,
+        ..ImportedPassword::default()
     })
 }
 
-// Parseur spécifique Chrome
-fn parse_chrome_record(headers: &csv::StringRecord, record: &csv::StringRecord) -> Option<ImportedPassword> {
+// Parseur spécifique Chrome et navigateurs Chromium (colonne note ajoutée depuis Chrome 118)
+fn parse_chrome_record(headers: &csv::StringRecord, record: &csv::StringRecord) -> Result<ImportedPassword, SkippedRow> {
     let header_map: HashMap<String, usize> = headers
         .iter()
         .enumerate()
         .map(|(i, h)| (h.to_lowercase(), i))
         .collect();
 
-    let site = get_field_value(&header_map, record, &["name", "title", "url"])?;
-    let username = get_field_value(&header_map, record, &["username", "login"])?;
-    let password = get_field_value(&header_map, record, &["password"])?;
+    let site = required_field_value(&header_map, record, &["name", "title", "url"])?;
+    let username = required_field_value(&header_map, record, &["username", "login"])?;
+    let password = required_field_value(&header_map, record, &["password"])?;
     let url = get_field_value(&header_map, record, &["url", "website"]);
 
-    Some(ImportedPassword {
+    Ok(ImportedPassword {
         site: extract_domain_from_url(&site).unwrap_or(site),
         username,
         password,
         url,
-        notes: None,
+        notes: get_field_value(&header_map, record, &["note"]),
         folder: None,
+        ..ImportedPassword::default()
+    })
+}
+
+// Parseur spécifique Safari / app Mots de passe d'Apple : Title, URL, Username, Password,
+// Notes, OTPAuth (URI otpauth:// du code de vérification)
+fn parse_apple_record(headers: &csv::StringRecord, record: &csv::StringRecord) -> Result<ImportedPassword, SkippedRow> {
+    let header_map: HashMap<String, usize> = headers
+        .iter()
+        .enumerate()
+        .map(|(i, h)| (h.to_lowercase(), i))
+        .collect();
+
+    let url = get_field_value(&header_map, record, &["url"]);
+    let site = get_field_value(&header_map, record, &["title"])
+        .or_else(|| url.as_deref().and_then(extract_domain_from_url))
+        .ok_or_else(|| SkippedRow::missing("title"))?;
+    let username = required_field_value(&header_map, record, &["username"])?;
+    let password = required_field_value(&header_map, record, &["password"])?;
+
+    Ok(ImportedPassword {
+        site,
+        username,
+        password,
+        url,
+        notes: get_field_value(&header_map, record, &["notes"]),
+        totp: get_field_value(&header_map, record, &["otpauth"]),
+        ..ImportedPassword::default()
     })
 }
 
-// Parseur spécifique Firefox
-fn parse_firefox_record(headers: &csv::StringRecord, record: &csv::StringRecord) -> Option<ImportedPassword> {
+// Parseur spécifique Firefox : le realm HTTP et le GUID deviennent des champs personnalisés,
+// l'origine du formulaire une URI supplémentaire et les horodatages les dates de l'entrée
+fn parse_firefox_record(headers: &csv::StringRecord, record: &csv::StringRecord) -> Result<ImportedPassword, SkippedRow> {
     let header_map: HashMap<String, usize> = headers
         .iter()
         .enumerate()
         .map(|(i, h)| (h.to_lowercase(), i))
         .collect();
 
-    let url = get_field_value(&header_map, record, &["url", "hostname"])?;
-    let username = get_field_value(&header_map, record, &["username", "login"])?;
-    let password = get_field_value(&header_map, record, &["password"])?;
+    let url = required_field_value(&header_map, record, &["url", "hostname"])?;
+    let username = required_field_value(&header_map, record, &["username", "login"])?;
+    let password = required_field_value(&header_map, record, &["password"])?;
     let site = extract_domain_from_url(&url).unwrap_or(url.clone());
 
-    Some(ImportedPassword {
+    let form_origin = get_field_value(&header_map, record, &["formactionorigin"]).filter(|origin| *origin != url);
+    let custom_fields = [("httprealm", "Realm HTTP"), ("guid", "GUID Firefox")]
+        .into_iter()
+        .filter_map(|(column, name)| {
+            get_field_value(&header_map, record, &[column]).map(|value| CustomField { name: name.to_string(), value, hidden: false })
+        })
+        .collect();
+    // Firefox exporte des horodatages Unix en millisecondes
+    let date = |column: &str| {
+        get_field_value(&header_map, record, &[column])
+            .and_then(|value| value.parse::<i64>().ok())
+            .and_then(timestamp_from_unix_millis)
+    };
+
+    Ok(ImportedPassword {
         site,
         username,
         password,
         url: Some(url),
-        notes: None,
-        folder: None,
-    })
+        custom_fields,
+        created_at: date("timecreated"),
+        updated_at: date("timepasswordchanged"),
+        last_used_at: date("timelastused"),
+        ..ImportedPassword::default()
+    }.with_uris(form_origin))
 }
 
 // Parseur spécifique Bitwarden CSV
-fn parse_bitwarden_csv_record(headers: &csv::StringRecord, record: &csv::StringRecord) -> Option<ImportedPassword> {
+fn parse_bitwarden_csv_record(headers: &csv::StringRecord, record: &csv::StringRecord) -> Result<ImportedPassword, SkippedRow> {
     let header_map: HashMap<String, usize> = headers
         .iter()
         .enumerate()
         .map(|(i, h)| (h.to_lowercase(), i))
         .collect();
 
-    let site = get_field_value(&header_map, record, &["name", "title"])?;
-    let username = get_field_value(&header_map, record, &["username", "login_username"])?;
-    let password = get_field_value(&header_map, record, &["password", "login_password"])?;
-    let url = get_field_value(&header_map, record, &["login_uri", "url"]);
+    let site = required_field_value(&header_map, record, &["name", "title"])?;
+    let username = required_field_value(&header_map, record, &["username", "login_username"])?;
+    let password = required_field_value(&header_map, record, &["password", "login_password"])?;
+    let uris = get_field_value(&header_map, record, &["login_uri", "url"]);
     let notes = get_field_value(&header_map, record, &["notes"]);
     let folder = get_field_value(&header_map, record, &["folder"]);
 
-    Some(ImportedPassword {
+    Ok(ImportedPassword {
+        site,
+        username,
+        password,
+        notes,
+        folder,
+        ..ImportedPassword::default()
+    }.with_uris(split_uri_list(uris)))
+}
+
+// Parseur spécifique Dashlane (credentials.csv de l'export)
+fn parse_dashlane_record(headers: &csv::StringRecord, record: &csv::StringRecord) -> Result<ImportedPassword, SkippedRow> {
+    let header_map: HashMap<String, usize> = headers
+        .iter()
+        .enumerate()
+        .map(|(i, h)| (h.to_lowercase(), i))
+        .collect();
+
+    let url = get_field_value(&header_map, record, &["url"]);
+    let site = get_field_value(&header_map, record, &["title"])
+        .or_else(|| url.as_deref().and_then(extract_domain_from_url))
+        .ok_or_else(|| SkippedRow::missing("title"))?;
+    let username = required_field_value(&header_map, record, &["username", "username2", "username3"])?;
+    let password = required_field_value(&header_map, record, &["password"])?;
+    let notes = get_field_value(&header_map, record, &["note"]);
+    let folder = get_field_value(&header_map, record, &["category"]);
+    // Secret base32 dans les anciens exports, URI otpauth:// dans les plus récents
+    let totp = get_field_value(&header_map, record, &["otpsecret", "otpurl"]);
+
+    Ok(ImportedPassword {
         site,
         username,
         password,
         url,
         notes,
         folder,
+        totp,
+        ..ImportedPassword::default()
     })
 }
 
+// Parseur spécifique NordPass : une ligne par élément, seuls les mots de passe sont repris
+fn parse_nordpass_record(headers: &csv::StringRecord, record: &csv::StringRecord) -> Result<ImportedPassword, SkippedRow> {
+    let header_map: HashMap<String, usize> = headers
+        .iter()
+        .enumerate()
+        .map(|(i, h)| (h.to_lowercase(), i))
+        .collect();
+
+    if let Some(item_type) = get_field_value(&header_map, record, &["type"]).filter(|t| t != "password") {
+        return Err(SkippedRow::unsupported_type(&item_type));
+    }
+
+    let url = get_field_value(&header_map, record, &["url"]);
+    let site = get_field_value(&header_map, record, &["name"])
+        .or_else(|| url.as_deref().and_then(extract_domain_from_url))
+        .ok_or_else(|| SkippedRow::missing("name"))?;
+    let username = required_field_value(&header_map, record, &["username", "email"])?;
+    let password = required_field_value(&header_map, record, &["password"])?;
+    let notes = get_field_value(&header_map, record, &["note"]);
+    let folder = get_field_value(&header_map, record, &["folder"]);
+
+    Ok(ImportedPassword {
+        site,
+        username,
+        password,
+        url,
+        notes,
+        folder,
+        ..ImportedPassword::default()
+    })
+}
+
+// Parseur spécifique Proton Pass CSV : le coffre devient le dossier
+fn parse_protonpass_record(headers: &csv::StringRecord, record: &csv::StringRecord) -> Result<ImportedPassword, SkippedRow> {
+    let header_map: HashMap<String, usize> = headers
+        .iter()
+        .enumerate()
+        .map(|(i, h)| (h.to_lowercase(), i))
+        .collect();
+
+    if let Some(item_type) = get_field_value(&header_map, record, &["type"]).filter(|t| t != "login") {
+        return Err(SkippedRow::unsupported_type(&item_type));
+    }
+
+    let uris = split_uri_list(get_field_value(&header_map, record, &["url"]));
+    let site = get_field_value(&header_map, record, &["name"])
+        .or_else(|| uris.first().and_then(|url| extract_domain_from_url(url)))
+        .ok_or_else(|| SkippedRow::missing("name"))?;
+    let username = required_field_value(&header_map, record, &["username", "email"])?;
+    let password = required_field_value(&header_map, record, &["password"])?;
+    let notes = get_field_value(&header_map, record, &["note"]);
+    let folder = get_field_value(&header_map, record, &["vault"]);
+
+    Ok(ImportedPassword {
+        site,
+        username,
+        password,
+        notes,
+        folder,
+        ..ImportedPassword::default()
+    }.with_uris(uris))
+}
+
+// Parseur spécifique Keeper, colonnes positionnelles :
+// dossier, titre, identifiant, mot de passe, URL, notes, dossier partagé, puis champs personnalisés
+fn parse_keeper_record(record: &csv::StringRecord) -> Result<ImportedPassword, SkippedRow> {
+    let field = |index: usize| {
+        record
+            .get(index)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    };
+
+    let url = field(4);
+    let site = field(1)
+        .or_else(|| url.as_deref().and_then(extract_domain_from_url))
+        .ok_or_else(|| SkippedRow::missing("colonne 2"))?;
+    let username = field(2).ok_or_else(|| SkippedRow::missing("colonne 3"))?;
+    let password = field(3).ok_or_else(|| SkippedRow::missing("colonne 4"))?;
+
+    // Les colonnes suivant le dossier partagé sont des paires nom/valeur de champs personnalisés,
+    // le code TOTP compris (« TFC:Keeper »)
+    let mut totp = None;
+    let mut custom_fields = Vec::new();
+    for index in (7..record.len()).step_by(2) {
+        let Some(value) = field(index + 1) else { continue };
+        let name = field(index).unwrap_or_default();
+        if totp.is_none() && value.starts_with("otpauth://") {
+            totp = Some(value);
+        } else {
+            custom_fields.push(CustomField { name, value, hidden: false });
+        }
+    }
+
+    Ok(ImportedPassword {
+        site,
+        username,
+        password,
+        url,
+        notes: field(5),
+        folder: keeper::keeper_folder(field(6).as_deref(), field(0).as_deref()),
+        custom_fields,
+        totp,
+        ..ImportedPassword::default()
+    })
+}
+
+// Parseur spécifique KeePassXC (Group, Title, Username, Password, URL, Notes, TOTP...) et
+// export CSV de KeePass 2 (Account, Login Name, Password, Web Site, Comments)
+fn parse_keepass_record(headers: &csv::StringRecord, record: &csv::StringRecord) -> Result<ImportedPassword, SkippedRow> {
+    let header_map: HashMap<String, usize> = headers
+        .iter()
+        .enumerate()
+        .map(|(i, h)| (h.to_lowercase(), i))
+        .collect();
+
+    let url = get_field_value(&header_map, record, &["url", "web site"]);
+    let site = get_field_value(&header_map, record, &["title", "account"])
+        .or_else(|| url.as_deref().and_then(extract_domain_from_url))
+        .ok_or_else(|| SkippedRow::missing("title"))?;
+    // KeePass n'impose pas d'identifiant ; le mot de passe n'est pas rogné
+    let username = get_field_value(&header_map, record, &["username", "login name"]).unwrap_or_default();
+    let password = header_map
+        .get("password")
+        .and_then(|&index| record.get(index))
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| SkippedRow::missing("password"))?
+        .to_string();
+
+    Ok(ImportedPassword {
+        site,
+        username,
+        password,
+        url,
+        notes: get_field_value(&header_map, record, &["notes", "comments"]),
+        folder: get_field_value(&header_map, record, &["group"]).and_then(|group| keepass_folder(&group)),
+        totp: get_field_value(&header_map, record, &["totp"]),
+        ..ImportedPassword::default()
+    })
+}
+
+// KeePassXC préfixe le chemin du groupe par le groupe racine, qui porte le nom de la base :
+// comme pour l'import XML, il n'apparaît pas dans les dossiers
+fn keepass_folder(group: &str) -> Option<String> {
+    let path: Vec<&str> = group.split('/').map(str::trim).filter(|name| !name.is_empty()).skip(1).collect();
+    (!path.is_empty()).then(|| path.join("/"))
+}
+
 // Parseur générique CSV
-fn parse_generic_csv_record(headers: &csv::StringRecord, record: &csv::StringRecord) -> Option<ImportedPassword> {
+fn parse_generic_csv_record(headers: &csv::StringRecord, record: &csv::StringRecord) -> Result<ImportedPassword, SkippedRow> {
     let header_map: HashMap<String, usize> = headers
         .iter()
         .enumerate()
         .map(|(i, h)| (h.to_lowercase(), i))
         .collect();
 
-    let site = get_field_value(&header_map, record, &["site", "name", "title", "service", "domain"])?;
-    let username = get_field_value(&header_map, record, &["username", "user", "login", "email"])?;
-    let password = get_field_value(&header_map, record, &["password", "pass", "pwd"])?;
+    let site = required_field_value(&header_map, record, &["site", "name", "title", "service", "domain"])?;
+    let username = required_field_value(&header_map, record, &["username", "user", "login", "email"])?;
+    let password = required_field_value(&header_map, record, &["password", "pass", "pwd"])?;
     let url = get_field_value(&header_map, record, &["url", "website", "link"]);
+    let uris = parse_uri_list(get_field_value(&header_map, record, &["uris"]));
     let notes = get_field_value(&header_map, record, &["notes", "note", "comment", "description"]);
 
-    Some(ImportedPassword {
+    let mut password = ImportedPassword {
         site,
         username,
         password,
         url,
+        uris,
         notes,
         folder: None,
-    })
+        ..ImportedPassword::default()
+    };
+    password.normalize_uris();
+    Ok(password)
 }
 
-// Parseur JSON pour Bitwarden
-pub fn parse_bitwarden_json(content: &str) -> Result<Vec<ImportedPassword>, String> {
-    let json_data: serde_json::Value = serde_json::from_str(content)
-        .map_err(|e| format!("JSON invalide: {}", e))?;
-
-    let mut passwords = Vec::new();
-
-    if let Some(items) = json_data.get("items").and_then(|v| v.as_array()) {
-        for item in items {
-            if let Some(login) = item.get("login") {
-                let site = item.get("name")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("Site inconnu")
-                    .to_string();
-
-                let username = login.get("username")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_string();
-
-                let password = login.get("password")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_string();
-
-                let url = login.get("uris")
-                    .and_then(|v| v.as_array())
-                    .and_then(|arr| arr.first())
-                    .and_then(|uri| uri.get("uri"))
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string());
-
-                let notes = item.get("notes")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string());
-
-                let folder = item.get("folderId")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string());
-
-                if !username.is_empty() && !password.is_empty() {
-                    passwords.push(ImportedPassword {
-                        site,
-                        username,
-                        password,
-                        url,
-                        notes,
-                        folder,
-                    });
-                }
-            }
-        }
-    }
+// Liste d'URIs d'une cellule CSV tierce (Bitwarden, Proton Pass), séparées par des virgules
+pub(crate) fn split_uri_list(value: Option<String>) -> Vec<String> {
+    value
+        .iter()
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .filter(|uri| !uri.is_empty())
+        .map(str::to_string)
+        .collect()
+}
 
-    Ok(passwords)
+// Cellule « uris » de l'export CSV : une URI par ligne, la règle de correspondance la précède
+// entre crochets (« [starts_with] https://example.com/login »), une URI sans règle est écrite telle quelle.
+// Le saut de ligne ne peut pas apparaître dans une URI, contrairement à la virgule
+pub fn format_uri_list(uris: &[EntryUri]) -> String {
+    uris.iter()
+        .map(|uri| match uri.match_type {
+            Some(match_type) => format!("[{}] {}", match_type.as_str(), uri.uri),
+            None => uri.uri.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn parse_uri_list(value: Option<String>) -> Vec<EntryUri> {
+    value
+        .iter()
+        .flat_map(|value| value.lines())
+        .map(str::trim)
+        .filter(|uri| !uri.is_empty())
+        .map(|uri| {
+            let rule = uri
+                .strip_prefix('[')
+                .and_then(|rest| rest.split_once("] "))
+                .and_then(|(name, rest)| Some((UriMatch::from_name(name)?, rest.trim_start().to_string())));
+            match rule {
+                Some((match_type, uri)) => EntryUri { uri, match_type: Some(match_type) },
+                None => EntryUri { uri: uri.to_string(), match_type: None },
+            }
+        })
+        .collect()
 }
 
 // Fonctions utilitaires
@@ -275,7 +894,53 @@ fn get_field_value(
     None
 }
 
-fn extract_domain_from_url(url: &str) -> Option<String> {
+// Valeur obligatoire : la colonne signalée est celle du fichier quand elle existe, sinon
+// le premier nom attendu
+fn required_field_value(
+    header_map: &HashMap<String, usize>,
+    record: &csv::StringRecord,
+    field_names: &[&str],
+) -> Result<String, SkippedRow> {
+    get_field_value(header_map, record, field_names).ok_or_else(|| {
+        let column = field_names
+            .iter()
+            .find(|name| header_map.contains_key(**name))
+            .unwrap_or(&field_names[0]);
+        SkippedRow::missing(column)
+    })
+}
+
+// Horodatage Unix en millisecondes au format des dates du coffre
+pub(crate) fn timestamp_from_unix_millis(millis: i64) -> Option<String> {
+    if millis <= 0 {
+        return None;
+    }
+    let seconds = millis / 1000;
+    let (days, time) = (seconds / 86_400, seconds % 86_400);
+
+    // Jours depuis 1970 vers la date civile (algorithme de Howard Hinnant)
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z % 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    Some(format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        time / 3_600,
+        time % 3_600 / 60,
+        time % 60
+    ))
+}
+
+pub(crate) fn extract_domain_from_url(url: &str) -> Option<String> {
     if let Ok(parsed_url) = url::Url::parse(url) {
         if let Some(host) = parsed_url.host_str() {
             return Some(host.to_string());
@@ -296,6 +961,25 @@ fn extract_domain_from_url(url: &str) -> Option<String> {
     None
 }
 
+// Score (0 à 4) en dessous duquel un mot de passe importé est signalé comme faible
+const WEAK_PASSWORD_SCORE: u8 = 2;
+
+// Avertissement pour un mot de passe facile à deviner, en tenant compte du site et de l'identifiant
+fn weak_password_warning(password: &ImportedPassword, line: usize) -> Option<String> {
+    if password.password.is_empty() {
+        return None;
+    }
+    let strength = estimate_password_strength(&password.password, &[&password.site, &password.username]);
+    if strength.score >= WEAK_PASSWORD_SCORE {
+        return None;
+    }
+    let reason = strength.feedback.warning.map(|w| format!("{}, ", w)).unwrap_or_default();
+    Some(format!(
+        "Ligne {}: Mot de passe faible ({}deviné en {} hors ligne)",
+        line, reason, strength.crack_times.offline_slow_hashing.display
+    ))
+}
+
 // Validation des mots de passe importés
 pub fn validate_imported_passwords(passwords: &[ImportedPassword]) -> Vec<String> {
     let mut warnings = Vec::new();
@@ -315,9 +999,7 @@ pub fn validate_imported_passwords(passwords: &[ImportedPassword]) -> Vec<String
         }
         
         // Vérifier la force du mot de passe
-        if password.password.len() < 8 {
-            warnings.push(format!("Ligne {}: Mot de passe faible (< 8 caractères)", index + 1));
-        }
+        warnings.extend(weak_password_warning(password, index + 1));
         
         // Vérifier la validité de l'URL si présente
         if let Some(url_str) = &password.url {
@@ -330,19 +1012,18 @@ pub fn validate_imported_passwords(passwords: &[ImportedPassword]) -> Vec<String
     warnings
 }
 
-// Détection des doublons
+// Détection des doublons : paires (i, j), i < j, regroupées par clé site/identifiant
 pub fn find_duplicates(passwords: &[ImportedPassword]) -> Vec<(usize, usize)> {
+    let mut groups: HashMap<(String, String), Vec<usize>> = HashMap::new();
     let mut duplicates = Vec::new();
-    
-    for (i, password1) in passwords.iter().enumerate() {
-        for (j, password2) in passwords.iter().enumerate().skip(i + 1) {
-            if password1.site.to_lowercase() == password2.site.to_lowercase() &&
-               password1.username.to_lowercase() == password2.username.to_lowercase() {
-                duplicates.push((i, j));
-            }
-        }
+
+    for (j, password) in passwords.iter().enumerate() {
+        let group = groups.entry(duplicate_key(&password.site, &password.username)).or_default();
+        duplicates.extend(group.iter().map(|&i| (i, j)));
+        group.push(j);
     }
-    
+
+    duplicates.sort_unstable();
     duplicates
 }
 
@@ -357,7 +1038,7 @@ mod tests {
         let result = parse_csv_content(csv_content, "lastpass");
         
         assert!(result.is_ok());
-        let passwords = result.unwrap();
+        let passwords = result.unwrap().passwords;
         assert_eq!(passwords.len(), 1);
         
         let password = &passwords[0];
@@ -377,7 +1058,7 @@ mod tests {
         let result = parse_csv_content(csv_content, "chrome");
         
         assert!(result.is_ok());
-        let passwords = result.unwrap();
+        let passwords = result.unwrap().passwords;
         assert_eq!(passwords.len(), 1);
         
         let password = &passwords[0];
@@ -387,33 +1068,305 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_bitwarden_json() {
-        let json_content = r#"{
-            "items": [
-                {
-                    "type": 1,
-                    "name": "Example Site",
-                    "login": {
-                        "username": "user@test.com",
-                        "password": "password123",
-                        "uris": [{"uri": "https://example.com"}]
-                    },
-                    "notes": "Test notes"
-                }
-            ]
-        }"#;
-        
-        let result = parse_bitwarden_json(json_content);
-        
+    fn test_parse_chromium_csv_keeps_notes() {
+        let csv_content = "name,url,username,password,note\nexample.com,https://example.com/login,user@test.com,password123,Compte pro";
+
+        for source in ["chrome", "edge", "opera", "brave", "vivaldi"] {
+            let passwords = parse_csv_content(csv_content, source).unwrap().passwords;
+            assert_eq!(passwords.len(), 1, "{}", source);
+            assert_eq!(passwords[0].site, "example.com");
+            assert_eq!(passwords[0].notes.as_deref(), Some("Compte pro"), "{}", source);
+        }
+    }
+
+    #[test]
+    fn test_parse_apple_passwords_csv() {
+        let csv_content = "Title,URL,Username,Password,Notes,OTPAuth\n\
+            example.com (user@test.com),https://example.com/,user@test.com,password123,Code de secours,otpauth://totp/example.com:user@test.com?secret=JBSWY3DPEHPK3PXP&issuer=example.com\n\
+            ,https://other.com/,alice,hunter22,,";
+
+        let parsed = parse_import_content(csv_content, "safari", "csv").unwrap();
+        assert_eq!(parsed.passwords.len(), 2);
+
+        let password = &parsed.passwords[0];
+        assert_eq!(password.site, "example.com (user@test.com)");
+        assert_eq!(password.username, "user@test.com");
+        assert_eq!(password.url.as_deref(), Some("https://example.com/"));
+        assert_eq!(password.notes.as_deref(), Some("Code de secours"));
+        assert!(password.totp.as_deref().is_some_and(|totp| totp.starts_with("otpauth://totp/")));
+
+        assert_eq!(parsed.passwords[1].site, "other.com");
+        assert_eq!(parsed.passwords[1].totp, None);
+    }
+
+    #[test]
+    fn test_parse_dashlane_csv() {
+        let csv_content = "username,username2,username3,title,password,note,url,category,otpSecret\nuser@test.com,,,Example Site,password123,Test notes,https://example.com,Work,JBSWY3DPEHPK3PXP\n,,,Sans identifiant,secret,,https://other.com,,";
+
+        let result = parse_csv_content(csv_content, "dashlane");
+
         assert!(result.is_ok());
-        let passwords = result.unwrap();
+        let passwords = result.unwrap().passwords;
         assert_eq!(passwords.len(), 1);
-        
+
         let password = &passwords[0];
-        assert_eq!(password.site, "Example Site"); // Le nom vient du champ "name"
+        assert_eq!(password.site, "Example Site");
         assert_eq!(password.username, "user@test.com");
         assert_eq!(password.password, "password123");
         assert_eq!(password.notes, Some("Test notes".to_string()));
+        assert_eq!(password.folder, Some("Work".to_string()));
+        assert_eq!(password.totp.as_deref(), Some("JBSWY3DPEHPK3PXP"));
+    }
+
+    #[test]
+    fn test_parse_nordpass_csv() {
+        let csv_content = "name,url,additional_urls,username,password,note,cardholdername,cardnumber,cvc,pin,expirydate,zipcode,folder,full_name,phone_number,email,address1,address2,city,country,state,type,custom_fields\n\
+            Example Site,https://example.com,,user@test.com,password123,Test notes,,,,,,,Work,,,,,,,,,password,\n\
+            Work,,,,,,,,,,,,,,,,,,,,,folder,\n\
+            Carte,,,,,,Alice,4111111111111111,123,,12/30,,Work,,,,,,,,,credit_card,";
+
+        let result = parse_csv_content(csv_content, "nordpass");
+
+        assert!(result.is_ok());
+        let passwords = result.unwrap().passwords;
+        assert_eq!(passwords.len(), 1);
+
+        let password = &passwords[0];
+        assert_eq!(password.site, "Example Site");
+        assert_eq!(password.username, "user@test.com");
+        assert_eq!(password.password, "password123");
+        assert_eq!(password.url, Some("https://example.com".to_string()));
+        assert_eq!(password.folder, Some("Work".to_string()));
+    }
+
+    #[test]
+    fn test_parse_protonpass_csv() {
+        let csv_content = "type,name,url,email,username,password,note,totp,createTime,modifyTime,vault\n\
+            login,Example Site,\"https://example.com, https://login.example.com\",user@test.com,,password123,Test notes,,1700000000,1700000000,Personal\n\
+            note,Note,,,,,Contenu,,1700000000,1700000000,Personal";
+
+        let result = parse_csv_content(csv_content, "protonpass");
+
+        assert!(result.is_ok());
+        let passwords = result.unwrap().passwords;
+        assert_eq!(passwords.len(), 1);
+
+        let password = &passwords[0];
+        assert_eq!(password.site, "Example Site");
+        assert_eq!(password.username, "user@test.com");
+        assert_eq!(password.url, Some("https://example.com".to_string()));
+        assert_eq!(password.folder, Some("Personal".to_string()));
+    }
+
+    #[test]
+    fn test_parse_keeper_csv() {
+        let csv_content = "Work\\Infra,Example Site,user@test.com,password123,https://example.com,Test notes,,PIN,1234,TFC:Keeper,otpauth://totp/Example?secret=JBSWY3DPEHPK3PXP\n\
+            ,Shared Site,shared,hunter2,https://shared.com,,Team\n\
+            Work,Sans mot de passe,user,,https://other.com,";
+
+        let result = parse_csv_content(csv_content, "keeper");
+
+        assert!(result.is_ok());
+        let passwords = result.unwrap().passwords;
+        assert_eq!(passwords.len(), 2);
+
+        let password = &passwords[0];
+        assert_eq!(password.site, "Example Site");
+        assert_eq!(password.username, "user@test.com");
+        assert_eq!(password.password, "password123");
+        assert_eq!(password.notes, Some("Test notes".to_string()));
+        assert_eq!(password.folder, Some("Work/Infra".to_string()));
+        assert_eq!(password.custom_fields, vec![CustomField { name: "PIN".to_string(), value: "1234".to_string(), hidden: false }]);
+        assert_eq!(password.totp.as_deref(), Some("otpauth://totp/Example?secret=JBSWY3DPEHPK3PXP"));
+        assert_eq!(passwords[1].folder, Some("Team".to_string()));
+        assert!(passwords[1].custom_fields.is_empty());
+    }
+
+    #[test]
+    fn test_parse_keepass_csv() {
+        let csv_content = "\"Group\",\"Title\",\"Username\",\"Password\",\"URL\",\"Notes\",\"TOTP\",\"Icon\",\"Last Modified\",\"Created\"\n\
+            \"Passwords/Work/Infra/DB\",\"Postgres\",\"admin\",\" pg secret \",\"https://db.example.com\",\"Prod\",\"otpauth://totp/DB?secret=JBSWY3DPEHPK3PXP\",\"0\",\"2024-01-01T00:00:00Z\",\"2024-01-01T00:00:00Z\"\n\
+            \"Passwords\",\"Wi-Fi\",\"\",\"wifi-pass\",\"\",\"\",\"\",\"0\",\"\",\"\"\n\
+            \"Passwords\",\"Vide\",\"user\",\"\",\"\",\"\",\"\",\"0\",\"\",\"\"";
+
+        let parsed = parse_import_content(csv_content, "keepass", "csv").unwrap();
+        assert_eq!(parsed.passwords.len(), 2);
+
+        let db = &parsed.passwords[0];
+        assert_eq!(db.site, "Postgres");
+        assert_eq!(db.password, " pg secret ");
+        assert_eq!(db.folder.as_deref(), Some("Work/Infra/DB"));
+        assert_eq!(db.notes.as_deref(), Some("Prod"));
+        assert_eq!(db.totp.as_deref(), Some("otpauth://totp/DB?secret=JBSWY3DPEHPK3PXP"));
+
+        let wifi = &parsed.passwords[1];
+        assert_eq!(wifi.username, "");
+        assert_eq!(wifi.folder, None);
+        assert_eq!(parsed.issues.len(), 1);
+        assert_eq!(parsed.issues[0].line, 4);
+
+        // Export CSV de KeePass 2, sans groupe
+        let keepass2 = "\"Account\",\"Login Name\",\"Password\",\"Web Site\",\"Comments\"\n\
+            \"Mail\",\"alice\",\"secret\",\"https://mail.example.com\",\"Perso\"";
+        let password = &parse_import_content(keepass2, "keepass", "csv").unwrap().passwords[0];
+        assert_eq!(password.site, "Mail");
+        assert_eq!(password.username, "alice");
+        assert_eq!(password.url.as_deref(), Some("https://mail.example.com"));
+        assert_eq!(password.notes.as_deref(), Some("Perso"));
+    }
+
+    #[test]
+    fn test_parse_keepass_xml_export() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8" standalone="yes"?>
+<KeePassFile><Root><Group><Name>Base</Name>
+    <Group><Name>Work</Name><Group><Name>Infra</Name>
+        <Entry>
+            <String><Key>Title</Key><Value>DB</Value></String>
+            <String><Key>Password</Key><Value>secret</Value></String>
+            <String><Key>otp</Key><Value>otpauth://totp/DB?secret=JBSWY3DPEHPK3PXP</Value></String>
+        </Entry>
+    </Group></Group>
+</Group></Root></KeePassFile>"#;
+
+        let parsed = parse_import_bytes(xml.as_bytes(), AUTO_SOURCE, "xml", None, None).unwrap();
+        assert_eq!(parsed.passwords.len(), 1);
+        assert_eq!(parsed.passwords[0].folder.as_deref(), Some("Work/Infra"));
+        assert!(parsed.passwords[0].totp.is_some());
+        assert!(parse_import_content(xml, "lastpass", "xml").is_err());
+    }
+
+    #[test]
+    fn test_parse_csv_reports_skipped_rows() {
+        let csv_content = "name,url,username,password\n\
+            Example Site,https://example.com,user@test.com,password123\n\
+            Tronquée,https://broken.com\n\
+            Sans mot de passe,https://other.com,user,\n\
+            \"Sur deux\nlignes\",https://multi.com,user,secret123\n\
+            Carte,,,\n\
+            Dernier,https://last.com,user,secret456\n\
+            Example Site,https://example.com,user@test.com,autre-mdp";
+
+        let parsed = parse_csv_content(csv_content, "chrome").unwrap();
+        assert_eq!(parsed.passwords.len(), 4);
+        assert_eq!(parsed.passwords[2].site, "Dernier");
+        // Les lignes du fichier sont conservées malgré les lignes écartées
+        let lines: Vec<Option<usize>> = parsed.passwords.iter().map(|p| p.source_line).collect();
+        assert_eq!(lines, vec![Some(2), Some(5), Some(8), Some(9)]);
+        assert_eq!(parsed.issues, vec![
+            ImportIssue {
+                line: 3,
+                column: None,
+                kind: ImportIssueKind::MalformedRow,
+                message: "2 colonne(s) au lieu de 4, ligne ignorée".to_string(),
+            },
+            ImportIssue {
+                line: 4,
+                column: Some("password".to_string()),
+                kind: ImportIssueKind::MissingField,
+                message: "Champ « password » manquant, ligne ignorée".to_string(),
+            },
+            ImportIssue {
+                line: 7,
+                column: Some("username".to_string()),
+                kind: ImportIssueKind::MissingField,
+                message: "Champ « username » manquant, ligne ignorée".to_string(),
+            },
+        ]);
+
+        let nordpass = "name,url,username,password,type\nCarte,,,,credit_card";
+        let issue = &parse_csv_content(nordpass, "nordpass").unwrap().issues[0];
+        assert_eq!(issue.kind, ImportIssueKind::UnsupportedType);
+        assert_eq!(issue.message, "Élément de type « credit_card » non importé");
+
+        // Chaque ligne écartée à la lecture compte parmi les entrées ignorées
+        let options = ImportOptions {
+            skip_duplicates: true,
+            update_existing: false,
+            validate_urls: false,
+            import_notes: true,
+        };
+        let plan = plan_import(parsed, &[], &options);
+        assert_eq!(plan.result.imported, 3);
+        assert_eq!(plan.result.skipped, 4);
+        assert_eq!(plan.result.issues[3], ImportIssue {
+            line: 9,
+            column: None,
+            kind: ImportIssueKind::Duplicate,
+            message: "Doublon de la ligne 2, ligne ignorée".to_string(),
+        });
+    }
+
+    #[test]
+    fn test_parse_import_auto_source() {
+        let csv_content = "url,username,password,extra,name,grouping,fav\nhttps://example.com,user@test.com,password123,notes,Example Site,Work,0";
+
+        let parsed = parse_import_bytes(csv_content.as_bytes(), AUTO_SOURCE, "csv", None, None).unwrap();
+        assert_eq!(parsed.passwords.len(), 1);
+        assert_eq!(parsed.passwords[0].site, "Example Site");
+
+        let err = parse_import_bytes(b"a,b\n1,2", AUTO_SOURCE, "csv", None, None).unwrap_err();
+        assert!(err.contains("source"));
+    }
+
+    #[test]
+    fn test_parse_firefox_csv_keeps_metadata() {
+        let csv_content = "\"url\",\"username\",\"password\",\"httpRealm\",\"formActionOrigin\",\"guid\",\"timeCreated\",\"timeLastUsed\",\"timePasswordChanged\"\n\
+            \"https://example.com\",\"user@test.com\",\"password123\",,\"https://login.example.com\",\"{0b6d2c1e-1111-4c3b-9d7e-2a0c0d2b5f10}\",\"1700000000000\",\"1710000000000\",\"1705000000000\"\n\
+            \"https://intranet.example.com\",\"admin\",\"hunter22\",\"Espace admin\",,\"{guid-2}\",\"0\",\"\",\"\"";
+
+        let passwords = parse_csv_content(csv_content, "firefox").unwrap().passwords;
+        assert_eq!(passwords.len(), 2);
+
+        let password = &passwords[0];
+        assert_eq!(password.site, "example.com");
+        assert_eq!(password.url.as_deref(), Some("https://example.com"));
+        assert_eq!(password.uris.len(), 2);
+        assert_eq!(password.uris[1].uri, "https://login.example.com");
+        assert_eq!(password.custom_fields, vec![CustomField {
+            name: "GUID Firefox".to_string(),
+            value: "{0b6d2c1e-1111-4c3b-9d7e-2a0c0d2b5f10}".to_string(),
+            hidden: false,
+        }]);
+        assert_eq!(password.created_at.as_deref(), Some("2023-11-14 22:13:20"));
+        assert_eq!(password.last_used_at.as_deref(), Some("2024-03-09 16:00:00"));
+        assert_eq!(password.updated_at.as_deref(), Some("2024-01-11 19:06:40"));
+
+        let realm = &passwords[1];
+        assert_eq!(realm.custom_fields[0], CustomField { name: "Realm HTTP".to_string(), value: "Espace admin".to_string(), hidden: false });
+        assert_eq!(realm.created_at, None);
+        assert_eq!(realm.uris.len(), 1);
+
+        let input = VaultEntryInput::from(password);
+        assert_eq!(input.created_at.as_deref(), Some("2023-11-14 22:13:20"));
+    }
+
+    #[test]
+    fn test_csv_uris_keep_match_types() {
+        let uris = vec![
+            EntryUri { uri: "https://example.com".to_string(), match_type: None },
+            EntryUri { uri: "https://example.com/login".to_string(), match_type: Some(UriMatch::StartsWith) },
+            EntryUri { uri: "^https://x\\.com/.{1,3}$".to_string(), match_type: Some(UriMatch::Regex) },
+            EntryUri { uri: "https://example.com/list?ids=1,2".to_string(), match_type: Some(UriMatch::Exact) },
+        ];
+        let cell = format_uri_list(&uris);
+        assert_eq!(
+            cell,
+            "https://example.com\n[starts_with] https://example.com/login\n[regex] ^https://x\\.com/.{1,3}$\n[exact] https://example.com/list?ids=1,2"
+        );
+
+        let content = format!("site,username,password,url,uris\nExample,user,pass,https://example.com,\"{}\"\n", cell);
+        let parsed = parse_csv_content(&content, "generic").unwrap();
+        assert_eq!(parsed.passwords[0].uris, uris);
+    }
+
+    #[test]
+    fn test_parse_bitwarden_csv() {
+        let csv_content = "folder,favorite,type,name,notes,fields,reprompt,login_uri,login_username,login_password,login_totp\n\
+            ,,login,Example,,,0,\"https://example.com,https://example.org\",user,password123,";
+        let parsed = parse_import_content(csv_content, "bitwarden", "csv").unwrap();
+        let uris: Vec<&str> = parsed.passwords[0].uris.iter().map(|uri| uri.uri.as_str()).collect();
+        assert_eq!(uris, vec!["https://example.com", "https://example.org"]);
+        assert_eq!(parsed.passwords[0].url.as_deref(), Some("https://example.com"));
     }
 
     #[test]
@@ -426,6 +1379,7 @@ mod tests {
                 notes: Some("notes".to_string()),
                 url: Some("https://example.com".to_string()),
                 folder: None,
+                ..ImportedPassword::default()
             },
             ImportedPassword {
                 site: "".to_string(), // Site manquant
@@ -434,6 +1388,7 @@ mod tests {
                 notes: None,
                 url: None,
                 folder: None,
+                ..ImportedPassword::default()
             },
             ImportedPassword {
                 site: "test.com".to_string(),
@@ -442,6 +1397,7 @@ mod tests {
                 notes: None,
                 url: None,
                 folder: None,
+                ..ImportedPassword::default()
             },
         ];
 
@@ -449,6 +1405,7 @@ mod tests {
         
         // Devrait y avoir des warnings pour les champs manquants
         assert!(warnings.len() >= 2); // Au moins 2 warnings pour les champs manquants
+        assert!(warnings.iter().any(|w| w.starts_with("Ligne 1: Mot de passe faible")));
     }
 
     #[test]
@@ -461,6 +1418,7 @@ mod tests {
                 notes: None,
                 url: None,
                 folder: None,
+                ..ImportedPassword::default()
             },
             ImportedPassword {
                 site: "example.com".to_string(),
@@ -469,6 +1427,7 @@ mod tests {
                 notes: None,
                 url: None,
                 folder: None,
+                ..ImportedPassword::default()
             },
             ImportedPassword {
                 site: "unique.com".to_string(),
@@ -477,6 +1436,7 @@ mod tests {
                 notes: None,
                 url: None,
                 folder: None,
+                ..ImportedPassword::default()
             },
         ];
 
@@ -487,6 +1447,131 @@ mod tests {
         assert_eq!(duplicates[0].1, 1); // Deuxième index
     }
 
+    fn imported(site: &str, username: &str, password: &str) -> ImportedPassword {
+        ImportedPassword {
+            site: site.to_string(),
+            username: username.to_string(),
+            password: password.to_string(),
+            notes: Some("notes".to_string()),
+            url: None,
+            folder: None,
+            ..ImportedPassword::default()
+        }
+    }
+
+    fn existing_entry(id: i64, site: &str, username: &str) -> VaultEntry {
+        VaultEntry {
+            id,
+            site: site.to_string(),
+            username: username.to_string(),
+            password: "ancien".to_string(),
+            url: None,
+            uris: Vec::new(),
+            notes: None,
+            folder: None,
+            tags: vec!["perso".to_string()],
+            custom_fields: Vec::new(),
+            entry_type: EntryType::Login,
+            totp: None,
+            password_history: Vec::new(),
+            favorite: true,
+            created_at: "2024-01-01 00:00:00".to_string(),
+            updated_at: None,
+            last_used_at: None,
+        }
+    }
+
+    #[test]
+    fn test_plan_import_flags_weak_passwords() {
+        let options = ImportOptions {
+            skip_duplicates: false,
+            update_existing: false,
+            validate_urls: false,
+            import_notes: true,
+        };
+        let passwords = vec![
+            imported("example.com", "alice", "password123"),
+            imported("other.com", "bob", "xK9#mQ2$vL7!pR4&"),
+        ];
+        let plan = plan_import(ParsedImport { passwords, ..ParsedImport::default() }, &[], &options);
+
+        assert_eq!(plan.result.imported, 2);
+        let weak: Vec<&String> = plan.result.warnings.iter().filter(|w| w.contains("Mot de passe faible")).collect();
+        assert_eq!(weak.len(), 1);
+        assert!(weak[0].starts_with("Ligne 1: Mot de passe faible"));
+    }
+
+    #[test]
+    fn test_plan_import_applies_options() {
+        let existing = vec![existing_entry(7, "Example.com", "user@test.com")];
+        let mut with_bad_url = imported("other.com", "bob", "password123");
+        with_bad_url.url = Some("pas une url".to_string());
+        let passwords = vec![
+            imported("example.com", "user@test.com", "nouveau-mdp"),
+            with_bad_url,
+            imported("", "anonyme", "password123"),
+            imported("other.com", "bob", "password456"),
+        ];
+
+        let options = ImportOptions {
+            skip_duplicates: true,
+            update_existing: false,
+            validate_urls: true,
+            import_notes: false,
+        };
+        let parsed = ParsedImport { passwords: passwords.clone(), ..ParsedImport::default() };
+        let plan = plan_import(parsed, &existing, &options);
+
+        assert_eq!(plan.inserts.len(), 1);
+        assert!(plan.inserts[0].url.is_none());
+        assert!(plan.inserts[0].notes.is_none());
+        assert!(plan.updates.is_empty());
+        assert_eq!(plan.result.imported, 1);
+        assert_eq!(plan.result.skipped, 3);
+        assert_eq!(plan.result.duplicates.len(), 2);
+        assert!(plan.result.errors.is_empty());
+        let kinds: Vec<(usize, ImportIssueKind)> = plan.result.issues.iter().map(|i| (i.line, i.kind)).collect();
+        assert_eq!(kinds, vec![
+            (1, ImportIssueKind::Duplicate),
+            (3, ImportIssueKind::MissingField),
+            (4, ImportIssueKind::Duplicate),
+        ]);
+        assert!(plan.result.warnings.iter().any(|w| w.contains("URL invalide")));
+
+        let options = ImportOptions { update_existing: true, ..options };
+        let plan = plan_import(ParsedImport { passwords, ..ParsedImport::default() }, &existing, &options);
+
+        assert_eq!(plan.updates.len(), 1);
+        assert_eq!(plan.updates[0].0, 7);
+        assert_eq!(plan.updates[0].1.password, "nouveau-mdp");
+        assert_eq!(plan.updates[0].1.tags, vec!["perso"]);
+        assert!(plan.updates[0].1.favorite);
+    }
+
+    #[test]
+    fn test_plan_import_validates_each_uri() {
+        // Mot de passe robuste : seul l'avertissement sur l'URL invalide est attendu
+        let mut password = imported("example.com", "user", "xK9#mQ2$vL7!pR4&");
+        password.uris = vec![
+            EntryUri { uri: "pas une url".to_string(), match_type: None },
+            EntryUri { uri: "^https://.*\\.example\\.com/".to_string(), match_type: Some(UriMatch::Regex) },
+            EntryUri { uri: "https://example.com/login".to_string(), match_type: Some(UriMatch::StartsWith) },
+        ];
+        let options = ImportOptions {
+            skip_duplicates: true,
+            update_existing: false,
+            validate_urls: true,
+            import_notes: true,
+        };
+        let plan = plan_import(ParsedImport { passwords: vec![password], ..ParsedImport::default() }, &[], &options);
+
+        let insert = &plan.inserts[0];
+        assert_eq!(insert.uris.len(), 2);
+        assert_eq!(insert.uris[0].match_type, Some(UriMatch::Regex));
+        assert_eq!(insert.url.as_deref(), Some("https://example.com/login"));
+        assert_eq!(plan.result.warnings.len(), 1);
+    }
+
     #[test]
     fn test_extract_domain_from_url() {
         // Tester la fonction telle qu'elle est implémentée
@@ -504,9 +1589,8 @@ mod tests {
         let result = parse_csv_content(invalid_csv, "lastpass");
         
         // Devrait retourner une erreur ou un vecteur vide selon l'implémentation
-        match result {
-            Ok(passwords) => assert!(passwords.is_empty()),
-            Err(_) => {} // Erreur attendue
+        if let Ok(parsed) = result {
+            assert!(parsed.passwords.is_empty());
         }
     }
 
@@ -515,9 +1599,9 @@ mod tests {
         let empty_csv = "";
         let result = parse_csv_content(empty_csv, "lastpass");
         
-        match result {
-            Ok(passwords) => assert!(passwords.is_empty()),
-            Err(_) => {} // Erreur attendue pour entrée vide
+        // Erreur attendue pour entrée vide, sinon aucun résultat
+        if let Ok(parsed) = result {
+            assert!(parsed.passwords.is_empty());
         }
     }
 } 
\ No newline at end of file
@@ -0,0 +1,200 @@
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use zeroize::Zeroizing;
+
+use crate::import_detect::{detect_import_source, AUTO_SOURCE};
+use crate::import_export::{parse_csv_reader, parse_import_bytes, ImportedPassword, ParsedImport};
+use crate::pass_store;
+
+// Import d'un fichier lu directement sur le disque, sans faire transiter son contenu par
+// l'IPC : les CSV sont parsés au fil de la lecture, avec progression et annulation
+
+pub const IMPORT_PROGRESS_EVENT: &str = "import-progress";
+
+// Une progression est signalée toutes les PROGRESS_INTERVAL lignes
+const PROGRESS_INTERVAL: usize = 500;
+
+// Début du fichier examiné pour reconnaître la source d'un CSV
+const DETECTION_PREFIX_SIZE: u64 = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportStage {
+    Parsing,
+    Saving,
+    Done,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportProgress {
+    pub stage: ImportStage,
+    pub rows: usize,
+    pub bytes_read: u64,
+    pub total_bytes: u64,
+}
+
+// Demande d'annulation de l'import en cours, partagée entre les commandes
+#[derive(Default)]
+pub struct ImportCancellation(AtomicBool);
+
+impl ImportCancellation {
+    pub fn reset(&self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn check(&self) -> Result<(), String> {
+        if self.0.load(Ordering::SeqCst) {
+            return Err("Import annulé".to_string());
+        }
+        Ok(())
+    }
+}
+
+pub fn parse_import_path(
+    path: &Path,
+    source: &str,
+    file_password: Option<&str>,
+    cancellation: &ImportCancellation,
+    mut on_progress: impl FnMut(ImportProgress),
+) -> Result<ParsedImport, String> {
+    // Un dossier ne peut être qu'un store pass déchiffré, parcouru fichier par fichier
+    if path.is_dir() {
+        let mut parsed = pass_store::parse_pass_directory(path, |files| {
+            cancellation.check()?;
+            if files % PROGRESS_INTERVAL == 0 {
+                on_progress(ImportProgress { stage: ImportStage::Parsing, rows: files, bytes_read: 0, total_bytes: 0 });
+            }
+            Ok(())
+        })?;
+        parsed.passwords.iter_mut().for_each(ImportedPassword::normalize_uris);
+        on_progress(ImportProgress { stage: ImportStage::Parsing, rows: parsed.passwords.len(), bytes_read: 0, total_bytes: 0 });
+        return Ok(parsed);
+    }
+
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_lowercase)
+        .unwrap_or_default();
+    let total_bytes = std::fs::metadata(path)
+        .map_err(|e| format!("Impossible de lire le fichier: {}", e))?
+        .len();
+
+    // Les formats structurés (JSON, XML, bases chiffrées) se parsent d'un bloc
+    if extension != "csv" {
+        let content = Zeroizing::new(std::fs::read(path).map_err(|e| format!("Impossible de lire le fichier: {}", e))?);
+        cancellation.check()?;
+        let parsed = parse_import_bytes(&content, source, &extension, file_password, None)?;
+        on_progress(ImportProgress {
+            stage: ImportStage::Parsing,
+            rows: parsed.passwords.len(),
+            bytes_read: total_bytes,
+            total_bytes,
+        });
+        return Ok(parsed);
+    }
+
+    let mut file = File::open(path).map_err(|e| format!("Impossible d'ouvrir le fichier: {}", e))?;
+    let source = if source == AUTO_SOURCE {
+        detect_csv_source(&mut file)?
+    } else {
+        source.to_string()
+    };
+
+    let mut parsed = parse_csv_reader(BufReader::new(file), &source, |rows, bytes_read| {
+        cancellation.check()?;
+        if rows % PROGRESS_INTERVAL == 0 {
+            on_progress(ImportProgress { stage: ImportStage::Parsing, rows, bytes_read, total_bytes });
+        }
+        Ok(())
+    })?;
+    parsed.passwords.iter_mut().for_each(ImportedPassword::normalize_uris);
+
+    on_progress(ImportProgress {
+        stage: ImportStage::Parsing,
+        rows: parsed.passwords.len() + parsed.issues.len(),
+        bytes_read: total_bytes,
+        total_bytes,
+    });
+    Ok(parsed)
+}
+
+// Les en-têtes suffisent à reconnaître un CSV : seules les premières lignes complètes sont lues
+fn detect_csv_source(file: &mut File) -> Result<String, String> {
+    let mut prefix = Vec::new();
+    file.by_ref()
+        .take(DETECTION_PREFIX_SIZE)
+        .read_to_end(&mut prefix)
+        .map_err(|e| format!("Impossible de lire le fichier: {}", e))?;
+    if prefix.len() as u64 == DETECTION_PREFIX_SIZE {
+        let end = prefix.iter().rposition(|b| *b == b'\n').unwrap_or(prefix.len());
+        prefix.truncate(end);
+    }
+    let prefix = Zeroizing::new(prefix);
+
+    // Le lecteur CSV repart du début du fichier
+    file.rewind().map_err(|e| format!("Impossible de lire le fichier: {}", e))?;
+
+    detect_import_source(&prefix, "csv")
+        .into_iter()
+        .next()
+        .map(|candidate| candidate.source)
+        .ok_or_else(|| "Impossible de reconnaître la source du fichier".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn temp_file(name: &str, content: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("vaultword-import-stream-{}", name));
+        File::create(&path).unwrap().write_all(content.as_bytes()).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_parse_import_path_streams_csv() {
+        let mut content = String::from("name,url,username,password\n");
+        for i in 0..1_200 {
+            content.push_str(&format!("Site {},https://site{}.example.com,user{},password{}\n", i, i, i, i));
+        }
+        let path = temp_file("chrome.csv", &content);
+
+        let mut progress = Vec::new();
+        let parsed = parse_import_path(&path, AUTO_SOURCE, None, &ImportCancellation::default(), |p| progress.push(p)).unwrap();
+        assert_eq!(parsed.passwords.len(), 1_200);
+        assert_eq!(parsed.passwords[1_199].site, "Site 1199");
+        assert_eq!(parsed.passwords[0].uris.len(), 1);
+
+        let rows: Vec<usize> = progress.iter().map(|p| p.rows).collect();
+        assert_eq!(rows, vec![500, 1_000, 1_200]);
+        assert_eq!(progress.last().unwrap().bytes_read, content.len() as u64);
+
+        let cancellation = ImportCancellation::default();
+        cancellation.cancel();
+        let err = parse_import_path(&path, "chrome", None, &cancellation, |_| {}).unwrap_err();
+        assert_eq!(err, "Import annulé");
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_parse_import_path_structured_file() {
+        let path = temp_file("export.json", r#"[{"site": "Example", "username": "user", "password": "password123"}]"#);
+
+        let parsed = parse_import_path(&path, "vaultword", None, &ImportCancellation::default(), |_| {}).unwrap();
+        assert_eq!(parsed.passwords.len(), 1);
+        assert!(parse_import_path(Path::new("/nonexistent/export.csv"), "chrome", None, &ImportCancellation::default(), |_| {}).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,706 @@
+use aes::cipher::{block_padding::Pkcs7, BlockDecryptMut, BlockEncrypt, KeyInit, KeyIvInit, StreamCipher};
+use aes::Aes256;
+use argon2::{Algorithm, Argon2, AssociatedData, ParamsBuilder, Version};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chacha20::ChaCha20;
+use hmac::{Hmac, Mac};
+use salsa20::Salsa20;
+use sha2::{Digest, Sha256, Sha512};
+use flate2::{write::GzEncoder, Compression};
+use rand::RngCore;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use zeroize::Zeroizing;
+
+use crate::import_export::{ImportedPassword, ParsedImport};
+use crate::kdf::KdfParams;
+use crate::keepass_xml;
+
+// Lecture et écriture des bases KeePass au format KDBX 4 (KeePass 2.35+, KeePassXC)
+pub(crate) const SIGNATURE: [u8; 8] = [0x03, 0xD9, 0xA2, 0x9A, 0x67, 0xFB, 0x4B, 0xB5];
+const MAJOR_VERSION: u16 = 4;
+const MINOR_VERSION: u16 = 1;
+
+const CIPHER_AES256: [u8; 16] = [
+    0x31, 0xC1, 0xF2, 0xE6, 0xBF, 0x71, 0x43, 0x50, 0xBE, 0x58, 0x05, 0x21, 0x6A, 0xFC, 0x5A, 0xFF,
+];
+const CIPHER_CHACHA20: [u8; 16] = [
+    0xD6, 0x03, 0x8A, 0x2B, 0x8B, 0x6F, 0x4C, 0xB5, 0xA5, 0x24, 0x33, 0x9A, 0x31, 0xDB, 0xB5, 0x9A,
+];
+const KDF_AES_KDBX3: [u8; 16] = [
+    0xC9, 0xD9, 0xF3, 0x9A, 0x62, 0x8A, 0x44, 0x60, 0xBF, 0x74, 0x0D, 0x08, 0xC1, 0x8A, 0x4F, 0xEA,
+];
+const KDF_AES_KDBX4: [u8; 16] = [
+    0x7C, 0x02, 0xBB, 0x82, 0x79, 0xA7, 0x4A, 0xC0, 0x92, 0x7D, 0x11, 0x4A, 0x00, 0x64, 0x82, 0x38,
+];
+const KDF_ARGON2D: [u8; 16] = [
+    0xEF, 0x63, 0x6D, 0xDF, 0x8C, 0x29, 0x44, 0x4B, 0x91, 0xF7, 0xA9, 0xA4, 0x03, 0xE3, 0x0A, 0x0C,
+];
+const KDF_ARGON2ID: [u8; 16] = [
+    0x9E, 0x29, 0x8B, 0x19, 0x56, 0xDB, 0x47, 0x73, 0xB2, 0x3D, 0xFC, 0x3E, 0xC6, 0xF0, 0xA1, 0xE6,
+];
+
+// Champs de l'en-tête externe
+const HEADER_END: u8 = 0;
+const HEADER_CIPHER_ID: u8 = 2;
+const HEADER_COMPRESSION: u8 = 3;
+const HEADER_MASTER_SEED: u8 = 4;
+const HEADER_ENCRYPTION_IV: u8 = 7;
+const HEADER_KDF_PARAMETERS: u8 = 11;
+
+// Champs de l'en-tête interne
+const INNER_HEADER_END: u8 = 0;
+const INNER_STREAM_ID: u8 = 1;
+const INNER_STREAM_KEY: u8 = 2;
+
+// Types du dictionnaire de variantes utilisés par les paramètres KDF
+const VARIANT_END: u8 = 0;
+const VARIANT_U32: u8 = 0x04;
+const VARIANT_U64: u8 = 0x05;
+const VARIANT_BYTES: u8 = 0x42;
+
+// Taille des blocs HMAC écrits, identique à KeePass
+const BLOCK_SIZE: usize = 1024 * 1024;
+
+const INNER_STREAM_SALSA20: u32 = 2;
+const INNER_STREAM_CHACHA20: u32 = 3;
+const SALSA20_NONCE: [u8; 8] = [0xE8, 0x30, 0x09, 0x4B, 0x97, 0x20, 0x5D, 0x2A];
+
+// Une base malveillante pourrait sinon réclamer une allocation démesurée
+const MAX_ARGON2_MEMORY_KIB: u64 = 4 * 1024 * 1024;
+// ou bloquer l'import pendant des heures (KeePass et KeePassXC calibrent bien en dessous)
+const MAX_AES_KDF_ROUNDS: u64 = 100_000_000;
+const MAX_ARGON2_ITERATIONS: u64 = 1_000;
+
+type HmacSha256 = Hmac<Sha256>;
+
+// Importe une base KDBX 4 protégée par mot de passe et/ou fichier clé
+pub fn parse_kdbx(data: &[u8], password: Option<&str>, key_file: Option<&[u8]>) -> Result<ParsedImport, String> {
+    let composite = composite_key(password, key_file)?;
+    let payload = decrypt_payload(data, &composite)?;
+
+    let mut reader = ByteReader::new(&payload);
+    let mut stream_id = 0;
+    let mut stream_key = Zeroizing::new(Vec::new());
+    loop {
+        let field = reader.u8()?;
+        let size = reader.u32()? as usize;
+        let value = reader.take(size)?;
+        match field {
+            INNER_HEADER_END => break,
+            INNER_STREAM_ID => stream_id = ByteReader::new(value).u32()?,
+            INNER_STREAM_KEY => stream_key = Zeroizing::new(value.to_vec()),
+            // Les pièces jointes (champ 3) ne sont pas importées
+            _ => {}
+        }
+    }
+
+    let xml = Zeroizing::new(
+        String::from_utf8(reader.rest().to_vec()).map_err(|_| "Contenu XML de la base illisible".to_string())?,
+    );
+    let mut stream = InnerStream::new(stream_id, &stream_key)?;
+    keepass_xml::parse_keepass_xml(&xml, Some(&mut |data: &mut [u8]| stream.apply(data)))
+}
+
+// Écrit une base KDBX 4 (Argon2id, ChaCha20, compressée) lisible par KeePass et KeePassXC
+pub fn write_kdbx(passwords: &[ImportedPassword], password: &str, kdf: &KdfParams) -> Result<Vec<u8>, String> {
+    kdf.validate()?;
+    let composite = composite_key(Some(password), None)?;
+
+    let mut master_seed = [0u8; 32];
+    let mut encryption_iv = [0u8; 12];
+    let mut kdf_salt = [0u8; 32];
+    let mut stream_key = Zeroizing::new([0u8; 64]);
+    for buffer in [&mut master_seed[..], &mut encryption_iv[..], &mut kdf_salt[..], &mut stream_key[..]] {
+        rand::rngs::OsRng.fill_bytes(buffer);
+    }
+
+    let kdf_variants: [(u8, &str, Vec<u8>); 6] = [
+        (VARIANT_BYTES, "$UUID", KDF_ARGON2ID.to_vec()),
+        (VARIANT_BYTES, "S", kdf_salt.to_vec()),
+        (VARIANT_U32, "P", kdf.parallelism.to_le_bytes().to_vec()),
+        (VARIANT_U64, "M", (kdf.memory_kib as u64 * 1024).to_le_bytes().to_vec()),
+        (VARIANT_U64, "I", (kdf.iterations as u64).to_le_bytes().to_vec()),
+        (VARIANT_U32, "V", 0x13u32.to_le_bytes().to_vec()),
+    ];
+    let kdf_parameters: HashMap<String, Vec<u8>> = kdf_variants
+        .iter()
+        .map(|(_, name, value)| (name.to_string(), value.clone()))
+        .collect();
+
+    let mut header = SIGNATURE.to_vec();
+    header.extend_from_slice(&MINOR_VERSION.to_le_bytes());
+    header.extend_from_slice(&MAJOR_VERSION.to_le_bytes());
+    write_field(&mut header, HEADER_CIPHER_ID, &CIPHER_CHACHA20);
+    write_field(&mut header, HEADER_COMPRESSION, &1u32.to_le_bytes());
+    write_field(&mut header, HEADER_MASTER_SEED, &master_seed);
+    write_field(&mut header, HEADER_ENCRYPTION_IV, &encryption_iv);
+    write_field(&mut header, HEADER_KDF_PARAMETERS, &write_variant_dictionary(&kdf_variants));
+    write_field(&mut header, HEADER_END, b"\r\n\r\n");
+
+    let DerivedKeys { cipher_key, hmac_key } = derive_keys(&composite, &master_seed, &kdf_parameters)?;
+
+    let mut payload = Zeroizing::new(Vec::new());
+    write_field(&mut payload, INNER_STREAM_ID, &INNER_STREAM_CHACHA20.to_le_bytes());
+    write_field(&mut payload, INNER_STREAM_KEY, &stream_key[..]);
+    write_field(&mut payload, INNER_HEADER_END, &[]);
+    let mut stream = InnerStream::new(INNER_STREAM_CHACHA20, &stream_key[..])?;
+    let xml = Zeroizing::new(keepass_xml::write_keepass_xml(passwords, "VaultWord", &mut |data: &mut [u8]| {
+        stream.apply(data)
+    }));
+    payload.extend_from_slice(xml.as_bytes());
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(&payload)
+        .map_err(|e| format!("Erreur lors de la compression de la base: {}", e))?;
+    let mut encrypted = encoder
+        .finish()
+        .map_err(|e| format!("Erreur lors de la compression de la base: {}", e))?;
+    ChaCha20::new_from_slices(&cipher_key[..], &encryption_iv)
+        .map_err(|_| "Nonce ChaCha20 invalide".to_string())?
+        .apply_keystream(&mut encrypted);
+
+    let mut output = header.clone();
+    output.extend_from_slice(&Sha256::digest(&header));
+    output.extend_from_slice(&block_hmac(&hmac_key, u64::MAX).chain_update(&header).finalize().into_bytes());
+
+    // Le dernier bloc, vide, marque la fin du flux
+    let blocks = encrypted.chunks(BLOCK_SIZE).chain(std::iter::once(&[][..]));
+    for (index, block) in (0u64..).zip(blocks) {
+        let size = (block.len() as u32).to_le_bytes();
+        let mac = block_hmac(&hmac_key, index)
+            .chain_update(index.to_le_bytes())
+            .chain_update(size)
+            .chain_update(block)
+            .finalize()
+            .into_bytes();
+        output.extend_from_slice(&mac);
+        output.extend_from_slice(&size);
+        output.extend_from_slice(block);
+    }
+
+    Ok(output)
+}
+
+fn write_field(output: &mut Vec<u8>, field: u8, value: &[u8]) {
+    output.push(field);
+    output.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    output.extend_from_slice(value);
+}
+
+// Vérifie l'en-tête et les blocs HMAC, puis déchiffre et décompresse le contenu
+fn decrypt_payload(data: &[u8], composite: &[u8; 32]) -> Result<Zeroizing<Vec<u8>>, String> {
+    let mut reader = ByteReader::new(data);
+    if reader.take(8)? != SIGNATURE {
+        return Err("Le fichier n'est pas une base KeePass".to_string());
+    }
+    let _minor = reader.u16()?;
+    let major = reader.u16()?;
+    if major != MAJOR_VERSION {
+        return Err(format!(
+            "Version KDBX {} non prise en charge : enregistrez la base au format KDBX 4",
+            major
+        ));
+    }
+
+    let mut cipher_id = Vec::new();
+    let mut compressed = false;
+    let mut master_seed = Vec::new();
+    let mut encryption_iv = Vec::new();
+    let mut kdf_parameters = HashMap::new();
+    loop {
+        let field = reader.u8()?;
+        let size = reader.u32()? as usize;
+        let value = reader.take(size)?;
+        match field {
+            HEADER_END => break,
+            HEADER_CIPHER_ID => cipher_id = value.to_vec(),
+            HEADER_COMPRESSION => compressed = ByteReader::new(value).u32()? == 1,
+            HEADER_MASTER_SEED => master_seed = value.to_vec(),
+            HEADER_ENCRYPTION_IV => encryption_iv = value.to_vec(),
+            HEADER_KDF_PARAMETERS => kdf_parameters = read_variant_dictionary(value)?,
+            _ => {}
+        }
+    }
+    let header = &data[..reader.pos];
+
+    let header_hash = reader.take(32)?;
+    if Sha256::digest(header).as_slice() != header_hash {
+        return Err("En-tête KDBX corrompu".to_string());
+    }
+    if master_seed.len() != 32 {
+        return Err("Graine maîtresse KDBX invalide".to_string());
+    }
+
+    let DerivedKeys { cipher_key, hmac_key } = derive_keys(composite, &master_seed, &kdf_parameters)?;
+
+    // Le HMAC de l'en-tête est le premier élément dépendant de la clé : il signale un mauvais mot de passe
+    let header_hmac = reader.take(32)?;
+    block_hmac(&hmac_key, u64::MAX)
+        .chain_update(header)
+        .verify_slice(header_hmac)
+        .map_err(|_| "Mot de passe ou fichier clé incorrect".to_string())?;
+
+    let mut encrypted = Vec::new();
+    for index in 0u64.. {
+        let block_hmac_value = reader.take(32)?;
+        let size = reader.u32()?;
+        let block = reader.take(size as usize)?;
+        block_hmac(&hmac_key, index)
+            .chain_update(index.to_le_bytes())
+            .chain_update(size.to_le_bytes())
+            .chain_update(block)
+            .verify_slice(block_hmac_value)
+            .map_err(|_| format!("Bloc {} de la base corrompu", index))?;
+        if size == 0 {
+            break;
+        }
+        encrypted.extend_from_slice(block);
+    }
+
+    let decrypted = Zeroizing::new(if cipher_id == CIPHER_AES256 {
+        cbc::Decryptor::<Aes256>::new_from_slices(&cipher_key[..], &encryption_iv)
+            .map_err(|_| "Vecteur d'initialisation AES invalide".to_string())?
+            .decrypt_padded_vec_mut::<Pkcs7>(&encrypted)
+            .map_err(|_| "Impossible de déchiffrer la base".to_string())?
+    } else if cipher_id == CIPHER_CHACHA20 {
+        let mut cipher = ChaCha20::new_from_slices(&cipher_key[..], &encryption_iv)
+            .map_err(|_| "Nonce ChaCha20 invalide".to_string())?;
+        cipher.apply_keystream(&mut encrypted);
+        encrypted
+    } else {
+        return Err("Algorithme de chiffrement de la base non pris en charge (Twofish ?)".to_string());
+    });
+
+    if !compressed {
+        return Ok(decrypted);
+    }
+    let mut payload = Zeroizing::new(Vec::new());
+    flate2::read::GzDecoder::new(&decrypted[..])
+        .read_to_end(&mut payload)
+        .map_err(|e| format!("Erreur lors de la décompression de la base: {}", e))?;
+    Ok(payload)
+}
+
+// Clés de chiffrement et d'authentification dérivées de la clé maîtresse et de la graine du fichier
+struct DerivedKeys {
+    cipher_key: Zeroizing<[u8; 32]>,
+    hmac_key: Zeroizing<[u8; 64]>,
+}
+
+fn derive_keys(
+    composite: &[u8; 32],
+    master_seed: &[u8],
+    kdf_parameters: &HashMap<String, Vec<u8>>,
+) -> Result<DerivedKeys, String> {
+    let transformed = transform_key(composite, kdf_parameters)?;
+    let mut cipher_key = Zeroizing::new([0u8; 32]);
+    cipher_key.copy_from_slice(&Sha256::new().chain_update(master_seed).chain_update(*transformed).finalize());
+    let mut hmac_key = Zeroizing::new([0u8; 64]);
+    hmac_key.copy_from_slice(
+        &Sha512::new()
+            .chain_update(master_seed)
+            .chain_update(*transformed)
+            .chain_update([1u8])
+            .finalize(),
+    );
+    Ok(DerivedKeys { cipher_key, hmac_key })
+}
+
+// Clé composite KeePass : SHA-256 de l'empreinte du mot de passe suivie de celle du fichier clé
+fn composite_key(password: Option<&str>, key_file: Option<&[u8]>) -> Result<Zeroizing<[u8; 32]>, String> {
+    if password.is_none() && key_file.is_none() {
+        return Err("Mot de passe ou fichier clé requis pour ouvrir la base".to_string());
+    }
+
+    let mut hasher = Sha256::new();
+    if let Some(password) = password {
+        hasher.update(Sha256::digest(password.as_bytes()));
+    }
+    if let Some(key_file) = key_file {
+        hasher.update(*key_file_key(key_file)?);
+    }
+    let mut composite = Zeroizing::new([0u8; 32]);
+    composite.copy_from_slice(&hasher.finalize());
+    Ok(composite)
+}
+
+// Formats de fichier clé : XML v1/v2, 32 octets bruts, 64 caractères hexadécimaux, ou tout autre fichier haché
+fn key_file_key(content: &[u8]) -> Result<Zeroizing<[u8; 32]>, String> {
+    let mut key = Zeroizing::new([0u8; 32]);
+
+    if let Some(data) = xml_key_file_data(content)? {
+        key.copy_from_slice(&data);
+    } else if content.len() == 32 {
+        key.copy_from_slice(content);
+    } else if let Some(decoded) = decode_hex(content).filter(|d| d.len() == 32) {
+        key.copy_from_slice(&decoded);
+    } else {
+        key.copy_from_slice(&Sha256::digest(content));
+    }
+    Ok(key)
+}
+
+fn xml_key_file_data(content: &[u8]) -> Result<Option<Zeroizing<Vec<u8>>>, String> {
+    let Some(doc) = std::str::from_utf8(content)
+        .ok()
+        .and_then(|text| roxmltree::Document::parse(text).ok())
+    else {
+        return Ok(None);
+    };
+    let root = doc.root_element();
+    if !root.has_tag_name("KeyFile") {
+        return Ok(None);
+    }
+
+    let version = root
+        .descendants()
+        .find(|n| n.has_tag_name("Version"))
+        .and_then(|n| n.text())
+        .unwrap_or("1.0");
+    let data_node = root
+        .descendants()
+        .find(|n| n.has_tag_name("Data"))
+        .ok_or("Fichier clé XML sans données")?;
+    let text = data_node.text().unwrap_or("");
+
+    let data = if version.starts_with('2') {
+        let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+        let data = decode_hex(compact.as_bytes()).ok_or("Fichier clé XML invalide")?;
+        if let Some(hash) = data_node.attribute("Hash") {
+            let expected = decode_hex(hash.as_bytes()).ok_or("Empreinte du fichier clé invalide")?;
+            if Sha256::digest(&data)[..4] != expected[..] {
+                return Err("Fichier clé corrompu (empreinte incorrecte)".to_string());
+            }
+        }
+        data
+    } else {
+        STANDARD
+            .decode(text.trim())
+            .map_err(|_| "Fichier clé XML invalide".to_string())?
+    };
+
+    if data.len() != 32 {
+        return Err("Fichier clé XML invalide".to_string());
+    }
+    Ok(Some(Zeroizing::new(data)))
+}
+
+fn decode_hex(text: &[u8]) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    text.chunks(2)
+        .map(|pair| {
+            let pair = std::str::from_utf8(pair).ok()?;
+            u8::from_str_radix(pair, 16).ok()
+        })
+        .collect()
+}
+
+// Dérivation de la clé maîtresse selon les paramètres KDF de l'en-tête
+fn transform_key(composite: &[u8; 32], parameters: &HashMap<String, Vec<u8>>) -> Result<Zeroizing<[u8; 32]>, String> {
+    let kdf_id = parameters.get("$UUID").ok_or("Paramètres KDF absents")?;
+    let salt = parameters.get("S").ok_or("Sel KDF absent")?;
+    let mut transformed = Zeroizing::new([0u8; 32]);
+
+    if kdf_id[..] == KDF_AES_KDBX3 || kdf_id[..] == KDF_AES_KDBX4 {
+        let rounds = variant_u64(parameters, "R")?;
+        if rounds > MAX_AES_KDF_ROUNDS {
+            return Err("Nombre de tours AES-KDF de la base hors limites".to_string());
+        }
+        let cipher = Aes256::new_from_slice(salt).map_err(|_| "Graine AES-KDF invalide".to_string())?;
+        let mut blocks = Zeroizing::new(*composite);
+        for _ in 0..rounds {
+            let (first, second) = blocks.split_at_mut(16);
+            cipher.encrypt_block(first.into());
+            cipher.encrypt_block(second.into());
+        }
+        transformed.copy_from_slice(&Sha256::digest(&blocks[..]));
+    } else if kdf_id[..] == KDF_ARGON2D || kdf_id[..] == KDF_ARGON2ID {
+        let algorithm = if kdf_id[..] == KDF_ARGON2D { Algorithm::Argon2d } else { Algorithm::Argon2id };
+        let memory_kib = variant_u64(parameters, "M")? / 1024;
+        let iterations = variant_u64(parameters, "I")?;
+        let parallelism = variant_u32(parameters, "P")?;
+        let version = match variant_u32(parameters, "V")? {
+            0x10 => Version::V0x10,
+            _ => Version::V0x13,
+        };
+        if memory_kib > MAX_ARGON2_MEMORY_KIB || iterations > MAX_ARGON2_ITERATIONS {
+            return Err("Paramètres Argon2 de la base hors limites".to_string());
+        }
+
+        let mut builder = ParamsBuilder::new();
+        builder
+            .m_cost(memory_kib as u32)
+            .t_cost(iterations as u32)
+            .p_cost(parallelism)
+            .output_len(32);
+        if let Some(associated) = parameters.get("A").filter(|a| !a.is_empty()) {
+            builder.data(AssociatedData::new(associated).map_err(|e| format!("Paramètres Argon2 invalides: {}", e))?);
+        }
+        let params = builder.build().map_err(|e| format!("Paramètres Argon2 invalides: {}", e))?;
+        let argon2 = match parameters.get("K").filter(|k| !k.is_empty()) {
+            Some(secret) => Argon2::new_with_secret(secret, algorithm, version, params)
+                .map_err(|e| format!("Paramètres Argon2 invalides: {}", e))?,
+            None => Argon2::new(algorithm, version, params),
+        };
+        argon2
+            .hash_password_into(composite, salt, &mut transformed[..])
+            .map_err(|e| format!("Erreur lors de la dérivation de la clé: {}", e))?;
+    } else {
+        return Err("Fonction de dérivation de clé non prise en charge".to_string());
+    }
+
+    Ok(transformed)
+}
+
+fn block_hmac(hmac_key: &[u8; 64], index: u64) -> HmacSha256 {
+    let key = Sha512::new().chain_update(index.to_le_bytes()).chain_update(hmac_key).finalize();
+    <HmacSha256 as Mac>::new_from_slice(&key).expect("HMAC accepte toute taille de clé")
+}
+
+// Dictionnaire de variantes KDBX : seules les valeurs brutes sont conservées
+fn read_variant_dictionary(data: &[u8]) -> Result<HashMap<String, Vec<u8>>, String> {
+    let mut reader = ByteReader::new(data);
+    let version = reader.u16()?;
+    if version >> 8 != 1 {
+        return Err("Paramètres KDF dans un format inconnu".to_string());
+    }
+
+    let mut values = HashMap::new();
+    loop {
+        let kind = reader.u8()?;
+        if kind == VARIANT_END {
+            break;
+        }
+        let name_len = reader.u32()? as usize;
+        let name = String::from_utf8_lossy(reader.take(name_len)?).to_string();
+        let value_len = reader.u32()? as usize;
+        values.insert(name, reader.take(value_len)?.to_vec());
+    }
+    Ok(values)
+}
+
+fn write_variant_dictionary(variants: &[(u8, &str, Vec<u8>)]) -> Vec<u8> {
+    let mut output = 0x0100u16.to_le_bytes().to_vec();
+    for (kind, name, value) in variants {
+        output.push(*kind);
+        output.extend_from_slice(&(name.len() as u32).to_le_bytes());
+        output.extend_from_slice(name.as_bytes());
+        output.extend_from_slice(&(value.len() as u32).to_le_bytes());
+        output.extend_from_slice(value);
+    }
+    output.push(VARIANT_END);
+    output
+}
+
+fn variant_u32(parameters: &HashMap<String, Vec<u8>>, name: &str) -> Result<u32, String> {
+    parameters
+        .get(name)
+        .and_then(|value| value.as_slice().try_into().ok())
+        .map(u32::from_le_bytes)
+        .ok_or(format!("Paramètre KDF {} invalide", name))
+}
+
+fn variant_u64(parameters: &HashMap<String, Vec<u8>>, name: &str) -> Result<u64, String> {
+    parameters
+        .get(name)
+        .and_then(|value| value.as_slice().try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or(format!("Paramètre KDF {} invalide", name))
+}
+
+// Flux de chiffrement des valeurs protégées du XML
+enum InnerStream {
+    None,
+    Salsa20(Box<Salsa20>),
+    ChaCha20(Box<ChaCha20>),
+}
+
+impl InnerStream {
+    fn new(id: u32, key: &[u8]) -> Result<Self, String> {
+        match id {
+            0 => Ok(InnerStream::None),
+            INNER_STREAM_SALSA20 => {
+                let key = Sha256::digest(key);
+                Ok(InnerStream::Salsa20(Box::new(Salsa20::new(&key, &SALSA20_NONCE.into()))))
+            }
+            INNER_STREAM_CHACHA20 => {
+                let hash = Sha512::digest(key);
+                let cipher = ChaCha20::new_from_slices(&hash[..32], &hash[32..44])
+                    .map_err(|_| "Clé du flux interne invalide".to_string())?;
+                Ok(InnerStream::ChaCha20(Box::new(cipher)))
+            }
+            _ => Err(format!("Flux interne {} non pris en charge", id)),
+        }
+    }
+
+    fn apply(&mut self, data: &mut [u8]) {
+        match self {
+            InnerStream::None => {}
+            InnerStream::Salsa20(cipher) => cipher.apply_keystream(data),
+            InnerStream::ChaCha20(cipher) => cipher.apply_keystream(data),
+        }
+    }
+}
+
+struct ByteReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        ByteReader { data, pos: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self.pos.checked_add(len).filter(|end| *end <= self.data.len());
+        let end = end.ok_or("Fichier KDBX tronqué")?;
+        let slice = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn rest(&self) -> &'a [u8] {
+        &self.data[self.pos..]
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Bases générées avec la crate keepass (Argon2id à 1 Mio pour garder des tests rapides)
+    const ARGON2ID_CHACHA20: &[u8] = include_bytes!("../tests/fixtures/argon2id-chacha20.kdbx");
+    const AESKDF_AES256_KEYFILE: &[u8] = include_bytes!("../tests/fixtures/aeskdf-aes256-keyfile.kdbx");
+    const KEY_FILE: &[u8] = include_bytes!("../tests/fixtures/vaultword.keyx");
+
+    #[test]
+    fn test_parse_kdbx_argon2id_chacha20() {
+        let parsed = parse_kdbx(ARGON2ID_CHACHA20, Some("vaultword"), None).unwrap();
+        assert_eq!(parsed.passwords.len(), 2);
+
+        let mail = parsed.passwords.iter().find(|p| p.site == "Mail").unwrap();
+        assert_eq!(mail.username, "alice");
+        assert_eq!(mail.password, "mdp-mail-123");
+        assert_eq!(mail.url.as_deref(), Some("https://mail.example.com"));
+        assert_eq!(mail.folder, None);
+
+        let db = parsed.passwords.iter().find(|p| p.site == "Database").unwrap();
+        assert_eq!(db.password, "db-secret-456");
+        assert_eq!(db.notes.as_deref(), Some("Base de prod"));
+        assert_eq!(db.folder.as_deref(), Some("Work/Infra"));
+
+        // Le champ otp de KeePassXC est repris comme TOTP de l'entrée
+        assert!(parsed.passwords.iter().any(|p| p.totp.as_deref().is_some_and(|t| t.starts_with("otpauth://"))));
+        assert!(!parsed.warnings.iter().any(|w| w.contains("« otp »")));
+    }
+
+    #[test]
+    fn test_parse_kdbx_aes_kdf_with_key_file() {
+        let parsed = parse_kdbx(AESKDF_AES256_KEYFILE, Some("vaultword"), Some(KEY_FILE)).unwrap();
+        assert_eq!(parsed.passwords.len(), 2);
+
+        // Le fichier clé est indispensable
+        assert!(parse_kdbx(AESKDF_AES256_KEYFILE, Some("vaultword"), None).is_err());
+    }
+
+    #[test]
+    fn test_transform_key_rejects_oversized_parameters() {
+        let composite = [0u8; 32];
+        let aes_kdf = HashMap::from([
+            ("$UUID".to_string(), KDF_AES_KDBX4.to_vec()),
+            ("S".to_string(), vec![0u8; 32]),
+            ("R".to_string(), (MAX_AES_KDF_ROUNDS + 1).to_le_bytes().to_vec()),
+        ]);
+        let err = transform_key(&composite, &aes_kdf).unwrap_err();
+        assert_eq!(err, "Nombre de tours AES-KDF de la base hors limites");
+
+        let argon2 = HashMap::from([
+            ("$UUID".to_string(), KDF_ARGON2ID.to_vec()),
+            ("S".to_string(), vec![0u8; 32]),
+            ("M".to_string(), (1024u64 * 1024).to_le_bytes().to_vec()),
+            ("I".to_string(), (MAX_ARGON2_ITERATIONS + 1).to_le_bytes().to_vec()),
+            ("P".to_string(), 1u32.to_le_bytes().to_vec()),
+            ("V".to_string(), 0x13u32.to_le_bytes().to_vec()),
+        ]);
+        let err = transform_key(&composite, &argon2).unwrap_err();
+        assert_eq!(err, "Paramètres Argon2 de la base hors limites");
+    }
+
+    #[test]
+    fn test_parse_kdbx_rejects_wrong_password() {
+        let err = parse_kdbx(ARGON2ID_CHACHA20, Some("mauvais"), None).unwrap_err();
+        assert_eq!(err, "Mot de passe ou fichier clé incorrect");
+        assert!(parse_kdbx(b"pas une base", Some("vaultword"), None).is_err());
+    }
+
+    #[test]
+    fn test_write_kdbx_round_trip() {
+        let passwords = vec![
+            ImportedPassword {
+                site: "Mail".to_string(),
+                username: "alice".to_string(),
+                password: "mdp-mail-123".to_string(),
+                notes: None,
+                url: Some("https://mail.example.com".to_string()),
+                folder: None,
+                ..ImportedPassword::default()
+            },
+            ImportedPassword {
+                site: "Database".to_string(),
+                username: "admin".to_string(),
+                password: "db-secret-456".to_string(),
+                notes: Some("Base de prod".to_string()),
+                url: None,
+                folder: Some("Work/Infra".to_string()),
+                ..ImportedPassword::default()
+            },
+        ];
+        let kdf = KdfParams { memory_kib: 8 * 1024, iterations: 1, parallelism: 1 };
+
+        let data = write_kdbx(&passwords, "export-password", &kdf).unwrap();
+        assert!(parse_kdbx(&data, Some("mauvais"), None).is_err());
+
+        let parsed = parse_kdbx(&data, Some("export-password"), None).unwrap();
+        assert!(parsed.warnings.is_empty());
+        assert_eq!(parsed.passwords.len(), 2);
+        let db = parsed.passwords.iter().find(|p| p.site == "Database").unwrap();
+        assert_eq!(db.password, "db-secret-456");
+        assert_eq!(db.folder.as_deref(), Some("Work/Infra"));
+        assert_eq!(db.notes.as_deref(), Some("Base de prod"));
+    }
+
+    #[test]
+    fn test_key_file_formats() {
+        let raw = [7u8; 32];
+        assert_eq!(*key_file_key(&raw).unwrap(), raw);
+
+        let hex = "07".repeat(32);
+        assert_eq!(*key_file_key(hex.as_bytes()).unwrap(), raw);
+
+        let xml_v1 = format!(
+            "<KeyFile><Meta><Version>1.00</Version></Meta><Key><Data>{}</Data></Key></KeyFile>",
+            STANDARD.encode(raw)
+        );
+        assert_eq!(*key_file_key(xml_v1.as_bytes()).unwrap(), raw);
+
+        let other = b"n'importe quel fichier";
+        assert_eq!(key_file_key(other).unwrap()[..], Sha256::digest(other)[..]);
+    }
+}
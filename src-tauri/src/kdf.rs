@@ -0,0 +1,133 @@
+use argon2::{Algorithm, Argon2, Params, Version};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+// Bornes appliquées aux paramètres configurés ou calibrés
+const MIN_MEMORY_KIB: u32 = 8 * 1024;
+const MAX_MEMORY_KIB: u32 = 1024 * 1024;
+const MAX_ITERATIONS: u32 = 16;
+const MAX_PARALLELISM: u32 = 4;
+
+pub const DEFAULT_TARGET_MS: u64 = 500;
+
+// Paramètres Argon2id, stockés avec le coffre pour pouvoir re-dériver la clé
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct KdfParams {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Default for KdfParams {
+    // Profil équivalent à Argon2::default(), utilisé par les coffres créés avant le calibrage
+    fn default() -> Self {
+        KdfParams {
+            memory_kib: Params::DEFAULT_M_COST,
+            iterations: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+impl KdfParams {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(MIN_MEMORY_KIB..=MAX_MEMORY_KIB).contains(&self.memory_kib) {
+            return Err(format!(
+                "Mémoire KDF invalide: {} KiB (entre {} et {} KiB)",
+                self.memory_kib, MIN_MEMORY_KIB, MAX_MEMORY_KIB
+            ));
+        }
+        if !(1..=MAX_ITERATIONS).contains(&self.iterations) {
+            return Err(format!("Nombre d'itérations KDF invalide: {}", self.iterations));
+        }
+        if !(1..=MAX_PARALLELISM).contains(&self.parallelism) {
+            return Err(format!("Parallélisme KDF invalide: {}", self.parallelism));
+        }
+        Ok(())
+    }
+
+    pub fn argon2(&self) -> Result<Argon2<'static>, String> {
+        self.validate()?;
+        let params = Params::new(self.memory_kib, self.iterations, self.parallelism, None)
+            .map_err(|e| format!("Paramètres KDF invalides: {}", e))?;
+        Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+    }
+}
+
+// Mesure le temps d'une dérivation avec les paramètres donnés
+fn benchmark(params: &KdfParams) -> Result<Duration, String> {
+    let argon2 = params.argon2()?;
+    let mut output = [0u8; 32];
+    let start = Instant::now();
+    argon2
+        .hash_password_into(b"vaultword-calibration", b"vaultword-salt00", &mut output)
+        .map_err(|e| format!("Erreur lors du calibrage: {}", e))?;
+    Ok(start.elapsed())
+}
+
+// Choisit des paramètres dont la dérivation approche la durée cible sur cette machine :
+// la mémoire est augmentée en priorité, puis les itérations
+pub fn calibrate(target: Duration) -> Result<KdfParams, String> {
+    let parallelism = std::thread::available_parallelism()
+        .map(|n| n.get() as u32)
+        .unwrap_or(1)
+        .min(MAX_PARALLELISM);
+
+    let mut params = KdfParams {
+        memory_kib: MIN_MEMORY_KIB,
+        iterations: KdfParams::default().iterations,
+        parallelism,
+    };
+    let mut elapsed = benchmark(&params)?;
+
+    while elapsed < target && params.memory_kib < MAX_MEMORY_KIB {
+        let previous = params;
+        params.memory_kib = (params.memory_kib * 2).min(MAX_MEMORY_KIB);
+        let next_elapsed = benchmark(&params)?;
+
+        if next_elapsed > target {
+            // Interpolation linéaire entre les deux mesures pour ne pas doubler la cible
+            let ratio = target.as_secs_f64() / elapsed.as_secs_f64().max(f64::EPSILON);
+            params.memory_kib = ((previous.memory_kib as f64 * ratio) as u32)
+                .clamp(previous.memory_kib, params.memory_kib);
+            return Ok(params);
+        }
+        elapsed = next_elapsed;
+    }
+
+    while elapsed < target && params.iterations < MAX_ITERATIONS {
+        params.iterations += 1;
+        elapsed = benchmark(&params)?;
+    }
+
+    Ok(params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_params_match_argon2_default() {
+        let params = KdfParams::default();
+        assert!(params.validate().is_ok());
+        assert_eq!(params.argon2().unwrap().params(), Argon2::default().params());
+    }
+
+    #[test]
+    fn test_invalid_params_are_rejected() {
+        let params = KdfParams { memory_kib: 1024, ..KdfParams::default() };
+        assert!(params.validate().is_err());
+
+        let params = KdfParams { iterations: 0, ..KdfParams::default() };
+        assert!(params.validate().is_err());
+    }
+
+    #[test]
+    fn test_calibrate_with_tiny_target_keeps_minimum_profile() {
+        let params = calibrate(Duration::from_millis(0)).unwrap();
+        assert_eq!(params.memory_kib, MIN_MEMORY_KIB);
+        assert_eq!(params.iterations, KdfParams::default().iterations);
+        assert!(params.validate().is_ok());
+    }
+}
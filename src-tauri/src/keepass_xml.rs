@@ -0,0 +1,442 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rand::RngCore;
+use roxmltree::{Document, Node, NodeId};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::import_export::{extract_domain_from_url, ImportedPassword, ParsedImport};
+
+// Champs d'une entrée KeePass repris tels quels dans ImportedPassword
+const STANDARD_FIELDS: &[&str] = &["Title", "UserName", "Password", "URL", "Notes"];
+
+// Champs où KeePass (TimeOtp-*) et KeePassXC (otp, ou TOTP Seed avant la 2.6) rangent
+// la configuration TOTP
+const TOTP_FIELDS: &[&str] = &[
+    "otp",
+    "TimeOtp-Secret-Base32",
+    "TimeOtp-Period",
+    "TimeOtp-Length",
+    "TimeOtp-Algorithm",
+    "TOTP Seed",
+];
+
+// UUID nul utilisé par KeePass quand la corbeille est désactivée
+const EMPTY_UUID: &str = "AAAAAAAAAAAAAAAAAAAAAA==";
+
+// Applique le flux interne de la base aux valeurs protégées (Protected="True"),
+// pour les chiffrer comme pour les déchiffrer
+pub type ProtectedStream<'a> = dyn FnMut(&mut [u8]) + 'a;
+
+// Convertit le document XML d'une base KeePass 2 en entrées importables.
+// Sans `unprotect`, les valeurs protégées sont lues en clair (export XML de KeePass)
+pub fn parse_keepass_xml(xml: &str, unprotect: Option<&mut ProtectedStream>) -> Result<ParsedImport, String> {
+    let doc = Document::parse(xml).map_err(|e| format!("XML KeePass invalide: {}", e))?;
+    let root = doc.root_element();
+    if !root.has_tag_name("KeePassFile") {
+        return Err("Le fichier n'est pas une base KeePass".to_string());
+    }
+
+    let protected = match unprotect {
+        Some(unprotect) => decrypt_protected_values(&doc, unprotect)?,
+        None => HashMap::new(),
+    };
+
+    let recycle_bin = child(root, "Meta")
+        .and_then(|meta| child_text(meta, "RecycleBinUUID"))
+        .filter(|uuid| uuid != EMPTY_UUID);
+    let root_group = child(root, "Root")
+        .and_then(|node| child(node, "Group"))
+        .ok_or("Base KeePass sans groupe racine")?;
+
+    let mut reader = GroupReader {
+        protected: &protected,
+        recycle_bin: recycle_bin.as_deref(),
+        parsed: ParsedImport::default(),
+    };
+    // Le groupe racine porte le nom de la base : il n'apparaît pas dans les dossiers
+    reader.read_group(root_group, &mut Vec::new());
+    Ok(reader.parsed)
+}
+
+// Les valeurs protégées partagent un seul flux, consommé dans l'ordre du document
+fn decrypt_protected_values(doc: &Document, unprotect: &mut ProtectedStream) -> Result<HashMap<NodeId, String>, String> {
+    let mut values = HashMap::new();
+    for node in doc
+        .descendants()
+        .filter(|n| n.has_tag_name("Value") && n.attribute("Protected") == Some("True"))
+    {
+        let mut raw = STANDARD
+            .decode(node.text().unwrap_or("").trim())
+            .map_err(|e| format!("Valeur protégée invalide: {}", e))?;
+        unprotect(&mut raw);
+        let value = String::from_utf8(raw).map_err(|_| "Valeur protégée illisible (clé incorrecte ?)".to_string())?;
+        values.insert(node.id(), value);
+    }
+    Ok(values)
+}
+
+struct GroupReader<'a> {
+    protected: &'a HashMap<NodeId, String>,
+    recycle_bin: Option<&'a str>,
+    parsed: ParsedImport,
+}
+
+impl GroupReader<'_> {
+    fn read_group(&mut self, group: Node, path: &mut Vec<String>) {
+        for node in group.children().filter(|n| n.is_element()) {
+            match node.tag_name().name() {
+                "Entry" => self.read_entry(node, path),
+                "Group" => {
+                    let name = child_text(node, "Name").unwrap_or_default();
+                    if self.recycle_bin.is_some() && child_text(node, "UUID").as_deref() == self.recycle_bin {
+                        let count = node.descendants().filter(|n| n.has_tag_name("Entry")).count();
+                        if count > 0 {
+                            self.parsed
+                                .warnings
+                                .push(format!("{} entrée(s) de la corbeille « {} » ignorée(s)", count, name));
+                        }
+                        continue;
+                    }
+                    path.push(name);
+                    self.read_group(node, path);
+                    path.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn read_entry(&mut self, entry: Node, path: &[String]) {
+        // L'historique (Entry/History) n'est pas parcouru : seules les valeurs courantes sont importées
+        let mut fields: Vec<(String, String)> = Vec::new();
+        for string in entry.children().filter(|n| n.has_tag_name("String")) {
+            let key = child_text(string, "Key").unwrap_or_default();
+            let value = child(string, "Value")
+                .map(|value| match self.protected.get(&value.id()) {
+                    Some(decrypted) => decrypted.clone(),
+                    None => value.text().unwrap_or("").to_string(),
+                })
+                .unwrap_or_default();
+            fields.push((key, value));
+        }
+        let field = |name: &str| {
+            fields
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+
+        let url = field("URL");
+        let folder = (!path.is_empty()).then(|| path.join("/"));
+        let site = field("Title")
+            .or_else(|| url.as_deref().and_then(extract_domain_from_url))
+            .or_else(|| url.clone())
+            .unwrap_or_default();
+        let label = match &folder {
+            Some(folder) => format!("{}/{}", folder, site),
+            None => site.clone(),
+        };
+
+        // Le mot de passe n'est pas rogné : les espaces peuvent en faire partie
+        let password = fields
+            .iter()
+            .find(|(key, _)| key == "Password")
+            .map(|(_, value)| value.clone())
+            .unwrap_or_default();
+        if password.is_empty() {
+            self.parsed
+                .warnings
+                .push(format!("{}: entrée sans mot de passe ignorée", label));
+            return;
+        }
+
+        let totp = field("otp")
+            .or_else(|| field("TimeOtp-Secret-Base32").map(|secret| keepass_otpauth(&secret, &field)))
+            .or_else(|| field("TOTP Seed"));
+
+        for (key, value) in &fields {
+            let known = STANDARD_FIELDS.contains(&key.as_str()) || TOTP_FIELDS.contains(&key.as_str());
+            if !known && !value.is_empty() {
+                self.parsed
+                    .warnings
+                    .push(format!("{}: champ « {} » non importé", label, key));
+            }
+        }
+        let attachments = entry.children().filter(|n| n.has_tag_name("Binary")).count();
+        if attachments > 0 {
+            self.parsed
+                .warnings
+                .push(format!("{}: {} pièce(s) jointe(s) non importée(s)", label, attachments));
+        }
+        if child_text(entry, "Tags").is_some_and(|tags| !tags.trim().is_empty()) {
+            self.parsed.warnings.push(format!("{}: tags non importés", label));
+        }
+
+        self.parsed.passwords.push(ImportedPassword {
+            site,
+            username: field("UserName").unwrap_or_default(),
+            password,
+            notes: field("Notes"),
+            url,
+            folder,
+            totp,
+            ..ImportedPassword::default()
+        });
+    }
+}
+
+// Secret TOTP natif de KeePass 2.47+ : les paramètres non standard sont conservés dans une
+// URI otpauth, sinon le secret seul suffit
+fn keepass_otpauth(secret: &str, field: &dyn Fn(&str) -> Option<String>) -> String {
+    let params: Vec<String> = [("TimeOtp-Period", "period"), ("TimeOtp-Length", "digits"), ("TimeOtp-Algorithm", "algorithm")]
+        .into_iter()
+        .filter_map(|(key, param)| {
+            // KeePass écrit l'algorithme sous la forme « HMAC-SHA-256 »
+            let value = field(key)?.replace("HMAC-", "").replace('-', "");
+            Some(format!("{}={}", param, value))
+        })
+        .collect();
+    if params.is_empty() {
+        return secret.to_string();
+    }
+    format!("otpauth://totp/?secret={}&{}", secret.replace(' ', ""), params.join("&"))
+}
+
+// Génère le document XML d'une base KeePass : les dossiers deviennent des groupes imbriqués
+// et les mots de passe sont chiffrés par le flux interne, dans l'ordre du document
+pub fn write_keepass_xml(passwords: &[ImportedPassword], database_name: &str, protect: &mut ProtectedStream) -> String {
+    let mut tree = GroupTree::default();
+    for password in passwords {
+        let path = password.folder.as_deref().unwrap_or("").split('/').map(str::trim).filter(|s| !s.is_empty());
+        path.fold(&mut tree, |node, name| node.groups.entry(name.to_string()).or_default())
+            .entries
+            .push(password);
+    }
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\" standalone=\"yes\"?>\n<KeePassFile>\n<Meta>\n");
+    xml.push_str("<Generator>VaultWord</Generator>\n");
+    xml.push_str(&format!("<DatabaseName>{}</DatabaseName>\n", escape_xml(database_name)));
+    xml.push_str("<MemoryProtection><ProtectTitle>False</ProtectTitle><ProtectUserName>False</ProtectUserName>");
+    xml.push_str("<ProtectPassword>True</ProtectPassword><ProtectURL>False</ProtectURL><ProtectNotes>False</ProtectNotes></MemoryProtection>\n");
+    xml.push_str("<RecycleBinEnabled>False</RecycleBinEnabled>\n</Meta>\n<Root>\n");
+    tree.write(&mut xml, database_name, protect);
+    xml.push_str("</Root>\n</KeePassFile>\n");
+    xml
+}
+
+#[derive(Default)]
+struct GroupTree<'a> {
+    entries: Vec<&'a ImportedPassword>,
+    groups: BTreeMap<String, GroupTree<'a>>,
+}
+
+impl GroupTree<'_> {
+    fn write(&self, xml: &mut String, name: &str, protect: &mut ProtectedStream) {
+        xml.push_str(&format!("<Group>\n<UUID>{}</UUID>\n<Name>{}</Name>\n", random_uuid(), escape_xml(name)));
+        for password in &self.entries {
+            xml.push_str(&format!("<Entry>\n<UUID>{}</UUID>\n", random_uuid()));
+            write_string(xml, "Title", &password.site);
+            write_string(xml, "UserName", &password.username);
+
+            let mut protected = password.password.clone().into_bytes();
+            protect(&mut protected);
+            xml.push_str(&format!(
+                "<String><Key>Password</Key><Value Protected=\"True\">{}</Value></String>\n",
+                STANDARD.encode(&protected)
+            ));
+
+            write_string(xml, "URL", password.url.as_deref().unwrap_or(""));
+            write_string(xml, "Notes", password.notes.as_deref().unwrap_or(""));
+            xml.push_str("</Entry>\n");
+        }
+        for (name, group) in &self.groups {
+            group.write(xml, name, protect);
+        }
+        xml.push_str("</Group>\n");
+    }
+}
+
+fn write_string(xml: &mut String, key: &str, value: &str) {
+    xml.push_str(&format!("<String><Key>{}</Key><Value>{}</Value></String>\n", key, escape_xml(value)));
+}
+
+fn random_uuid() -> String {
+    let mut uuid = [0u8; 16];
+    rand::rngs::OsRng.fill_bytes(&mut uuid);
+    STANDARD.encode(uuid)
+}
+
+// Échappe le texte et retire les caractères de contrôle interdits en XML 1.0
+fn escape_xml(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c.is_control() && (c as u32) < 0x20 => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|n| n.has_tag_name(name))
+}
+
+fn child_text(node: Node, name: &str) -> Option<String> {
+    child(node, name).map(|n| n.text().unwrap_or("").to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT_XML: &str = r#"<?xml version="1.0" encoding="utf-8" standalone="yes"?>
+<KeePassFile>
+    <Meta>
+        <RecycleBinUUID>cmVjeWNsZWJpbnV1aWQwMA==</RecycleBinUUID>
+    </Meta>
+    <Root>
+        <Group>
+            <UUID>cm9vdGdyb3VwdXVpZDAwMA==</UUID>
+            <Name>Base</Name>
+            <Entry>
+                <String><Key>Title</Key><Value>Mail</Value></String>
+                <String><Key>UserName</Key><Value>alice</Value></String>
+                <String><Key>Password</Key><Value ProtectInMemory="True"> mdp avec espaces </Value></String>
+                <String><Key>URL</Key><Value>https://mail.example.com</Value></String>
+                <String><Key>Recovery</Key><Value>1234</Value></String>
+                <String><Key>otp</Key><Value>otpauth://totp/Mail:alice?secret=JBSWY3DPEHPK3PXP&amp;period=30</Value></String>
+                <Binary><Key>cle.txt</Key><Value Ref="0"/></Binary>
+                <History>
+                    <Entry>
+                        <String><Key>Title</Key><Value>Ancien</Value></String>
+                        <String><Key>Password</Key><Value>ancien</Value></String>
+                    </Entry>
+                </History>
+            </Entry>
+            <Group>
+                <UUID>d29ya2dyb3VwdXVpZDAwMA==</UUID>
+                <Name>Work</Name>
+                <Group>
+                    <UUID>aW5mcmFncm91cHV1aWQwMA==</UUID>
+                    <Name>Infra</Name>
+                    <Entry>
+                        <String><Key>Title</Key><Value></Value></String>
+                        <String><Key>Password</Key><Value>db-secret</Value></String>
+                        <String><Key>URL</Key><Value>https://db.example.com/admin</Value></String>
+                        <String><Key>Notes</Key><Value>Base de prod</Value></String>
+                        <String><Key>TimeOtp-Secret-Base32</Key><Value>GEZDGNBVGY3TQOJQ</Value></String>
+                        <String><Key>TimeOtp-Algorithm</Key><Value>HMAC-SHA-256</Value></String>
+                    </Entry>
+                    <Entry>
+                        <String><Key>Title</Key><Value>Vide</Value></String>
+                        <String><Key>Password</Key><Value></Value></String>
+                    </Entry>
+                </Group>
+            </Group>
+            <Group>
+                <UUID>cmVjeWNsZWJpbnV1aWQwMA==</UUID>
+                <Name>Corbeille</Name>
+                <Entry>
+                    <String><Key>Title</Key><Value>Supprimé</Value></String>
+                    <String><Key>Password</Key><Value>x</Value></String>
+                </Entry>
+            </Group>
+        </Group>
+    </Root>
+</KeePassFile>"#;
+
+    #[test]
+    fn test_parse_keepass_xml_maps_groups_and_fields() {
+        let parsed = parse_keepass_xml(EXPORT_XML, None).unwrap();
+        assert_eq!(parsed.passwords.len(), 2);
+
+        let mail = &parsed.passwords[0];
+        assert_eq!(mail.site, "Mail");
+        assert_eq!(mail.username, "alice");
+        assert_eq!(mail.password, " mdp avec espaces ");
+        assert_eq!(mail.folder, None);
+        assert_eq!(mail.totp.as_deref(), Some("otpauth://totp/Mail:alice?secret=JBSWY3DPEHPK3PXP&period=30"));
+
+        let db = &parsed.passwords[1];
+        assert_eq!(db.site, "db.example.com");
+        assert_eq!(db.folder.as_deref(), Some("Work/Infra"));
+        assert_eq!(db.notes.as_deref(), Some("Base de prod"));
+        assert_eq!(db.totp.as_deref(), Some("otpauth://totp/?secret=GEZDGNBVGY3TQOJQ&algorithm=SHA256"));
+
+        assert!(parsed.warnings.iter().any(|w| w == "Mail: champ « Recovery » non importé"));
+        assert!(!parsed.warnings.iter().any(|w| w.contains("otp")));
+        assert!(parsed.warnings.iter().any(|w| w.contains("pièce(s) jointe(s)")));
+        assert!(parsed.warnings.iter().any(|w| w == "Work/Infra/Vide: entrée sans mot de passe ignorée"));
+        assert!(parsed.warnings.iter().any(|w| w.contains("corbeille")));
+    }
+
+    #[test]
+    fn test_parse_keepass_xml_applies_unprotect_in_document_order() {
+        let first = STANDARD.encode([b'a' ^ 1, b'b' ^ 2]);
+        let second = STANDARD.encode([b'c' ^ 3]);
+        let xml = format!(
+            r#"<KeePassFile><Root><Group><Name>Base</Name>
+                <Entry>
+                    <String><Key>Title</Key><Value>Un</Value></String>
+                    <String><Key>Password</Key><Value Protected="True">{}</Value></String>
+                </Entry>
+                <Entry>
+                    <String><Key>Title</Key><Value>Deux</Value></String>
+                    <String><Key>Password</Key><Value Protected="True">{}</Value></String>
+                </Entry>
+            </Group></Root></KeePassFile>"#,
+            first, second
+        );
+
+        let mut keystream = [1u8, 2, 3].into_iter();
+        let mut unprotect = |data: &mut [u8]| {
+            for byte in data.iter_mut() {
+                *byte ^= keystream.next().unwrap();
+            }
+        };
+        let parsed = parse_keepass_xml(&xml, Some(&mut unprotect)).unwrap();
+        assert_eq!(parsed.passwords[0].password, "ab");
+        assert_eq!(parsed.passwords[1].password, "c");
+    }
+
+    #[test]
+    fn test_write_keepass_xml_round_trip() {
+        let with_folder = ImportedPassword {
+            site: "Intranet <RH>".to_string(),
+            username: "bob & co".to_string(),
+            password: "secret\u{1}\"'".to_string(),
+            notes: Some("ligne 1\nligne 2".to_string()),
+            url: Some("https://rh.example.com".to_string()),
+            folder: Some("Work/Infra".to_string()),
+            ..ImportedPassword::default()
+        };
+        let root = ImportedPassword { folder: None, site: "Racine".to_string(), ..with_folder.clone() };
+        let passwords = vec![with_folder.clone(), root];
+
+        let xml = write_keepass_xml(&passwords, "Export", &mut |data: &mut [u8]| data.iter_mut().for_each(|b| *b ^= 0x5A));
+        assert!(!xml.contains("secret"));
+
+        let parsed = parse_keepass_xml(&xml, Some(&mut |data: &mut [u8]| data.iter_mut().for_each(|b| *b ^= 0x5A))).unwrap();
+        assert_eq!(parsed.passwords.len(), 2);
+        assert_eq!(parsed.passwords[0].site, "Racine");
+        let nested = &parsed.passwords[1];
+        assert_eq!(nested.site, with_folder.site);
+        assert_eq!(nested.username, with_folder.username);
+        assert_eq!(nested.password, with_folder.password);
+        assert_eq!(nested.notes, with_folder.notes);
+        assert_eq!(nested.folder.as_deref(), Some("Work/Infra"));
+    }
+
+    #[test]
+    fn test_parse_keepass_xml_rejects_other_documents() {
+        assert!(parse_keepass_xml("<html></html>", None).is_err());
+        assert!(parse_keepass_xml("pas du xml", None).is_err());
+    }
+}
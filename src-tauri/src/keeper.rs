@@ -0,0 +1,192 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::import_export::{extract_domain_from_url, ImportedPassword, ParsedImport};
+
+// Import de l'export JSON Keeper : liste « records », les dossiers étant portés par chaque entrée
+
+#[derive(Deserialize)]
+struct Export {
+    records: Vec<Record>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Record {
+    title: String,
+    #[serde(rename = "$type")]
+    record_type: Option<String>,
+    login: String,
+    password: String,
+    login_url: String,
+    notes: String,
+    custom_fields: BTreeMap<String, Value>,
+    folders: Vec<FolderRef>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct FolderRef {
+    shared_folder: Option<String>,
+    folder: Option<String>,
+}
+
+pub fn parse_keeper_json(content: &str) -> Result<ParsedImport, String> {
+    let export: Export = serde_json::from_str(content)
+        .map_err(|e| format!("Le fichier n'est pas un export JSON Keeper: {}", e))?;
+
+    let mut parsed = ParsedImport::default();
+    for record in export.records {
+        let title = record.title.trim().to_string();
+        // Les entrées antérieures aux types Keeper n'ont pas de $type et sont des identifiants
+        if let Some(record_type) = record.record_type.as_deref().filter(|t| *t != "login") {
+            parsed.warnings.push(format!(
+                "{}: élément de type « {} » non importé",
+                title,
+                type_name(record_type)
+            ));
+            continue;
+        }
+
+        let url = Some(record.login_url).filter(|url| !url.trim().is_empty());
+        let site = Some(title)
+            .filter(|title| !title.is_empty())
+            .or_else(|| url.as_deref().and_then(extract_domain_from_url))
+            .unwrap_or_default();
+
+        if record.password.is_empty() {
+            parsed.warnings.push(format!("{}: entrée sans mot de passe ignorée", site));
+            continue;
+        }
+
+        let mut folders = record.folders.iter().filter_map(folder_path);
+        let folder = folders.next();
+        let other_folders = folders.count();
+        if other_folders > 0 {
+            parsed.warnings.push(format!(
+                "{}: {} dossier(s) supplémentaire(s) non importé(s)",
+                site, other_folders
+            ));
+        }
+        for name in record.custom_fields.keys() {
+            parsed.warnings.push(format!("{}: champ « {} » non importé", site, field_name(name)));
+        }
+
+        parsed.passwords.push(ImportedPassword {
+            site,
+            username: record.login,
+            password: record.password,
+            notes: Some(record.notes).filter(|notes| !notes.trim().is_empty()),
+            url,
+            folder,
+            ..ImportedPassword::default()
+        });
+    }
+
+    Ok(parsed)
+}
+
+// Keeper sépare les dossiers imbriqués par « \ » ; un dossier partagé préfixe le chemin
+pub(crate) fn keeper_folder(shared_folder: Option<&str>, folder: Option<&str>) -> Option<String> {
+    let path: Vec<String> = [shared_folder, folder]
+        .into_iter()
+        .flatten()
+        .flat_map(|path| path.split('\\'))
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(str::to_string)
+        .collect();
+    (!path.is_empty()).then(|| path.join("/"))
+}
+
+fn folder_path(folder: &FolderRef) -> Option<String> {
+    keeper_folder(folder.shared_folder.as_deref(), folder.folder.as_deref())
+}
+
+// Les champs typés Keeper sont préfixés (« $oneTimeCode », « $pinCode:PIN »)
+fn field_name(name: &str) -> &str {
+    match name {
+        "TFC:Keeper" | "$oneTimeCode" => "totp",
+        other => other.rsplit(':').next().unwrap_or(other).trim_start_matches('$'),
+    }
+}
+
+fn type_name(record_type: &str) -> &str {
+    match record_type {
+        "encryptedNotes" => "Note sécurisée",
+        "bankCard" => "Carte bancaire",
+        "bankAccount" => "Compte bancaire",
+        "address" => "Adresse",
+        "contact" => "Contact",
+        "birthCertificate" => "Acte de naissance",
+        "driverLicense" => "Permis de conduire",
+        "passport" => "Passeport",
+        "sslCertificate" => "Certificat SSL",
+        "sshKeys" => "Clé SSH",
+        "serverCredentials" => "Serveur",
+        "databaseCredentials" => "Base de données",
+        "wifiCredentials" => "Réseau Wi-Fi",
+        "file" => "Fichier",
+        "photo" => "Photo",
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_keeper_json() {
+        let json_content = r#"{
+            "shared_folders": [{"path": "Team", "manage_users": false}],
+            "records": [
+                {
+                    "uid": "uid-1",
+                    "title": "Example Site",
+                    "$type": "login",
+                    "login": "user@test.com",
+                    "password": "password123",
+                    "login_url": "https://example.com",
+                    "notes": "Test notes",
+                    "custom_fields": {"$oneTimeCode": "otpauth://totp/Example?secret=JBSWY3DPEHPK3PXP"},
+                    "folders": [{"folder": "Work\\Infra"}, {"shared_folder": "Team", "can_edit": true}]
+                },
+                {
+                    "uid": "uid-2",
+                    "title": "Legacy",
+                    "login": "legacy",
+                    "password": "hunter2",
+                    "login_url": ""
+                },
+                {
+                    "uid": "uid-3",
+                    "title": "Carte",
+                    "$type": "bankCard"
+                }
+            ]
+        }"#;
+
+        let parsed = parse_keeper_json(json_content).unwrap();
+        assert_eq!(parsed.passwords.len(), 2);
+
+        let password = &parsed.passwords[0];
+        assert_eq!(password.site, "Example Site");
+        assert_eq!(password.username, "user@test.com");
+        assert_eq!(password.password, "password123");
+        assert_eq!(password.url.as_deref(), Some("https://example.com"));
+        assert_eq!(password.folder.as_deref(), Some("Work/Infra"));
+
+        assert_eq!(parsed.passwords[1].site, "Legacy");
+        assert_eq!(parsed.passwords[1].folder, None);
+
+        assert_eq!(parsed.warnings, vec![
+            "Example Site: 1 dossier(s) supplémentaire(s) non importé(s)".to_string(),
+            "Example Site: champ « totp » non importé".to_string(),
+            "Carte: élément de type « Carte bancaire » non importé".to_string(),
+        ]);
+
+        assert!(parse_keeper_json(r#"{"items": []}"#).is_err());
+    }
+}
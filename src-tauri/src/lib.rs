@@ -2,9 +2,37 @@
 use serde::{Deserialize, Serialize};
 use rand::Rng;
 use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier, password_hash::{rand_core::OsRng, SaltString}};
+use tauri::{Emitter, Manager, State};
+use zeroize::Zeroizing;
 
+mod auto_lock;
+mod bitwarden;
+mod crypto;
+mod csv_mapping;
+mod dashlane;
+mod import_detect;
 mod import_export;
+mod import_stream;
+mod kdbx;
+mod keeper;
+mod kdf;
+mod keepass_xml;
+mod migrations;
+mod onepassword;
+mod pass_store;
+mod password_strength;
+mod protonpass;
+mod session;
+mod vault;
+mod vwx;
 use import_export::*;
+use import_detect::SourceCandidate;
+use import_stream::{ImportCancellation, ImportProgress, ImportStage, IMPORT_PROGRESS_EVENT};
+use csv_mapping::{CsvMapping, CsvPreview};
+use kdf::KdfParams;
+use password_strength::PasswordStrength;
+use session::{AutoLockSettings, VaultSession};
+use vault::{VaultEntry, VaultEntryInput};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PasswordGeneratorOptions {
@@ -67,20 +95,278 @@ async fn verify_password(password: String, hash: String) -> Result<bool, String>
     Ok(argon2.verify_password(password.as_bytes(), &parsed_hash).is_ok())
 }
 
-// Commandes d'import/export
+// Cycle de vie du mot de passe maître
 #[tauri::command]
-async fn parse_import_file(content: String, source: String, file_extension: String) -> Result<Vec<ImportedPassword>, String> {
-    match file_extension.to_lowercase().as_str() {
-        "csv" => parse_csv_content(&content, &source),
-        "json" => {
-            if source == "bitwarden" {
-                parse_bitwarden_json(&content)
-            } else {
-                Err("Format JSON non supporté pour cette source".to_string())
-            }
-        },
-        _ => Err(format!("Extension de fichier non supportée: {}", file_extension))
+async fn is_master_password_set(session: State<'_, VaultSession>) -> Result<bool, String> {
+    session.is_setup()
+}
+
+#[tauri::command]
+async fn setup_master_password(session: State<'_, VaultSession>, master_password: String) -> Result<(), String> {
+    let master_password = Zeroizing::new(master_password);
+    session.setup(&master_password)
+}
+
+#[tauri::command]
+async fn unlock_vault(session: State<'_, VaultSession>, master_password: String) -> Result<(), String> {
+    let master_password = Zeroizing::new(master_password);
+    session.unlock(&master_password)
+}
+
+#[tauri::command]
+async fn lock_vault(app_handle: tauri::AppHandle) -> Result<(), String> {
+    auto_lock::lock_and_notify(&app_handle, "manual")
+}
+
+#[tauri::command]
+async fn is_unlocked(session: State<'_, VaultSession>) -> Result<bool, String> {
+    session.is_unlocked()
+}
+
+#[tauri::command]
+async fn change_master_password(session: State<'_, VaultSession>, old_password: String, new_password: String) -> Result<(), String> {
+    let old_password = Zeroizing::new(old_password);
+    let new_password = Zeroizing::new(new_password);
+    session.change_master_password(&old_password, &new_password)
+}
+
+// Verrouillage automatique
+#[tauri::command]
+async fn record_activity(session: State<'_, VaultSession>) -> Result<(), String> {
+    session.record_activity()
+}
+
+#[tauri::command]
+async fn get_auto_lock_settings(session: State<'_, VaultSession>) -> Result<AutoLockSettings, String> {
+    session.auto_lock_settings()
+}
+
+#[tauri::command]
+async fn set_auto_lock_settings(session: State<'_, VaultSession>, settings: AutoLockSettings) -> Result<(), String> {
+    session.set_auto_lock_settings(settings)
+}
+
+// Calibre Argon2 sur cette machine ; le profil obtenu est enregistré si le coffre est
+// déverrouillé et sera appliqué au prochain déverrouillage
+#[tauri::command]
+async fn calibrate_kdf(session: State<'_, VaultSession>, target_ms: Option<u64>) -> Result<KdfParams, String> {
+    let target = std::time::Duration::from_millis(target_ms.unwrap_or(kdf::DEFAULT_TARGET_MS));
+    let params = kdf::calibrate(target)?;
+
+    if session.is_unlocked()? {
+        session.with_vault(|vault| vault.set_kdf_profile(&params))?;
     }
+
+    Ok(params)
+}
+
+#[tauri::command]
+async fn get_kdf_params(session: State<'_, VaultSession>) -> Result<KdfParams, String> {
+    session.with_vault(|vault| vault.kdf_params())
+}
+
+// Commandes du coffre chiffré
+#[tauri::command]
+async fn vault_add_entry(session: State<'_, VaultSession>, entry: VaultEntryInput) -> Result<VaultEntry, String> {
+    session.with_vault(|vault| vault.add_entry(&entry))
+}
+
+#[tauri::command]
+async fn vault_list_entries(session: State<'_, VaultSession>) -> Result<Vec<VaultEntry>, String> {
+    session.with_vault(|vault| vault.list_entries())
+}
+
+#[tauri::command]
+async fn vault_update_entry(session: State<'_, VaultSession>, id: i64, entry: VaultEntryInput) -> Result<VaultEntry, String> {
+    session.with_vault(|vault| vault.update_entry(id, &entry))
+}
+
+#[tauri::command]
+async fn vault_delete_entry(session: State<'_, VaultSession>, id: i64) -> Result<(), String> {
+    session.with_vault(|vault| vault.delete_entry(id))
+}
+
+// Commandes d'import/export
+// Sources probables du fichier, la plus vraisemblable en premier
+#[tauri::command]
+async fn detect_import_source(content: Vec<u8>, file_extension: String) -> Result<Vec<SourceCandidate>, String> {
+    Ok(import_detect::detect_import_source(&content, &file_extension))
+}
+
+// source vaut « auto » pour s'en remettre à detect_import_source
+// file_password n'est utile qu'aux exports chiffrés (.vwx)
+#[tauri::command]
+async fn parse_import_file(
+    content: String,
+    source: String,
+    file_extension: String,
+    file_password: Option<String>,
+) -> Result<ParsedImport, String> {
+    let file_password = file_password.map(Zeroizing::new);
+    parse_import_bytes(content.as_bytes(), &source, &file_extension, file_password.as_deref().map(|p| p.as_str()), None)
+}
+
+// Parse, valide et enregistre l'import dans le coffre en une seule transaction
+#[tauri::command]
+async fn import_into_vault(
+    session: State<'_, VaultSession>,
+    content: String,
+    source: String,
+    file_extension: String,
+    options: ImportOptions,
+    file_password: Option<String>,
+) -> Result<ImportResult, String> {
+    let file_password = file_password.map(Zeroizing::new);
+    let parsed = parse_import_bytes(content.as_bytes(), &source, &file_extension, file_password.as_deref().map(|p| p.as_str()), None)?;
+    apply_import(&session, parsed, &options)
+}
+
+// Fichiers binaires ou protégés par mot de passe (bases KeePass .kdbx)
+#[tauri::command]
+async fn parse_import_binary(
+    content: Vec<u8>,
+    source: String,
+    file_extension: String,
+    file_password: Option<String>,
+    key_file: Option<Vec<u8>>,
+) -> Result<ParsedImport, String> {
+    let file_password = file_password.map(Zeroizing::new);
+    parse_import_bytes(&content, &source, &file_extension, file_password.as_deref().map(|p| p.as_str()), key_file.as_deref())
+}
+
+#[tauri::command]
+async fn import_binary_into_vault(
+    session: State<'_, VaultSession>,
+    content: Vec<u8>,
+    source: String,
+    file_extension: String,
+    options: ImportOptions,
+    file_password: Option<String>,
+    key_file: Option<Vec<u8>>,
+) -> Result<ImportResult, String> {
+    let file_password = file_password.map(Zeroizing::new);
+    let parsed = parse_import_bytes(&content, &source, &file_extension, file_password.as_deref().map(|p| p.as_str()), key_file.as_deref())?;
+    apply_import(&session, parsed, &options)
+}
+
+// Aperçu des premières lignes d'un CSV converties selon le mapping de l'utilisateur
+#[tauri::command]
+async fn preview_csv_import(content: Vec<u8>, mapping: CsvMapping, limit: Option<usize>) -> Result<CsvPreview, String> {
+    csv_mapping::preview_mapped_csv(&content, &mapping, limit.unwrap_or(csv_mapping::DEFAULT_PREVIEW_ROWS))
+}
+
+#[tauri::command]
+async fn import_mapped_csv_into_vault(
+    session: State<'_, VaultSession>,
+    content: Vec<u8>,
+    mapping: CsvMapping,
+    options: ImportOptions,
+) -> Result<ImportResult, String> {
+    let parsed = csv_mapping::parse_mapped_csv(&content, &mapping)?;
+    apply_import(&session, parsed, &options)
+}
+
+// Fichier choisi côté Rust : les gros exports sont ensuite lus sans passer par l'IPC
+#[tauri::command]
+async fn pick_import_file(app_handle: tauri::AppHandle) -> Result<Option<String>, String> {
+    use tauri_plugin_dialog::DialogExt;
+
+    let file_path = app_handle
+        .dialog()
+        .file()
+        .add_filter("Exports", &["csv", "json", "xml", "txt", "1pux", "1pif", "kdbx", "vwx", "zip"])
+        .blocking_pick_file();
+
+    Ok(file_path
+        .and_then(|path| path.as_path().map(|p| p.to_string_lossy().to_string())))
+}
+
+// Store pass déchiffré, importé ensuite par parse_import_path et import_path_into_vault
+#[tauri::command]
+async fn pick_import_directory(app_handle: tauri::AppHandle) -> Result<Option<String>, String> {
+    use tauri_plugin_dialog::DialogExt;
+
+    let dir_path = app_handle.dialog().file().blocking_pick_folder();
+
+    Ok(dir_path
+        .and_then(|path| path.as_path().map(|p| p.to_string_lossy().to_string())))
+}
+
+#[tauri::command]
+async fn parse_import_path(
+    app_handle: tauri::AppHandle,
+    cancellation: State<'_, ImportCancellation>,
+    path: String,
+    source: String,
+    file_password: Option<String>,
+) -> Result<ParsedImport, String> {
+    cancellation.reset();
+    let file_password = file_password.map(Zeroizing::new);
+    import_stream::parse_import_path(
+        std::path::Path::new(&path),
+        &source,
+        file_password.as_deref().map(|p| p.as_str()),
+        &cancellation,
+        |progress| emit_import_progress(&app_handle, progress),
+    )
+}
+
+// Lit, parse et enregistre un fichier volumineux en signalant la progression au frontend
+#[tauri::command]
+async fn import_path_into_vault(
+    app_handle: tauri::AppHandle,
+    session: State<'_, VaultSession>,
+    cancellation: State<'_, ImportCancellation>,
+    path: String,
+    source: String,
+    options: ImportOptions,
+    file_password: Option<String>,
+) -> Result<ImportResult, String> {
+    cancellation.reset();
+    let file_password = file_password.map(Zeroizing::new);
+    let parsed = import_stream::parse_import_path(
+        std::path::Path::new(&path),
+        &source,
+        file_password.as_deref().map(|p| p.as_str()),
+        &cancellation,
+        |progress| emit_import_progress(&app_handle, progress),
+    )?;
+    // L'enregistrement se fait en une transaction : l'annulation n'est plus possible ensuite
+    cancellation.check()?;
+
+    let rows = parsed.passwords.len();
+    emit_import_progress(&app_handle, ImportProgress { stage: ImportStage::Saving, rows, bytes_read: 0, total_bytes: 0 });
+    let result = apply_import(&session, parsed, &options)?;
+    emit_import_progress(&app_handle, ImportProgress { stage: ImportStage::Done, rows, bytes_read: 0, total_bytes: 0 });
+    Ok(result)
+}
+
+#[tauri::command]
+async fn cancel_import(cancellation: State<'_, ImportCancellation>) -> Result<(), String> {
+    cancellation.cancel();
+    Ok(())
+}
+
+fn emit_import_progress(app_handle: &tauri::AppHandle, progress: ImportProgress) {
+    // Une progression perdue n'interrompt pas l'import
+    let _ = app_handle.emit(IMPORT_PROGRESS_EVENT, progress);
+}
+
+fn apply_import(session: &VaultSession, parsed: ParsedImport, options: &ImportOptions) -> Result<ImportResult, String> {
+    session.with_vault_mut(|vault| {
+        let existing = vault.list_entries()?;
+        let plan = plan_import(parsed, &existing, options);
+        vault.import_batch(&plan.inserts, &plan.updates)?;
+        Ok(plan.result)
+    })
+}
+
+// user_inputs : site, identifiant… que le mot de passe ne devrait pas reprendre
+#[tauri::command]
+async fn estimate_password_strength(password: String, user_inputs: Option<Vec<String>>) -> Result<PasswordStrength, String> {
+    let user_inputs = user_inputs.unwrap_or_default();
+    let user_inputs: Vec<&str> = user_inputs.iter().map(String::as_str).collect();
+    Ok(password_strength::estimate_password_strength(&password, &user_inputs))
 }
 
 #[tauri::command]
@@ -99,7 +385,7 @@ async fn export_passwords_csv(passwords: Vec<ImportedPassword>, include_metadata
     
     // Headers
     if include_metadata {
-        csv_content.push_str("site,username,password,url,notes,folder\n");
+        csv_content.push_str("site,username,password,url,uris,notes,folder\n");
     } else {
         csv_content.push_str("site,username,password\n");
     }
@@ -112,9 +398,11 @@ async fn export_passwords_csv(passwords: Vec<ImportedPassword>, include_metadata
         
         if include_metadata {
             let url = escape_csv_field(&password.url.unwrap_or_default());
+            // Toutes les URIs et leurs règles, une par ligne, relues par l'import générique
+            let uris = escape_csv_field(&format_uri_list(&password.uris));
             let notes = escape_csv_field(&password.notes.unwrap_or_default());
             let folder = escape_csv_field(&password.folder.unwrap_or_default());
-            csv_content.push_str(&format!("{},{},{},{},{},{}\n", site, username, password_field, url, notes, folder));
+            csv_content.push_str(&format!("{},{},{},{},{},{},{}\n", site, username, password_field, url, uris, notes, folder));
         } else {
             csv_content.push_str(&format!("{},{},{}\n", site, username, password_field));
         }
@@ -131,15 +419,78 @@ async fn export_passwords_json(passwords: Vec<ImportedPassword>) -> Result<Strin
     }
 }
 
+// Export JSON au format Bitwarden, pour quitter VaultWord vers un autre gestionnaire
+#[tauri::command]
+async fn export_passwords_bitwarden_json(passwords: Vec<ImportedPassword>) -> Result<String, String> {
+    bitwarden::export_bitwarden_json(&passwords)
+}
+
+// Arborescence pass en clair, écrite dans un dossier choisi par l'utilisateur
+#[tauri::command]
+async fn export_passwords_pass(app_handle: tauri::AppHandle, passwords: Vec<ImportedPassword>) -> Result<String, String> {
+    use tauri_plugin_dialog::DialogExt;
+
+    let dir_path = app_handle
+        .dialog()
+        .file()
+        .blocking_pick_folder()
+        .ok_or("Export annulé par l'utilisateur")?;
+    let dir = dir_path.as_path().ok_or("Dossier d'export invalide")?;
+
+    pass_store::write_pass_tree(&passwords, dir)?;
+    Ok(dir.to_string_lossy().to_string())
+}
+
+// Base KeePass chiffrée, dérivée avec le profil Argon2 du coffre
+#[tauri::command]
+async fn export_passwords_kdbx(
+    session: State<'_, VaultSession>,
+    passwords: Vec<ImportedPassword>,
+    export_password: String,
+) -> Result<Vec<u8>, String> {
+    let export_password = Zeroizing::new(export_password);
+    if export_password.is_empty() {
+        return Err("Un mot de passe est requis pour protéger l'export".to_string());
+    }
+
+    let kdf = session.with_vault(|vault| vault.kdf_params())?;
+    kdbx::write_kdbx(&passwords, &export_password, &kdf)
+}
+
+// Export chiffré VaultWord (.vwx), voir vwx.rs pour le format
+#[tauri::command]
+async fn export_passwords_encrypted(
+    session: State<'_, VaultSession>,
+    passwords: Vec<ImportedPassword>,
+    export_password: String,
+) -> Result<String, String> {
+    let export_password = Zeroizing::new(export_password);
+    if export_password.is_empty() {
+        return Err("Un mot de passe est requis pour protéger l'export".to_string());
+    }
+
+    let kdf = session.with_vault(|vault| vault.kdf_params())?;
+    vwx::encrypt_export(&passwords, &export_password, &kdf)
+}
+
 #[tauri::command]
 async fn save_export_file(app_handle: tauri::AppHandle, content: String, filename: String) -> Result<String, String> {
+    save_with_dialog(&app_handle, content.as_bytes(), &filename)
+}
+
+#[tauri::command]
+async fn save_export_binary_file(app_handle: tauri::AppHandle, content: Vec<u8>, filename: String) -> Result<String, String> {
+    save_with_dialog(&app_handle, &content, &filename)
+}
+
+fn save_with_dialog(app_handle: &tauri::AppHandle, content: &[u8], filename: &str) -> Result<String, String> {
     use tauri_plugin_dialog::DialogExt;
     
     // Ouvrir une boîte de dialogue pour choisir où sauvegarder
     let file_path = app_handle
         .dialog()
         .file()
-        .set_file_name(&filename)
+        .set_file_name(filename)
         .blocking_save_file();
     
     match file_path {
@@ -148,7 +499,7 @@ async fn save_export_file(app_handle: tauri::AppHandle, content: String, filenam
             let path_buf = path.as_path().unwrap();
             
             // Écrire le contenu dans le fichier
-            match std::fs::write(&path_buf, content) {
+            match std::fs::write(path_buf, content) {
                 Ok(_) => Ok(path_buf.to_string_lossy().to_string()),
                 Err(e) => Err(format!("Erreur lors de l'écriture du fichier: {}", e))
             }
@@ -169,20 +520,60 @@ fn escape_csv_field(field: &str) -> String {
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
+        .setup(|app| {
+            let config_dir = app.path().app_config_dir()?;
+            std::fs::create_dir_all(&config_dir)?;
+            app.manage(VaultSession::new(config_dir.join("passwords.db")));
+            app.manage(ImportCancellation::default());
+            auto_lock::spawn_watcher(app.handle().clone());
+            Ok(())
+        })
+        .on_window_event(auto_lock::handle_window_event)
         .plugin(tauri_plugin_opener::init())
-        .plugin(tauri_plugin_sql::Builder::default().build())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .invoke_handler(tauri::generate_handler![
             generate_password,
+            estimate_password_strength,
             hash_password,
             verify_password,
+            is_master_password_set,
+            setup_master_password,
+            unlock_vault,
+            lock_vault,
+            is_unlocked,
+            change_master_password,
+            record_activity,
+            get_auto_lock_settings,
+            set_auto_lock_settings,
+            calibrate_kdf,
+            get_kdf_params,
+            vault_add_entry,
+            vault_list_entries,
+            vault_update_entry,
+            vault_delete_entry,
+            detect_import_source,
             parse_import_file,
+            import_into_vault,
+            parse_import_binary,
+            import_binary_into_vault,
+            preview_csv_import,
+            import_mapped_csv_into_vault,
+            pick_import_file,
+            pick_import_directory,
+            parse_import_path,
+            import_path_into_vault,
+            cancel_import,
             validate_import_data,
             find_import_duplicates,
             export_passwords_csv,
             export_passwords_json,
+            export_passwords_bitwarden_json,
+            export_passwords_pass,
+            export_passwords_kdbx,
+            export_passwords_encrypted,
             save_export_file,
+            save_export_binary_file,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
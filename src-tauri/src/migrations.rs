@@ -0,0 +1,142 @@
+use rusqlite::Connection;
+
+// Migrations du schéma du coffre, appliquées dans l'ordre et suivies via PRAGMA user_version
+struct Migration {
+    version: u32,
+    description: &'static str,
+    sql: &'static str,
+}
+
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "tables du coffre chiffré",
+        // IF NOT EXISTS : les coffres créés avant le suivi des versions ont déjà ces tables
+        sql: "CREATE TABLE IF NOT EXISTS vault_meta (
+                key TEXT PRIMARY KEY,
+                value BLOB NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS vault_entries (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                site BLOB NOT NULL,
+                username BLOB NOT NULL,
+                password BLOB NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );",
+    },
+    Migration {
+        version: 2,
+        description: "url, notes, dossier, tags, favoris et date de modification",
+        sql: "ALTER TABLE vault_entries ADD COLUMN url BLOB;
+            ALTER TABLE vault_entries ADD COLUMN notes BLOB;
+            ALTER TABLE vault_entries ADD COLUMN folder BLOB;
+            ALTER TABLE vault_entries ADD COLUMN tags BLOB;
+            ALTER TABLE vault_entries ADD COLUMN favorite INTEGER NOT NULL DEFAULT 0;
+            ALTER TABLE vault_entries ADD COLUMN updated_at DATETIME;",
+    },
+    Migration {
+        version: 3,
+        description: "champs personnalisés",
+        sql: "ALTER TABLE vault_entries ADD COLUMN custom_fields BLOB;",
+    },
+    Migration {
+        version: 4,
+        description: "URIs multiples avec règle de correspondance",
+        sql: "ALTER TABLE vault_entries ADD COLUMN uris BLOB;",
+    },
+    Migration {
+        version: 5,
+        description: "types d'entrée, TOTP et historique des mots de passe",
+        sql: "ALTER TABLE vault_entries ADD COLUMN entry_type TEXT NOT NULL DEFAULT 'login';
+            ALTER TABLE vault_entries ADD COLUMN totp BLOB;
+            ALTER TABLE vault_entries ADD COLUMN password_history BLOB;",
+    },
+    Migration {
+        version: 6,
+        description: "date de dernière utilisation",
+        sql: "ALTER TABLE vault_entries ADD COLUMN last_used_at DATETIME;",
+    },
+];
+
+pub fn latest_version() -> u32 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+pub fn current_version(conn: &Connection) -> Result<u32, String> {
+    conn.pragma_query_value(None, "user_version", |row| row.get(0))
+        .map_err(|e| format!("Impossible de lire la version du schéma: {}", e))
+}
+
+// Applique chaque migration manquante dans sa propre transaction
+pub fn run(conn: &mut Connection) -> Result<(), String> {
+    let current = current_version(conn)?;
+    if current > latest_version() {
+        return Err(format!(
+            "Ce coffre a été créé par une version plus récente de VaultWord (schéma v{})",
+            current
+        ));
+    }
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+        let tx = conn
+            .transaction()
+            .map_err(|e| format!("Erreur lors de la migration v{}: {}", migration.version, e))?;
+        tx.execute_batch(migration.sql)
+            .map_err(|e| format!("Erreur lors de la migration v{} ({}): {}", migration.version, migration.description, e))?;
+        tx.pragma_update(None, "user_version", migration.version)
+            .map_err(|e| format!("Erreur lors de la migration v{}: {}", migration.version, e))?;
+        tx.commit()
+            .map_err(|e| format!("Erreur lors de la migration v{}: {}", migration.version, e))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrations_are_ordered() {
+        for pair in MIGRATIONS.windows(2) {
+            assert_eq!(pair[1].version, pair[0].version + 1);
+        }
+    }
+
+    #[test]
+    fn test_run_upgrades_unversioned_vault() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        // Schéma d'un coffre créé avant l'introduction des migrations
+        conn.execute_batch(
+            "CREATE TABLE vault_meta (key TEXT PRIMARY KEY, value BLOB NOT NULL);
+            CREATE TABLE vault_entries (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                site BLOB NOT NULL,
+                username BLOB NOT NULL,
+                password BLOB NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+            INSERT INTO vault_entries (site, username, password) VALUES (x'01', x'02', x'03');",
+        )
+        .unwrap();
+
+        run(&mut conn).unwrap();
+        assert_eq!(current_version(&conn).unwrap(), latest_version());
+
+        let favorite: i64 = conn
+            .query_row("SELECT favorite FROM vault_entries", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(favorite, 0);
+
+        // Relancer les migrations ne doit rien modifier
+        run(&mut conn).unwrap();
+        assert_eq!(current_version(&conn).unwrap(), latest_version());
+    }
+
+    #[test]
+    fn test_run_rejects_newer_schema() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "user_version", latest_version() + 1).unwrap();
+        assert!(run(&mut conn).is_err());
+    }
+}
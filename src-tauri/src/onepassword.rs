@@ -0,0 +1,468 @@
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+use zeroize::Zeroizing;
+
+use crate::import_export::{extract_domain_from_url, ImportedPassword, ParsedImport};
+
+// Import des exports 1Password : archive 1PUX (1Password 8) et format historique 1PIF
+
+// Catégories importées comme identifiants : connexion et mot de passe seul
+const CATEGORY_LOGIN: &str = "001";
+const CATEGORY_PASSWORD: &str = "005";
+
+const TYPE_LOGIN: &str = "webforms.WebForm";
+const TYPE_PASSWORD: &str = "passwords.Password";
+const TYPE_FOLDER: &str = "system.folder.Regular";
+const TYPE_TOMBSTONE: &str = "system.Tombstone";
+
+// État 1PUX des éléments archivés, écartés comme ceux de la corbeille du format 1PIF
+const STATE_ARCHIVED: &str = "archived";
+
+// Séparateur des éléments d'un fichier 1PIF
+pub(crate) const PIF_SEPARATOR: &str = "***5642bee8-a5ff-11dc-8314-0800200c9a66***";
+
+// Fichier JSON de l'archive 1PUX
+pub(crate) const EXPORT_DATA_PATH: &str = "export.data";
+
+// Protection contre les archives décompressant un export démesuré
+const MAX_EXPORT_DATA_SIZE: u64 = 256 * 1024 * 1024;
+
+// Identifiant extrait d'un élément 1Password, avant conversion
+#[derive(Default)]
+struct Login {
+    title: String,
+    urls: Vec<String>,
+    username: Option<String>,
+    password: Option<String>,
+    notes: Option<String>,
+    folder: Option<String>,
+    extra_fields: Vec<String>,
+}
+
+pub fn parse_1pux(data: &[u8]) -> Result<ParsedImport, String> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(data)).map_err(|e| format!("Archive 1PUX invalide: {}", e))?;
+    let file = archive
+        .by_name(EXPORT_DATA_PATH)
+        .map_err(|_| "Archive 1PUX sans fichier export.data".to_string())?;
+
+    let mut content = Zeroizing::new(String::new());
+    file.take(MAX_EXPORT_DATA_SIZE)
+        .read_to_string(&mut content)
+        .map_err(|e| format!("Erreur lors de la lecture de l'archive 1PUX: {}", e))?;
+    let export: Value = serde_json::from_str(&content).map_err(|e| format!("export.data invalide: {}", e))?;
+
+    let mut parsed = ParsedImport::default();
+    let mut archived = 0;
+    let accounts = export
+        .get("accounts")
+        .and_then(Value::as_array)
+        .ok_or("export.data sans comptes 1Password")?;
+
+    for vault in accounts.iter().flat_map(|account| array(account, "vaults")) {
+        let vault_name = str_at(vault, &["attrs", "name"]);
+
+        for item in array(vault, "items") {
+            if item.get("state").and_then(Value::as_str) == Some(STATE_ARCHIVED) {
+                archived += 1;
+                continue;
+            }
+
+            let title = str_at(item, &["overview", "title"]).unwrap_or_default();
+            let category = item.get("categoryUuid").and_then(Value::as_str).unwrap_or("");
+            if category != CATEGORY_LOGIN && category != CATEGORY_PASSWORD {
+                parsed.warnings.push(format!(
+                    "{}: élément de catégorie « {} » non importé",
+                    title,
+                    category_name(category)
+                ));
+                continue;
+            }
+
+            let details = item.get("details").unwrap_or(&Value::Null);
+            let mut login = Login {
+                title,
+                folder: vault_name.clone(),
+                notes: non_empty(details.get("notesPlain")),
+                password: non_empty(details.get("password")),
+                ..Login::default()
+            };
+
+            login.urls.extend(str_at(item, &["overview", "url"]));
+            for url in array(item.get("overview").unwrap_or(&Value::Null), "urls") {
+                if let Some(url) = non_empty(url.get("url")) {
+                    if !login.urls.contains(&url) {
+                        login.urls.push(url);
+                    }
+                }
+            }
+
+            read_login_fields(&mut login, array(details, "loginFields"));
+            for field in array(details, "sections").iter().flat_map(|section| array(section, "fields")) {
+                if field.get("value").is_some_and(has_value) {
+                    let name = non_empty(field.get("title")).or_else(|| non_empty(field.get("id")));
+                    login.extra_fields.push(name.unwrap_or_default());
+                }
+            }
+
+            push_login(&mut parsed, login);
+        }
+    }
+
+    if archived > 0 {
+        parsed
+            .warnings
+            .push(format!("{} élément(s) archivé(s) ignoré(s)", archived));
+    }
+
+    Ok(parsed)
+}
+
+pub fn parse_1pif(content: &str) -> Result<ParsedImport, String> {
+    let mut items = Vec::new();
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with("***") {
+            continue;
+        }
+        let item: Value = serde_json::from_str(line).map_err(|e| format!("Élément 1PIF invalide: {}", e))?;
+        items.push(item);
+    }
+    if items.is_empty() && !content.contains(PIF_SEPARATOR) {
+        return Err("Le fichier n'est pas un export 1PIF".to_string());
+    }
+
+    // Les dossiers sont des éléments à part entière, référencés par folderUuid
+    let folders: HashMap<&str, &Value> = items
+        .iter()
+        .filter(|item| item.get("typeName").and_then(Value::as_str) == Some(TYPE_FOLDER))
+        .filter_map(|item| Some((item.get("uuid")?.as_str()?, item)))
+        .collect();
+
+    let mut parsed = ParsedImport::default();
+    let mut trashed = 0;
+
+    for item in &items {
+        let type_name = item.get("typeName").and_then(Value::as_str).unwrap_or("");
+        if type_name == TYPE_FOLDER {
+            continue;
+        }
+        if type_name == TYPE_TOMBSTONE || item.get("trashed").and_then(Value::as_bool) == Some(true) {
+            trashed += 1;
+            continue;
+        }
+
+        let title = non_empty(item.get("title")).unwrap_or_default();
+        if type_name != TYPE_LOGIN && type_name != TYPE_PASSWORD {
+            parsed.warnings.push(format!(
+                "{}: élément de type « {} » non importé",
+                title,
+                pif_type_name(type_name)
+            ));
+            continue;
+        }
+
+        let contents = item.get("secureContents").unwrap_or(&Value::Null);
+        let mut login = Login {
+            title,
+            folder: folder_path(&folders, item),
+            notes: non_empty(contents.get("notesPlain")),
+            password: non_empty(contents.get("password")),
+            ..Login::default()
+        };
+
+        login.urls.extend(non_empty(item.get("location")));
+        for url in array(contents, "URLs") {
+            if let Some(url) = non_empty(url.get("url")) {
+                if !login.urls.contains(&url) {
+                    login.urls.push(url);
+                }
+            }
+        }
+
+        read_login_fields(&mut login, array(contents, "fields"));
+        for field in array(contents, "sections").iter().flat_map(|section| array(section, "fields")) {
+            if field.get("v").is_some_and(has_value) {
+                let name = non_empty(field.get("t")).or_else(|| non_empty(field.get("n")));
+                login.extra_fields.push(name.unwrap_or_default());
+            }
+        }
+
+        push_login(&mut parsed, login);
+    }
+
+    if trashed > 0 {
+        parsed
+            .warnings
+            .push(format!("{} élément(s) de la corbeille ignoré(s)", trashed));
+    }
+
+    Ok(parsed)
+}
+
+// Champs de formulaire : seuls l'identifiant et le mot de passe désignés sont repris
+fn read_login_fields(login: &mut Login, fields: &[Value]) {
+    for field in fields {
+        let Some(value) = non_empty(field.get("value")) else {
+            continue;
+        };
+        match field.get("designation").and_then(Value::as_str) {
+            Some("username") if login.username.is_none() => login.username = Some(value),
+            Some("password") if login.password.is_none() => login.password = Some(value),
+            _ => login
+                .extra_fields
+                .push(non_empty(field.get("name")).unwrap_or_default()),
+        }
+    }
+}
+
+fn push_login(parsed: &mut ParsedImport, login: Login) {
+    let url = login.urls.first().cloned();
+    let site = Some(login.title.trim().to_string())
+        .filter(|title| !title.is_empty())
+        .or_else(|| url.as_deref().and_then(extract_domain_from_url))
+        .or_else(|| url.clone())
+        .unwrap_or_default();
+
+    let Some(password) = login.password else {
+        parsed.warnings.push(format!("{}: entrée sans mot de passe ignorée", site));
+        return;
+    };
+
+    for field in &login.extra_fields {
+        parsed.warnings.push(format!("{}: champ « {} » non importé", site, field));
+    }
+
+    parsed.passwords.push(ImportedPassword {
+        site,
+        username: login.username.unwrap_or_default(),
+        password,
+        notes: login.notes,
+        folder: login.folder,
+        ..ImportedPassword::default()
+    }.with_uris(login.urls));
+}
+
+// Chemin du dossier 1PIF, dossiers parents compris (Work/Infra)
+fn folder_path(folders: &HashMap<&str, &Value>, item: &Value) -> Option<String> {
+    let mut path = Vec::new();
+    let mut current = item.get("folderUuid").and_then(Value::as_str);
+    // La profondeur est bornée pour ne pas boucler sur un export incohérent
+    while let Some(folder) = current.and_then(|uuid| folders.get(uuid)).filter(|_| path.len() < 32) {
+        path.push(non_empty(folder.get("title")).unwrap_or_default());
+        current = folder.get("folderUuid").and_then(Value::as_str);
+    }
+    path.reverse();
+    (!path.is_empty()).then(|| path.join("/"))
+}
+
+fn category_name(uuid: &str) -> &str {
+    match uuid {
+        "002" => "Carte bancaire",
+        "003" => "Note sécurisée",
+        "004" => "Identité",
+        "006" => "Document",
+        "100" => "Licence logicielle",
+        "101" => "Compte bancaire",
+        "102" => "Base de données",
+        "103" => "Permis de conduire",
+        "104" => "Permis de chasse et pêche",
+        "105" => "Adhésion",
+        "106" => "Passeport",
+        "107" => "Programme de fidélité",
+        "108" => "Numéro de sécurité sociale",
+        "109" => "Routeur sans fil",
+        "110" => "Serveur",
+        "111" => "Compte e-mail",
+        "112" => "Identifiant d'API",
+        "113" => "Dossier médical",
+        "114" => "Clé SSH",
+        "115" => "Portefeuille crypto",
+        other => other,
+    }
+}
+
+fn pif_type_name(type_name: &str) -> &str {
+    match type_name {
+        "securenotes.SecureNote" => "Note sécurisée",
+        "wallet.financial.CreditCard" => "Carte bancaire",
+        "wallet.financial.BankAccountUS" => "Compte bancaire",
+        "identities.Identity" => "Identité",
+        "wallet.computer.License" => "Licence logicielle",
+        "wallet.computer.Database" => "Base de données",
+        "wallet.computer.UnixServer" => "Serveur",
+        "wallet.computer.Router" => "Routeur sans fil",
+        "wallet.government.Passport" => "Passeport",
+        "wallet.government.DriversLicense" => "Permis de conduire",
+        other => other,
+    }
+}
+
+fn array<'a>(value: &'a Value, key: &str) -> &'a [Value] {
+    value.get(key).and_then(Value::as_array).map(Vec::as_slice).unwrap_or(&[])
+}
+
+fn str_at(value: &Value, path: &[&str]) -> Option<String> {
+    non_empty(path.iter().try_fold(value, |node, key| node.get(key)))
+}
+
+fn non_empty(value: Option<&Value>) -> Option<String> {
+    value
+        .and_then(Value::as_str)
+        .filter(|s| !s.trim().is_empty())
+        .map(str::to_string)
+}
+
+// Valeur de champ de section 1Password : chaîne, date, ou objet typé ({"concealed": ".."})
+fn has_value(value: &Value) -> bool {
+    match value {
+        Value::String(s) => !s.trim().is_empty(),
+        Value::Number(_) => true,
+        Value::Object(map) => map.values().any(has_value),
+        Value::Array(items) => items.iter().any(has_value),
+        Value::Bool(_) | Value::Null => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const EXPORT_DATA: &str = r#"{
+        "accounts": [{
+            "attrs": { "accountName": "Alice", "name": "Alice" },
+            "vaults": [{
+                "attrs": { "uuid": "v1", "name": "Personal" },
+                "items": [
+                    {
+                        "uuid": "i1",
+                        "categoryUuid": "001",
+                        "state": "active",
+                        "details": {
+                            "loginFields": [
+                                { "value": "alice@example.com", "name": "email", "fieldType": "E", "designation": "username" },
+                                { "value": "s3cret-pass", "name": "password", "fieldType": "P", "designation": "password" },
+                                { "value": "on", "name": "remember", "fieldType": "C" }
+                            ],
+                            "notesPlain": "Compte principal",
+                            "sections": [{
+                                "title": "",
+                                "fields": [
+                                    { "title": "one-time password", "id": "totp", "value": { "totp": "otpauth://totp/x?secret=ABC" } },
+                                    { "title": "vide", "id": "vide", "value": { "string": "" } }
+                                ]
+                            }]
+                        },
+                        "overview": {
+                            "title": "Example",
+                            "url": "https://example.com/login",
+                            "urls": [
+                                { "label": "", "url": "https://example.com/login" },
+                                { "label": "", "url": "https://app.example.com" }
+                            ]
+                        }
+                    },
+                    {
+                        "uuid": "i2",
+                        "categoryUuid": "005",
+                        "details": { "password": "standalone-pass" },
+                        "overview": { "title": "Wi-Fi maison" }
+                    },
+                    {
+                        "uuid": "i4",
+                        "categoryUuid": "001",
+                        "state": "archived",
+                        "details": {
+                            "loginFields": [
+                                { "value": "old-pass", "name": "password", "fieldType": "P", "designation": "password" }
+                            ]
+                        },
+                        "overview": { "title": "Ancien compte" }
+                    },
+                    {
+                        "uuid": "i3",
+                        "categoryUuid": "002",
+                        "details": {},
+                        "overview": { "title": "Visa" }
+                    }
+                ]
+            }]
+        }]
+    }"#;
+
+    fn build_1pux(export_data: &str) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        writer
+            .start_file("export.attributes", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(br#"{"version":3}"#).unwrap();
+        writer
+            .start_file("export.data", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(export_data.as_bytes()).unwrap();
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_parse_1pux() {
+        let parsed = parse_1pux(&build_1pux(EXPORT_DATA)).unwrap();
+        assert_eq!(parsed.passwords.len(), 2);
+
+        let login = &parsed.passwords[0];
+        assert_eq!(login.site, "Example");
+        assert_eq!(login.username, "alice@example.com");
+        assert_eq!(login.password, "s3cret-pass");
+        assert_eq!(login.url.as_deref(), Some("https://example.com/login"));
+        assert_eq!(login.uris.len(), 2);
+        assert_eq!(login.notes.as_deref(), Some("Compte principal"));
+        assert_eq!(login.folder.as_deref(), Some("Personal"));
+
+        let standalone = &parsed.passwords[1];
+        assert_eq!(standalone.password, "standalone-pass");
+        assert_eq!(standalone.username, "");
+
+        let warnings = &parsed.warnings;
+        assert!(warnings.contains(&"Visa: élément de catégorie « Carte bancaire » non importé".to_string()));
+        assert!(warnings.contains(&"1 élément(s) archivé(s) ignoré(s)".to_string()));
+        assert!(!parsed.passwords.iter().any(|p| p.site == "Ancien compte"));
+        assert!(warnings.contains(&"Example: champ « one-time password » non importé".to_string()));
+        assert!(warnings.contains(&"Example: champ « remember » non importé".to_string()));
+        assert!(!warnings.iter().any(|w| w.contains("vide")));
+    }
+
+    #[test]
+    fn test_parse_1pux_rejects_invalid_archives() {
+        assert!(parse_1pux(b"pas une archive").is_err());
+        assert!(parse_1pux(&build_1pux("{}")).is_err());
+    }
+
+    #[test]
+    fn test_parse_1pif() {
+        let content = [
+            r#"{"uuid":"f1","typeName":"system.folder.Regular","title":"Work"}"#,
+            PIF_SEPARATOR,
+            r#"{"uuid":"f2","typeName":"system.folder.Regular","title":"Infra","folderUuid":"f1"}"#,
+            PIF_SEPARATOR,
+            r#"{"uuid":"a","typeName":"webforms.WebForm","title":"Serveur DB","location":"https://db.example.com","folderUuid":"f2","secureContents":{"fields":[{"designation":"username","name":"user","value":"admin","type":"T"},{"designation":"password","name":"pass","value":"db-secret","type":"P"}],"notesPlain":"prod","URLs":[{"label":"website","url":"https://db.example.com"}]}}"#,
+            PIF_SEPARATOR,
+            r#"{"uuid":"b","typeName":"securenotes.SecureNote","title":"Codes","secureContents":{"notesPlain":"1234"}}"#,
+            PIF_SEPARATOR,
+            r#"{"uuid":"c","typeName":"webforms.WebForm","title":"Ancien","trashed":true,"secureContents":{"fields":[]}}"#,
+            PIF_SEPARATOR,
+        ]
+        .join("\n");
+
+        let parsed = parse_1pif(&content).unwrap();
+        assert_eq!(parsed.passwords.len(), 1);
+
+        let login = &parsed.passwords[0];
+        assert_eq!(login.site, "Serveur DB");
+        assert_eq!(login.username, "admin");
+        assert_eq!(login.password, "db-secret");
+        assert_eq!(login.folder.as_deref(), Some("Work/Infra"));
+        assert_eq!(login.notes.as_deref(), Some("prod"));
+
+        assert!(parsed.warnings.contains(&"Codes: élément de type « Note sécurisée » non importé".to_string()));
+        assert!(parsed.warnings.contains(&"1 élément(s) de la corbeille ignoré(s)".to_string()));
+    }
+}
@@ -0,0 +1,406 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use zeroize::Zeroizing;
+
+use crate::import_export::{ImportedPassword, ParsedImport};
+
+// Import et export des stores pass (password-store). Les fichiers .gpg exigent le trousseau
+// de l'utilisateur : seuls un store déjà déchiffré ou un dump de `pass show` sont lus, par
+// exemple produit par :
+//   cd ~/.password-store && find . -name '*.gpg' | sed 's|^\./||; s|\.gpg$||' |
+//     while read -r e; do echo "=== $e ==="; pass show "$e"; done > pass-dump.txt
+// Chaque entrée suit la convention pass : mot de passe en première ligne, puis des lignes
+// « clé: valeur » ; les autres lignes forment les notes
+
+// Ligne « === chemin/de/l'entrée === » ouvrant chaque entrée d'un dump
+const DUMP_MARKER: &str = "===";
+
+const USERNAME_KEYS: &[&str] = &["login", "username", "user", "email"];
+const URL_KEYS: &[&str] = &["url", "website", "site"];
+const TOTP_KEYS: &[&str] = &["otp", "totp"];
+
+// Au-delà, le fichier n'est pas une entrée pass mais un binaire rangé dans le store
+const MAX_ENTRY_SIZE: u64 = 1024 * 1024;
+
+// Parcourt un store déchiffré : le chemin de chaque fichier devient dossier et nom de l'entrée.
+// on_entry reçoit le nombre de fichiers lus et interrompt le parcours en renvoyant une erreur
+pub fn parse_pass_directory(
+    root: &Path,
+    mut on_entry: impl FnMut(usize) -> Result<(), String>,
+) -> Result<ParsedImport, String> {
+    let mut walker = StoreWalker { parsed: ParsedImport::default(), files: 0, encrypted: 0 };
+    walker.walk(root, &mut Vec::new(), &mut on_entry)?;
+
+    let mut parsed = walker.parsed;
+    if walker.encrypted > 0 {
+        parsed.warnings.push(format!(
+            "{} fichier(s) chiffré(s) GPG ignoré(s) : déchiffrez le store avant l'import",
+            walker.encrypted
+        ));
+    }
+    Ok(parsed)
+}
+
+struct StoreWalker {
+    parsed: ParsedImport,
+    files: usize,
+    encrypted: usize,
+}
+
+impl StoreWalker {
+    fn walk(
+        &mut self,
+        dir: &Path,
+        path: &mut Vec<String>,
+        on_entry: &mut impl FnMut(usize) -> Result<(), String>,
+    ) -> Result<(), String> {
+        let mut entries = fs::read_dir(dir)
+            .map_err(|e| format!("Impossible de lire le dossier {}: {}", dir.display(), e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Impossible de lire le dossier {}: {}", dir.display(), e))?;
+        // Ordre stable d'une plateforme à l'autre
+        entries.sort_by_key(|entry| entry.file_name());
+
+        for entry in entries {
+            let name = entry.file_name().to_string_lossy().to_string();
+            // .git, .gpg-id et .extensions appartiennent au fonctionnement du store
+            if name.starts_with('.') {
+                continue;
+            }
+            let file_type = entry
+                .file_type()
+                .map_err(|e| format!("Impossible de lire {}: {}", entry.path().display(), e))?;
+
+            if file_type.is_dir() {
+                path.push(name);
+                self.walk(&entry.path(), path, on_entry)?;
+                path.pop();
+            } else if file_type.is_file() {
+                self.files += 1;
+                on_entry(self.files)?;
+                self.read_file(&entry.path(), &name, path);
+            }
+        }
+        Ok(())
+    }
+
+    fn read_file(&mut self, file: &Path, name: &str, folder: &[String]) {
+        if name.ends_with(".gpg") {
+            self.encrypted += 1;
+            return;
+        }
+        let entry_path = folder
+            .iter()
+            .map(String::as_str)
+            .chain([name.strip_suffix(".txt").unwrap_or(name)])
+            .collect::<Vec<_>>()
+            .join("/");
+
+        if fs::metadata(file).map(|m| m.len()).unwrap_or(0) > MAX_ENTRY_SIZE {
+            self.parsed.warnings.push(format!("{}: fichier trop volumineux ignoré", entry_path));
+            return;
+        }
+        let content = match fs::read(file) {
+            Ok(content) => Zeroizing::new(content),
+            Err(e) => {
+                self.parsed.warnings.push(format!("{}: fichier illisible ignoré ({})", entry_path, e));
+                return;
+            }
+        };
+        match std::str::from_utf8(&content) {
+            Ok(text) => push_entry(&mut self.parsed, &entry_path, text),
+            Err(_) => self.parsed.warnings.push(format!("{}: fichier non textuel ignoré", entry_path)),
+        }
+    }
+}
+
+// Dump texte de plusieurs `pass show`, chaque entrée précédée de sa ligne « === chemin === »
+pub fn parse_pass_dump(content: &str) -> Result<ParsedImport, String> {
+    let mut parsed = ParsedImport::default();
+    let mut current: Option<(&str, Vec<&str>)> = None;
+
+    for line in content.lines() {
+        if let Some(entry_path) = dump_marker(line) {
+            if let Some((path, lines)) = current.take() {
+                push_entry(&mut parsed, path, &Zeroizing::new(lines.join("\n")));
+            }
+            current = Some((entry_path, Vec::new()));
+        } else if let Some((_, lines)) = current.as_mut() {
+            lines.push(line);
+        } else if !line.trim().is_empty() {
+            return Err("Dump pass invalide : chaque entrée doit commencer par une ligne « === chemin === »".to_string());
+        }
+    }
+    if let Some((path, lines)) = current {
+        push_entry(&mut parsed, path, &Zeroizing::new(lines.join("\n")));
+    }
+
+    Ok(parsed)
+}
+
+pub(crate) fn dump_marker(line: &str) -> Option<&str> {
+    line.trim()
+        .strip_prefix(DUMP_MARKER)?
+        .strip_suffix(DUMP_MARKER)
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+}
+
+fn push_entry(parsed: &mut ParsedImport, entry_path: &str, content: &str) {
+    match parse_pass_entry(entry_path, content) {
+        Some(password) => parsed.passwords.push(password),
+        None => parsed.warnings.push(format!("{}: entrée sans mot de passe ignorée", entry_path)),
+    }
+}
+
+fn parse_pass_entry(entry_path: &str, content: &str) -> Option<ImportedPassword> {
+    let mut segments: Vec<&str> = entry_path.split('/').map(str::trim).filter(|s| !s.is_empty()).collect();
+    let site = segments.pop()?.to_string();
+    let folder = (!segments.is_empty()).then(|| segments.join("/"));
+
+    let mut lines = content.lines();
+    // Le mot de passe n'est pas rogné : les espaces peuvent en faire partie
+    let password = lines.next().filter(|line| !line.is_empty())?.to_string();
+
+    let mut username = String::new();
+    let mut urls = Vec::new();
+    let mut totp = None;
+    let mut notes = Vec::new();
+    for line in lines {
+        if line.trim().starts_with("otpauth://") && totp.is_none() {
+            totp = Some(line.trim().to_string());
+            continue;
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            let key = key.trim().to_lowercase();
+            let value = value.trim();
+            if !value.is_empty() {
+                if USERNAME_KEYS.contains(&key.as_str()) && username.is_empty() {
+                    username = value.to_string();
+                    continue;
+                }
+                if URL_KEYS.contains(&key.as_str()) {
+                    urls.push(value.to_string());
+                    continue;
+                }
+                if TOTP_KEYS.contains(&key.as_str()) && totp.is_none() {
+                    totp = Some(value.to_string());
+                    continue;
+                }
+            }
+        }
+        notes.push(line);
+    }
+    let notes = notes.join("\n").trim().to_string();
+
+    Some(ImportedPassword {
+        site,
+        username,
+        password,
+        notes: (!notes.is_empty()).then_some(notes),
+        folder,
+        totp,
+        ..ImportedPassword::default()
+    }.with_uris(urls))
+}
+
+// Écrit une entrée par fichier, dans l'arborescence des dossiers, au format lu par
+// `pass insert -m` :
+//   find . -type f | while read -r f; do pass insert -m "${f#./}" < "$f"; done
+// Les fichiers existants ne sont jamais écrasés
+pub fn write_pass_tree(passwords: &[ImportedPassword], root: &Path) -> Result<usize, String> {
+    let mut taken = HashSet::new();
+    for password in passwords {
+        let folder: Vec<String> = password
+            .folder
+            .as_deref()
+            .unwrap_or("")
+            .split('/')
+            .map(file_name)
+            .filter(|segment| !segment.is_empty())
+            .collect();
+        let dir = folder.iter().fold(root.to_path_buf(), |dir, segment| dir.join(segment));
+        let name = unique_name(&mut taken, &folder.join("/"), &file_name(&password.site), &password.username);
+
+        fs::create_dir_all(&dir).map_err(|e| format!("Impossible de créer le dossier {}: {}", dir.display(), e))?;
+        let file = dir.join(&name);
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        options
+            .open(&file)
+            .and_then(|mut f| f.write_all(format_pass_entry(password).as_bytes()))
+            .map_err(|e| format!("Impossible d'écrire {}: {}", file.display(), e))?;
+    }
+    Ok(passwords.len())
+}
+
+fn format_pass_entry(password: &ImportedPassword) -> Zeroizing<String> {
+    let mut content = Zeroizing::new(format!("{}\n", password.password));
+    if !password.username.is_empty() {
+        content.push_str(&format!("login: {}\n", password.username));
+    }
+    let uris = password.uris.iter().map(|uri| uri.uri.as_str());
+    let url = password.url.as_deref().filter(|url| !password.uris.iter().any(|uri| uri.uri == *url));
+    for uri in url.into_iter().chain(uris) {
+        content.push_str(&format!("url: {}\n", uri));
+    }
+    match password.totp.as_deref() {
+        Some(totp) if totp.starts_with("otpauth://") => content.push_str(&format!("{}\n", totp)),
+        Some(totp) => content.push_str(&format!("totp: {}\n", totp)),
+        None => {}
+    }
+    for field in &password.custom_fields {
+        content.push_str(&format!("{}: {}\n", field.name, field.value));
+    }
+    if let Some(notes) = password.notes.as_deref().filter(|notes| !notes.trim().is_empty()) {
+        content.push_str(notes);
+        content.push('\n');
+    }
+    content
+}
+
+// Nom de fichier sûr : ni séparateur, ni caractère de contrôle, ni fichier caché
+fn file_name(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| if c == '/' || c == '\\' || c.is_control() { '_' } else { c })
+        .collect::<String>()
+        .trim()
+        .trim_start_matches('.')
+        .to_string()
+}
+
+// Deux entrées d'un même dossier et d'un même site se distinguent par leur identifiant
+fn unique_name(taken: &mut HashSet<String>, folder: &str, site: &str, username: &str) -> String {
+    let site = if site.is_empty() { "sans-nom".to_string() } else { site.to_string() };
+    let candidates = std::iter::once(site.clone())
+        .chain((!username.is_empty()).then(|| format!("{} ({})", site, file_name(username))))
+        .chain((2..).map(|n| format!("{} {}", site, n)));
+    for name in candidates {
+        if taken.insert(format!("{}/{}", folder, name)) {
+            return name;
+        }
+    }
+    unreachable!()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vault::CustomField;
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("vaultword-pass-{}", name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_parse_pass_directory() {
+        let root = temp_dir("import");
+        fs::create_dir_all(root.join("Work/Infra")).unwrap();
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::write(root.join(".gpg-id"), "alice@example.com\n").unwrap();
+        fs::write(
+            root.join("Work/Infra/db.example.com"),
+            " pg secret \nlogin: admin\nurl: https://db.example.com\notpauth://totp/db?secret=JBSWY3DPEHPK3PXP\nPort: 5432\nBase de prod\n",
+        )
+        .unwrap();
+        fs::write(root.join("mail.txt"), "hunter22\nemail: alice@example.com\n").unwrap();
+        fs::write(root.join("vide"), "\nlogin: personne\n").unwrap();
+        fs::write(root.join("chiffre.gpg"), [0x85, 0x01]).unwrap();
+
+        let mut files = 0;
+        let parsed = parse_pass_directory(&root, |count| {
+            files = count;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(files, 4);
+        assert_eq!(parsed.passwords.len(), 2);
+
+        let db = &parsed.passwords[0];
+        assert_eq!(db.site, "db.example.com");
+        assert_eq!(db.folder.as_deref(), Some("Work/Infra"));
+        assert_eq!(db.password, " pg secret ");
+        assert_eq!(db.username, "admin");
+        assert_eq!(db.url.as_deref(), Some("https://db.example.com"));
+        assert_eq!(db.totp.as_deref(), Some("otpauth://totp/db?secret=JBSWY3DPEHPK3PXP"));
+        assert_eq!(db.notes.as_deref(), Some("Port: 5432\nBase de prod"));
+
+        let mail = &parsed.passwords[1];
+        assert_eq!(mail.site, "mail");
+        assert_eq!(mail.folder, None);
+        assert_eq!(mail.username, "alice@example.com");
+
+        assert_eq!(parsed.warnings, vec![
+            "vide: entrée sans mot de passe ignorée".to_string(),
+            "1 fichier(s) chiffré(s) GPG ignoré(s) : déchiffrez le store avant l'import".to_string(),
+        ]);
+
+        let err = parse_pass_directory(&root, |_| Err("Import annulé".to_string())).unwrap_err();
+        assert_eq!(err, "Import annulé");
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_parse_pass_dump() {
+        let dump = "=== Perso/github.com ===\nghp-secret\nusername: alice\n\n=== banque ===\n1234\n";
+
+        let parsed = parse_pass_dump(dump).unwrap();
+        assert_eq!(parsed.passwords.len(), 2);
+        assert_eq!(parsed.passwords[0].site, "github.com");
+        assert_eq!(parsed.passwords[0].folder.as_deref(), Some("Perso"));
+        assert_eq!(parsed.passwords[0].username, "alice");
+        assert_eq!(parsed.passwords[0].notes, None);
+        assert_eq!(parsed.passwords[1].password, "1234");
+
+        assert!(parse_pass_dump("mot de passe sans en-tête").is_err());
+    }
+
+    #[test]
+    fn test_write_pass_tree_round_trip() {
+        let root = temp_dir("export");
+        let github = ImportedPassword {
+            site: "github.com".to_string(),
+            username: "alice".to_string(),
+            password: "ghp-secret".to_string(),
+            notes: Some("Compte pro".to_string()),
+            folder: Some("Work/Dev".to_string()),
+            totp: Some("JBSWY3DPEHPK3PXP".to_string()),
+            custom_fields: vec![CustomField { name: "PIN".to_string(), value: "0000".to_string(), hidden: false }],
+            ..ImportedPassword::default()
+        }
+        .with_uris(["https://github.com".to_string(), "https://gist.github.com".to_string()]);
+        let other = ImportedPassword { username: "bob".to_string(), totp: None, ..github.clone() };
+        let hidden = ImportedPassword { site: "../.cache".to_string(), folder: None, ..other.clone() };
+
+        assert_eq!(write_pass_tree(&[github.clone(), other, hidden], &root).unwrap(), 3);
+        assert_eq!(
+            fs::read_to_string(root.join("Work/Dev/github.com")).unwrap(),
+            "ghp-secret\nlogin: alice\nurl: https://github.com\nurl: https://gist.github.com\ntotp: JBSWY3DPEHPK3PXP\nPIN: 0000\nCompte pro\n"
+        );
+        assert!(root.join("Work/Dev/github.com (bob)").is_file());
+        assert!(root.join("_.cache").is_file());
+
+        let parsed = parse_pass_directory(&root, |_| Ok(())).unwrap();
+        let imported = parsed.passwords.iter().find(|p| p.username == "alice").unwrap();
+        assert_eq!(imported.folder, github.folder);
+        assert_eq!(imported.uris, github.uris);
+        assert_eq!(imported.totp, github.totp);
+
+        // Un second export dans le même dossier n'écrase rien
+        assert!(write_pass_tree(&[github], &root).is_err());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
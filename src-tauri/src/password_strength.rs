@@ -0,0 +1,1098 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Estimation de la robustesse d'un mot de passe à la manière de zxcvbn : le mot de passe est
+// découpé en motifs (mots du dictionnaire, l33t, suites de touches, dates, répétitions, suites)
+// et le découpage le plus facile à deviner donne le nombre d'essais nécessaires à un attaquant
+
+// Au-delà, les caractères restants sont comptés en force brute sans chercher de motif
+const MAX_ANALYZED_LENGTH: usize = 100;
+
+// Aucun mot des dictionnaires n'est plus long
+const MAX_WORD_LENGTH: usize = 20;
+
+// Essais par caractère d'un segment sans motif reconnu
+const BRUTEFORCE_CARDINALITY: f64 = 10.0;
+const MIN_GUESSES_SINGLE_CHAR: f64 = 10.0;
+const MIN_GUESSES_MULTI_CHAR: f64 = 50.0;
+
+// Pénalité d'un découpage en plusieurs motifs, qui favorise les découpages courts
+const MIN_GUESSES_BEFORE_GROWING_SEQUENCE: f64 = 10_000.0;
+
+// Écart maximal entre deux caractères consécutifs d'une suite (« aceg », « 9630 »)
+const MAX_SEQUENCE_DELTA: i64 = 5;
+
+const MIN_YEAR: i32 = 1000;
+const MAX_YEAR: i32 = 2050;
+const MIN_YEAR_SPACE: i32 = 20;
+const DATE_SEPARATORS: &[char] = &[' ', '/', '\\', '_', '.', '-'];
+
+// Mots de passe les plus répandus, par ordre de fréquence
+const COMMON_PASSWORDS: &[&str] = &[
+    "123456", "password", "123456789", "12345678", "12345", "qwerty", "azerty", "1234567", "111111", "123123",
+    "abc123", "1234567890", "000000", "password1", "iloveyou", "soleil", "motdepasse", "dragon", "monkey", "letmein",
+    "football", "baseball", "sunshine", "princess", "master", "welcome", "shadow", "superman", "michael", "qwertyuiop",
+    "loulou", "doudou", "chouchou", "marseille", "bonjour", "trustno1", "admin", "passw0rd", "starwars", "jordan",
+    "hunter", "freedom", "whatever", "charlie", "computer", "secret", "chocolat", "coucou", "jetaime", "pokemon",
+    "batman", "login", "hello", "flower", "cheese", "summer", "winter", "mustang", "access", "killer",
+    "ginger", "hockey", "ranger", "daniel", "thomas", "camille", "julien", "nicolas", "olivier", "vacances",
+    "toulouse", "paris", "france", "maison", "bisous", "amour", "changeme", "root", "test", "guest",
+    "zxcvbnm", "asdfgh", "qazwsx", "passpass", "default", "internet", "samsung", "google", "orange", "freebox",
+    "pass", "matrix", "banane", "chaton", "marine", "nounours", "titi", "toto", "azertyuiop", "qwertz",
+];
+
+const FRENCH_WORDS: &[&str] = &[
+    "mot", "passe", "bonjour", "salut", "merci", "amour", "coeur", "chat", "chien", "soleil",
+    "lune", "etoile", "maison", "jardin", "fleur", "rose", "bleu", "rouge", "vert", "noir",
+    "blanc", "jaune", "ciel", "terre", "mer", "plage", "montagne", "neige", "hiver", "ete",
+    "printemps", "automne", "famille", "enfant", "bebe", "papa", "maman", "frere", "soeur", "ami",
+    "amie", "copain", "voiture", "moto", "velo", "musique", "danse", "football", "rugby", "tennis",
+    "chocolat", "fromage", "pomme", "banane", "fraise", "cerise", "cafe", "pain", "vin", "biere",
+    "travail", "bureau", "ecole", "vacances", "voyage", "liberte", "bonheur", "secret", "princesse", "prince",
+    "dragon", "tigre", "lion", "loup", "ours", "cheval", "oiseau", "poisson", "papillon", "dauphin",
+    "bonsoir", "jetaime", "toujours", "jamais", "demain", "hier", "nuit", "jour", "semaine", "annee",
+    "janvier", "fevrier", "mars", "avril", "mai", "juin", "juillet", "aout", "septembre", "octobre",
+    "novembre", "decembre", "lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche", "paris",
+];
+
+const ENGLISH_WORDS: &[&str] = &[
+    "the", "love", "password", "pass", "word", "secret", "admin", "welcome", "hello", "money",
+    "house", "home", "family", "baby", "angel", "happy", "life", "sun", "moon", "star",
+    "blue", "red", "green", "black", "white", "dog", "cat", "tiger", "lion", "bear",
+    "horse", "bird", "fish", "monkey", "dragon", "master", "super", "power", "magic", "princess",
+    "prince", "king", "queen", "friend", "summer", "winter", "spring", "music", "rock", "game",
+    "player", "soccer", "football", "baseball", "hockey", "golf", "coffee", "cookie", "apple", "orange",
+    "banana", "cherry", "chocolate", "flower", "garden", "beach", "ocean", "water", "fire", "thunder",
+    "shadow", "silver", "gold", "diamond", "heart", "sweet", "honey", "sugar", "lucky", "freedom",
+    "computer", "internet", "letmein", "monday", "sunday", "friday", "january", "june", "july", "august",
+    "correct", "horse", "battery", "staple", "dream", "forever", "always", "never", "good", "night",
+];
+
+// Substitutions l33t courantes : lettre d'origine et caractères qui la remplacent
+const L33T_TABLE: &[(char, &[char])] = &[
+    ('a', &['4', '@']),
+    ('b', &['8']),
+    ('c', &['(', '{', '[', '<']),
+    ('e', &['3']),
+    ('g', &['6', '9']),
+    ('i', &['1', '!', '|']),
+    ('l', &['1', '|', '7']),
+    ('o', &['0']),
+    ('s', &['$', '5']),
+    ('t', &['+', '7']),
+    ('x', &['%']),
+    ('z', &['2']),
+];
+
+// Rangées des claviers : caractères sans et avec Maj, décalage en demi-touches
+type KeyboardRow = (&'static str, &'static str, i32);
+
+const QWERTY: &[KeyboardRow] = &[
+    ("`1234567890-=", "~!@#$%^&*()_+", 0),
+    ("qwertyuiop[]\\", "QWERTYUIOP{}|", 3),
+    ("asdfghjkl;'", "ASDFGHJKL:\"", 4),
+    ("zxcvbnm,./", "ZXCVBNM<>?", 5),
+];
+
+const AZERTY: &[KeyboardRow] = &[
+    ("²&é\"'(-è_çà)=", "²1234567890°+", 0),
+    ("azertyuiop^$", "AZERTYUIOP¨£", 3),
+    ("qsdfghjklmù*", "QSDFGHJKLM%µ", 4),
+    ("<wxcvbn,;:!", ">WXCVBN?./§", 3),
+];
+
+const KEYPAD: &[KeyboardRow] = &[
+    ("/*-", "", 2),
+    ("789+", "", 0),
+    ("456", "", 0),
+    ("123", "", 0),
+    ("0.", "", 0),
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PasswordStrength {
+    // De 0 (trop facile à deviner) à 4 (très difficile à deviner)
+    pub score: u8,
+    pub guesses: f64,
+    pub guesses_log10: f64,
+    pub crack_times: CrackTimes,
+    pub feedback: StrengthFeedback,
+}
+
+// Temps nécessaire à un attaquant selon le modèle d'attaque
+#[derive(Debug, Clone, Serialize)]
+pub struct CrackTimes {
+    // Service en ligne limitant les tentatives (100 par heure)
+    pub online_throttling: CrackTime,
+    // Service en ligne sans limitation (10 par seconde)
+    pub online_no_throttling: CrackTime,
+    // Base volée, hachage lent type Argon2 ou bcrypt (10 000 par seconde)
+    pub offline_slow_hashing: CrackTime,
+    // Base volée, hachage rapide sur GPU (10 milliards par seconde)
+    pub offline_fast_hashing: CrackTime,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CrackTime {
+    pub seconds: f64,
+    pub display: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StrengthFeedback {
+    pub warning: Option<String>,
+    pub suggestions: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Pattern {
+    Dictionary {
+        dictionary: &'static str,
+        rank: usize,
+        reversed: bool,
+        // Paires (caractère l33t, lettre remplacée)
+        l33t_subs: Vec<(char, char)>,
+    },
+    Spatial {
+        keyboard: &'static str,
+        turns: usize,
+        shifted_count: usize,
+    },
+    Repeat {
+        base_guesses: f64,
+        base_length: usize,
+        repeat_count: usize,
+    },
+    Sequence {
+        ascending: bool,
+    },
+    Date {
+        year: i32,
+        separator: bool,
+    },
+    Year {
+        year: i32,
+    },
+    Bruteforce,
+}
+
+// Motif reconnu entre les positions i et j incluses
+#[derive(Debug, Clone, PartialEq)]
+struct Match {
+    i: usize,
+    j: usize,
+    token: Vec<char>,
+    pattern: Pattern,
+    guesses: f64,
+}
+
+pub fn estimate_password_strength(password: &str, user_inputs: &[&str]) -> PasswordStrength {
+    let chars: Vec<char> = password.chars().collect();
+    let analyzed = &chars[..chars.len().min(MAX_ANALYZED_LENGTH)];
+
+    let user_dictionary = user_inputs_dictionary(user_inputs);
+    let (mut guesses, sequence) = most_guessable_sequence(analyzed, &user_dictionary);
+    guesses *= BRUTEFORCE_CARDINALITY.powi((chars.len() - analyzed.len()) as i32);
+    let guesses = guesses.min(f64::MAX);
+
+    let score = score_from_guesses(guesses);
+    PasswordStrength {
+        score,
+        guesses,
+        guesses_log10: guesses.log10(),
+        crack_times: CrackTimes {
+            online_throttling: crack_time(guesses / (100.0 / 3600.0)),
+            online_no_throttling: crack_time(guesses / 10.0),
+            offline_slow_hashing: crack_time(guesses / 1e4),
+            offline_fast_hashing: crack_time(guesses / 1e10),
+        },
+        feedback: feedback(score, &sequence),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Dictionnaires
+
+struct RankedDictionary {
+    name: &'static str,
+    ranks: HashMap<String, usize>,
+}
+
+impl RankedDictionary {
+    // Le rang d'un mot est sa position dans la liste, la première occurrence l'emporte
+    fn new(name: &'static str, words: impl IntoIterator<Item = String>) -> Self {
+        let mut ranks = HashMap::new();
+        for word in words {
+            let rank = ranks.len() + 1;
+            ranks.entry(word).or_insert(rank);
+        }
+        RankedDictionary { name, ranks }
+    }
+}
+
+fn static_dictionaries() -> &'static [RankedDictionary] {
+    static DICTIONARIES: OnceLock<Vec<RankedDictionary>> = OnceLock::new();
+    DICTIONARIES.get_or_init(|| {
+        [("passwords", COMMON_PASSWORDS), ("french", FRENCH_WORDS), ("english", ENGLISH_WORDS)]
+            .into_iter()
+            .map(|(name, words)| RankedDictionary::new(name, words.iter().map(|w| w.to_string())))
+            .collect()
+    })
+}
+
+// Les dates sont estimées par rapport à l'année en cours, lue une seule fois ; les tests
+// utilisent une année fixe pour rester reproductibles
+fn reference_year() -> i32 {
+    if cfg!(test) {
+        return 2025;
+    }
+    static YEAR: OnceLock<i32> = OnceLock::new();
+    *YEAR.get_or_init(|| {
+        let millis = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as i64);
+        crate::import_export::timestamp_from_unix_millis(millis)
+            .and_then(|timestamp| timestamp[..4].parse().ok())
+            .unwrap_or(1970)
+    })
+}
+
+// Site, identifiant… : retrouvés tels quels ou par morceaux dans le mot de passe
+fn user_inputs_dictionary(user_inputs: &[&str]) -> RankedDictionary {
+    let mut words = Vec::new();
+    for input in user_inputs {
+        let input = input.trim().to_lowercase();
+        if input.is_empty() {
+            continue;
+        }
+        words.extend(
+            input
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|part| part.chars().count() >= 3 && *part != input)
+                .map(str::to_string),
+        );
+        words.push(input);
+    }
+    RankedDictionary::new("user_inputs", words)
+}
+
+fn dictionary_matches(chars: &[char], user_dictionary: &RankedDictionary) -> Vec<Match> {
+    let lower: Vec<char> = chars.iter().map(|c| c.to_lowercase().next().unwrap_or(*c)).collect();
+    let dictionaries = static_dictionaries().iter().chain(std::iter::once(user_dictionary));
+
+    let mut matches = Vec::new();
+    for dictionary in dictionaries {
+        for i in 0..lower.len() {
+            let max_length = if dictionary.name == "user_inputs" { lower.len() } else { MAX_WORD_LENGTH };
+            for j in i..lower.len().min(i + max_length) {
+                let word: String = lower[i..=j].iter().collect();
+                if let Some(&rank) = dictionary.ranks.get(&word) {
+                    matches.push(Match {
+                        i,
+                        j,
+                        token: chars[i..=j].to_vec(),
+                        pattern: Pattern::Dictionary { dictionary: dictionary.name, rank, reversed: false, l33t_subs: Vec::new() },
+                        guesses: 0.0,
+                    });
+                }
+            }
+        }
+    }
+    matches
+}
+
+fn reverse_dictionary_matches(chars: &[char], user_dictionary: &RankedDictionary) -> Vec<Match> {
+    let reversed: Vec<char> = chars.iter().rev().copied().collect();
+    let n = chars.len();
+    dictionary_matches(&reversed, user_dictionary)
+        .into_iter()
+        // Un palindrome est déjà reconnu à l'endroit
+        .filter(|m| m.token.iter().rev().ne(m.token.iter()))
+        .map(|mut m| {
+            let (i, j) = (n - 1 - m.j, n - 1 - m.i);
+            m.i = i;
+            m.j = j;
+            m.token.reverse();
+            if let Pattern::Dictionary { reversed, .. } = &mut m.pattern {
+                *reversed = true;
+            }
+            m
+        })
+        .collect()
+}
+
+fn l33t_matches(chars: &[char], user_dictionary: &RankedDictionary) -> Vec<Match> {
+    // Lettres possibles pour chaque caractère l33t présent
+    let mut candidates: Vec<(char, Vec<char>)> = Vec::new();
+    for &c in chars {
+        if candidates.iter().any(|(l33t, _)| *l33t == c) {
+            continue;
+        }
+        let letters: Vec<char> = L33T_TABLE.iter().filter(|(_, subs)| subs.contains(&c)).map(|(letter, _)| *letter).collect();
+        if !letters.is_empty() {
+            candidates.push((c, letters));
+        }
+    }
+    if candidates.is_empty() {
+        return Vec::new();
+    }
+
+    // Toutes les combinaisons de substitutions, chaque caractère l33t ne remplaçant qu'une lettre
+    let mut substitutions: Vec<Vec<(char, char)>> = vec![Vec::new()];
+    for (l33t, letters) in &candidates {
+        substitutions = substitutions
+            .into_iter()
+            .flat_map(|sub| {
+                letters.iter().map(move |letter| {
+                    let mut sub = sub.clone();
+                    sub.push((*l33t, *letter));
+                    sub
+                })
+            })
+            .collect();
+    }
+
+    let mut matches: Vec<Match> = Vec::new();
+    for sub in substitutions {
+        let translated: Vec<char> = chars
+            .iter()
+            .map(|c| sub.iter().find(|(l33t, _)| l33t == c).map_or(*c, |(_, letter)| *letter))
+            .collect();
+        for mut m in dictionary_matches(&translated, user_dictionary) {
+            let token = &chars[m.i..=m.j];
+            let used: Vec<(char, char)> = sub.iter().filter(|(l33t, _)| token.contains(l33t)).copied().collect();
+            // Un caractère isolé n'est pas un mot l33t
+            if used.is_empty() || token.len() < 2 {
+                continue;
+            }
+            m.token = token.to_vec();
+            if let Pattern::Dictionary { l33t_subs, .. } = &mut m.pattern {
+                *l33t_subs = used;
+            }
+            if !matches.contains(&m) {
+                matches.push(m);
+            }
+        }
+    }
+    matches
+}
+
+// ---------------------------------------------------------------------------
+// Suites de touches voisines
+
+#[derive(Debug, Clone, Copy)]
+struct Key {
+    // Abscisse en demi-touches, pour représenter le décalage des rangées
+    x: i32,
+    y: i32,
+    shifted: bool,
+}
+
+struct Keyboard {
+    name: &'static str,
+    // Sur un clavier décalé les voisins d'une rangée à l'autre sont à une demi-touche ;
+    // sur un pavé numérique aligné, ils sont au-dessus, en dessous et en diagonale
+    slanted: bool,
+    keys: HashMap<char, Key>,
+    starting_positions: f64,
+    average_degree: f64,
+}
+
+impl Keyboard {
+    fn new(name: &'static str, slanted: bool, rows: &[KeyboardRow]) -> Self {
+        let mut keys = HashMap::new();
+        for (y, (normal, shifted, offset)) in rows.iter().enumerate() {
+            for (column, c) in normal.chars().enumerate() {
+                keys.entry(c).or_insert(Key { x: offset + 2 * column as i32, y: y as i32, shifted: false });
+            }
+            for (column, c) in shifted.chars().enumerate() {
+                keys.entry(c).or_insert(Key { x: offset + 2 * column as i32, y: y as i32, shifted: true });
+            }
+        }
+
+        let positions: Vec<(i32, i32)> = {
+            let mut positions: Vec<(i32, i32)> = keys.values().map(|k| (k.x, k.y)).collect();
+            positions.sort_unstable();
+            positions.dedup();
+            positions
+        };
+        let mut keyboard = Keyboard { name, slanted, keys, starting_positions: positions.len() as f64, average_degree: 0.0 };
+        let degrees: usize = positions
+            .iter()
+            .map(|a| positions.iter().filter(|b| keyboard.direction_between(*a, **b).is_some()).count())
+            .sum();
+        keyboard.average_degree = degrees as f64 / positions.len() as f64;
+        keyboard
+    }
+
+    fn direction_between(&self, (ax, ay): (i32, i32), (bx, by): (i32, i32)) -> Option<(i32, i32)> {
+        let (dx, dy) = (bx - ax, by - ay);
+        let adjacent = if self.slanted {
+            (dy == 0 && dx.abs() == 2) || (dy.abs() == 1 && dx.abs() == 1)
+        } else {
+            dy.abs() <= 1 && dx.abs() <= 2 && dx % 2 == 0 && (dx, dy) != (0, 0)
+        };
+        adjacent.then_some((dx, dy))
+    }
+
+    fn direction(&self, a: char, b: char) -> Option<(i32, i32)> {
+        let (a, b) = (self.keys.get(&a)?, self.keys.get(&b)?);
+        self.direction_between((a.x, a.y), (b.x, b.y))
+    }
+}
+
+fn keyboards() -> &'static [Keyboard] {
+    static KEYBOARDS: OnceLock<Vec<Keyboard>> = OnceLock::new();
+    KEYBOARDS.get_or_init(|| {
+        vec![
+            Keyboard::new("qwerty", true, QWERTY),
+            Keyboard::new("azerty", true, AZERTY),
+            Keyboard::new("keypad", false, KEYPAD),
+        ]
+    })
+}
+
+fn spatial_matches(chars: &[char]) -> Vec<Match> {
+    let mut matches = Vec::new();
+    for keyboard in keyboards() {
+        let mut i = 0;
+        while i < chars.len() {
+            let Some(first) = keyboard.keys.get(&chars[i]) else {
+                i += 1;
+                continue;
+            };
+            let mut shifted_count = usize::from(first.shifted);
+            let mut turns = 0;
+            let mut last_direction = None;
+            let mut j = i;
+            while j + 1 < chars.len() {
+                let Some(direction) = keyboard.direction(chars[j], chars[j + 1]) else { break };
+                if last_direction != Some(direction) {
+                    turns += 1;
+                    last_direction = Some(direction);
+                }
+                if keyboard.keys[&chars[j + 1]].shifted {
+                    shifted_count += 1;
+                }
+                j += 1;
+            }
+            if j - i + 1 >= 3 {
+                matches.push(Match {
+                    i,
+                    j,
+                    token: chars[i..=j].to_vec(),
+                    pattern: Pattern::Spatial { keyboard: keyboard.name, turns, shifted_count },
+                    guesses: 0.0,
+                });
+            }
+            i = j + 1;
+        }
+    }
+    matches
+}
+
+// ---------------------------------------------------------------------------
+// Répétitions, suites et dates
+
+fn repeat_matches(chars: &[char], user_dictionary: &RankedDictionary) -> Vec<Match> {
+    let mut matches = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        // La plus longue répétition commençant en i, avec le motif de base le plus court
+        let mut best: Option<(usize, usize)> = None;
+        for base_length in 1..=(chars.len() - i) / 2 {
+            let base = &chars[i..i + base_length];
+            let mut count = 1;
+            while chars[i + count * base_length..].starts_with(base) {
+                count += 1;
+            }
+            if count >= 2 && best.is_none_or(|(l, c)| base_length * count > l * c) {
+                best = Some((base_length, count));
+            }
+        }
+
+        let Some((base_length, repeat_count)) = best else {
+            i += 1;
+            continue;
+        };
+        let j = i + base_length * repeat_count - 1;
+        let (base_guesses, _) = most_guessable_sequence(&chars[i..i + base_length], user_dictionary);
+        matches.push(Match {
+            i,
+            j,
+            token: chars[i..=j].to_vec(),
+            pattern: Pattern::Repeat { base_guesses, base_length, repeat_count },
+            guesses: 0.0,
+        });
+        i = j + 1;
+    }
+    matches
+}
+
+fn char_class(c: char) -> u8 {
+    if c.is_ascii_lowercase() {
+        0
+    } else if c.is_ascii_uppercase() {
+        1
+    } else if c.is_ascii_digit() {
+        2
+    } else {
+        3
+    }
+}
+
+fn sequence_matches(chars: &[char]) -> Vec<Match> {
+    let mut matches = Vec::new();
+    let mut push = |i: usize, j: usize, delta: i64| {
+        let token = &chars[i..=j];
+        if token.len() >= 3
+            && delta != 0
+            && delta.abs() <= MAX_SEQUENCE_DELTA
+            && token.iter().all(|c| char_class(*c) == char_class(token[0]))
+        {
+            matches.push(Match { i, j, token: token.to_vec(), pattern: Pattern::Sequence { ascending: delta > 0 }, guesses: 0.0 });
+        }
+    };
+
+    let mut start = 0;
+    let mut last_delta = None;
+    for k in 1..chars.len() {
+        let delta = chars[k] as i64 - chars[k - 1] as i64;
+        if last_delta == Some(delta) {
+            continue;
+        }
+        if let Some(last_delta) = last_delta {
+            push(start, k - 1, last_delta);
+        }
+        start = k - 1;
+        last_delta = Some(delta);
+    }
+    if let Some(last_delta) = last_delta {
+        push(start, chars.len() - 1, last_delta);
+    }
+    matches
+}
+
+fn two_to_four_digit_year(year: i32) -> i32 {
+    if year > 99 {
+        year
+    } else if year > 50 {
+        1900 + year
+    } else {
+        2000 + year
+    }
+}
+
+fn map_ints_to_day_month(a: i32, b: i32) -> bool {
+    [(a, b), (b, a)].iter().any(|&(day, month)| (1..=31).contains(&day) && (1..=12).contains(&month))
+}
+
+// Année d'un triplet jour/mois/année dans n'importe quel ordre usuel
+fn map_ints_to_year(ints: [i32; 3]) -> Option<i32> {
+    if ints[1] > 31 || ints[1] <= 0 {
+        return None;
+    }
+    let mut over_12 = 0;
+    let mut over_31 = 0;
+    let mut under_1 = 0;
+    for &int in &ints {
+        if (99 < int && int < MIN_YEAR) || int > MAX_YEAR {
+            return None;
+        }
+        over_31 += usize::from(int > 31);
+        over_12 += usize::from(int > 12);
+        under_1 += usize::from(int <= 0);
+    }
+    if over_31 >= 2 || over_12 == 3 || under_1 >= 2 {
+        return None;
+    }
+
+    let splits = [(ints[2], ints[0], ints[1]), (ints[0], ints[1], ints[2])];
+    for &(year, a, b) in &splits {
+        if (MIN_YEAR..=MAX_YEAR).contains(&year) {
+            return map_ints_to_day_month(a, b).then_some(year);
+        }
+    }
+    splits
+        .iter()
+        .find(|&&(_, a, b)| map_ints_to_day_month(a, b))
+        .map(|&(year, _, _)| two_to_four_digit_year(year))
+}
+
+fn parse_digits(chars: &[char]) -> Option<i32> {
+    if chars.is_empty() || !chars.iter().all(char::is_ascii_digit) {
+        return None;
+    }
+    chars.iter().collect::<String>().parse().ok()
+}
+
+// « 14/07/1989 », « 1989-07-14 » : trois nombres séparés deux fois par le même caractère
+fn parse_separated_date(token: &[char]) -> Option<[i32; 3]> {
+    let first = token.iter().position(|c| !c.is_ascii_digit())?;
+    let separator = token[first];
+    if !DATE_SEPARATORS.contains(&separator) || first > 4 {
+        return None;
+    }
+    let second = first + 1 + token[first + 1..].iter().position(|c| *c == separator)?;
+    let (a, b, c) = (&token[..first], &token[first + 1..second], &token[second + 1..]);
+    if b.len() > 2 || c.len() > 4 {
+        return None;
+    }
+    Some([parse_digits(a)?, parse_digits(b)?, parse_digits(c)?])
+}
+
+// Découpages possibles d'une date sans séparateur, selon sa longueur
+fn date_splits(length: usize) -> &'static [(usize, usize)] {
+    match length {
+        4 => &[(1, 2), (2, 3)],
+        5 => &[(1, 3), (2, 3)],
+        6 => &[(1, 2), (2, 4), (4, 5)],
+        7 => &[(1, 3), (2, 3), (4, 5), (4, 6)],
+        8 => &[(2, 4), (4, 6)],
+        _ => &[],
+    }
+}
+
+fn date_matches(chars: &[char]) -> Vec<Match> {
+    let mut matches = Vec::new();
+    let mut push = |i: usize, j: usize, year: i32, separator: bool| {
+        matches.push(Match { i, j, token: chars[i..=j].to_vec(), pattern: Pattern::Date { year, separator }, guesses: 0.0 });
+    };
+
+    for i in 0..chars.len() {
+        for j in i + 3..chars.len().min(i + 10) {
+            let token = &chars[i..=j];
+            if token.iter().all(char::is_ascii_digit) {
+                // L'année la plus proche de l'année de référence est retenue
+                let year = date_splits(token.len())
+                    .iter()
+                    .filter_map(|&(k, l)| {
+                        map_ints_to_year([parse_digits(&token[..k])?, parse_digits(&token[k..l])?, parse_digits(&token[l..])?])
+                    })
+                    .min_by_key(|year| (year - reference_year()).abs());
+                if let Some(year) = year {
+                    push(i, j, year, false);
+                }
+            } else if token.len() >= 6 {
+                if let Some(year) = parse_separated_date(token).and_then(map_ints_to_year) {
+                    push(i, j, year, true);
+                }
+            }
+        }
+    }
+    matches
+}
+
+fn year_matches(chars: &[char]) -> Vec<Match> {
+    chars
+        .windows(4)
+        .enumerate()
+        .filter_map(|(i, window)| {
+            let year = parse_digits(window)?;
+            (1900..=2049).contains(&year).then(|| Match {
+                i,
+                j: i + 3,
+                token: window.to_vec(),
+                pattern: Pattern::Year { year },
+                guesses: 0.0,
+            })
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Estimation du nombre d'essais
+
+fn n_choose_k(n: usize, k: usize) -> f64 {
+    if k > n {
+        return 0.0;
+    }
+    (1..=k).fold(1.0, |acc, i| acc * (n - k + i) as f64 / i as f64)
+}
+
+fn factorial(n: usize) -> f64 {
+    (2..=n).fold(1.0, |acc, i| acc * i as f64)
+}
+
+// Variantes de casse : une majuscule initiale ou finale, ou tout en majuscules, compte peu
+fn uppercase_variations(token: &[char]) -> f64 {
+    let upper = token.iter().filter(|c| c.is_uppercase()).count();
+    let lower = token.iter().filter(|c| c.is_lowercase()).count();
+    if upper == 0 {
+        return 1.0;
+    }
+    let starts_upper = token[0].is_uppercase() && upper == 1;
+    let ends_upper = token[token.len() - 1].is_uppercase() && upper == 1;
+    if starts_upper || ends_upper || lower == 0 {
+        return 2.0;
+    }
+    (1..=upper.min(lower)).map(|k| n_choose_k(upper + lower, k)).sum()
+}
+
+fn l33t_variations(token: &[char], subs: &[(char, char)]) -> f64 {
+    subs.iter()
+        .map(|(l33t, letter)| {
+            let substituted = token.iter().filter(|c| *c == l33t).count();
+            let unsubstituted = token.iter().filter(|c| c.to_lowercase().next() == Some(*letter)).count();
+            if substituted == 0 || unsubstituted == 0 {
+                2.0
+            } else {
+                (1..=substituted.min(unsubstituted)).map(|k| n_choose_k(substituted + unsubstituted, k)).sum()
+            }
+        })
+        .product()
+}
+
+fn spatial_guesses(token: &[char], keyboard: &str, turns: usize, shifted_count: usize) -> f64 {
+    let Some(keyboard) = keyboards().iter().find(|k| k.name == keyboard) else { return 0.0 };
+    let length = token.len();
+    let mut guesses = 0.0;
+    for i in 2..=length {
+        for j in 1..=turns.min(i - 1) {
+            guesses += n_choose_k(i - 1, j - 1) * keyboard.starting_positions * keyboard.average_degree.powi(j as i32);
+        }
+    }
+    if shifted_count > 0 {
+        let unshifted = length - shifted_count;
+        guesses *= if unshifted == 0 {
+            2.0
+        } else {
+            (1..=shifted_count.min(unshifted)).map(|k| n_choose_k(shifted_count + unshifted, k)).sum()
+        };
+    }
+    guesses
+}
+
+fn estimate_guesses(m: &Match, password_length: usize) -> f64 {
+    let length = m.token.len();
+    let guesses = match &m.pattern {
+        Pattern::Dictionary { rank, reversed, l33t_subs, .. } => {
+            *rank as f64
+                * uppercase_variations(&m.token)
+                * l33t_variations(&m.token, l33t_subs)
+                * if *reversed { 2.0 } else { 1.0 }
+        }
+        Pattern::Spatial { keyboard, turns, shifted_count } => spatial_guesses(&m.token, keyboard, *turns, *shifted_count),
+        Pattern::Repeat { base_guesses, repeat_count, .. } => base_guesses * *repeat_count as f64,
+        Pattern::Sequence { ascending } => {
+            let first = m.token[0];
+            let base = if matches!(first, 'a' | 'A' | 'z' | 'Z' | '0' | '1' | '9') {
+                4.0
+            } else if first.is_ascii_digit() {
+                10.0
+            } else {
+                26.0
+            };
+            base * if *ascending { 1.0 } else { 2.0 } * length as f64
+        }
+        Pattern::Date { year, separator } => {
+            let year_space = (year - reference_year()).abs().max(MIN_YEAR_SPACE) as f64;
+            year_space * 365.0 * if *separator { 4.0 } else { 1.0 }
+        }
+        Pattern::Year { year } => (year - reference_year()).abs().max(MIN_YEAR_SPACE) as f64,
+        Pattern::Bruteforce => {
+            let guesses = BRUTEFORCE_CARDINALITY.powi(length as i32).min(f64::MAX);
+            let min_guesses = if length == 1 { MIN_GUESSES_SINGLE_CHAR + 1.0 } else { MIN_GUESSES_MULTI_CHAR + 1.0 };
+            return guesses.max(min_guesses);
+        }
+    };
+
+    // Un motif noyé dans un mot de passe plus long coûte au moins quelques essais
+    if length < password_length {
+        let min_guesses = if length == 1 { MIN_GUESSES_SINGLE_CHAR } else { MIN_GUESSES_MULTI_CHAR };
+        guesses.max(min_guesses)
+    } else {
+        guesses
+    }
+}
+
+fn all_matches(chars: &[char], user_dictionary: &RankedDictionary) -> Vec<Match> {
+    let mut matches = dictionary_matches(chars, user_dictionary);
+    matches.extend(reverse_dictionary_matches(chars, user_dictionary));
+    matches.extend(l33t_matches(chars, user_dictionary));
+    matches.extend(spatial_matches(chars));
+    matches.extend(repeat_matches(chars, user_dictionary));
+    matches.extend(sequence_matches(chars));
+    matches.extend(date_matches(chars));
+    matches.extend(year_matches(chars));
+    for m in &mut matches {
+        m.guesses = estimate_guesses(m, chars.len());
+    }
+    matches
+}
+
+struct Step {
+    m: Match,
+    // Produit des essais des motifs du découpage
+    product: f64,
+    // Essais du découpage, pénalité de longueur comprise
+    guesses: f64,
+}
+
+// Découpage du mot de passe en motifs minimisant le nombre d'essais : pour chaque position k
+// et chaque nombre de motifs l, le meilleur découpage de chars[..=k] en l motifs est conservé
+fn most_guessable_sequence(chars: &[char], user_dictionary: &RankedDictionary) -> (f64, Vec<Match>) {
+    let n = chars.len();
+    if n == 0 {
+        return (1.0, Vec::new());
+    }
+
+    let mut by_end: Vec<Vec<Match>> = vec![Vec::new(); n];
+    for m in all_matches(chars, user_dictionary) {
+        let j = m.j;
+        by_end[j].push(m);
+    }
+
+    let mut optimal: Vec<BTreeMap<usize, Step>> = (0..n).map(|_| BTreeMap::new()).collect();
+    let update = |optimal: &mut Vec<BTreeMap<usize, Step>>, m: Match, l: usize| {
+        let mut product = m.guesses;
+        if l > 1 {
+            product *= optimal[m.i - 1][&(l - 1)].product;
+        }
+        let guesses = factorial(l) * product + MIN_GUESSES_BEFORE_GROWING_SEQUENCE.powi(l as i32 - 1);
+        // Inutile de garder un découpage plus long et plus facile à deviner qu'un autre
+        if optimal[m.j].iter().any(|(&other_l, step)| other_l <= l && step.guesses <= guesses) {
+            return;
+        }
+        let k = m.j;
+        optimal[k].insert(l, Step { m, product, guesses });
+    };
+    let bruteforce = |i: usize, j: usize| {
+        let mut m = Match { i, j, token: chars[i..=j].to_vec(), pattern: Pattern::Bruteforce, guesses: 0.0 };
+        m.guesses = estimate_guesses(&m, n);
+        m
+    };
+
+    for (k, ending) in by_end.into_iter().enumerate() {
+        for m in ending {
+            if m.i == 0 {
+                update(&mut optimal, m, 1);
+            } else {
+                let lengths: Vec<usize> = optimal[m.i - 1].keys().copied().collect();
+                for l in lengths {
+                    update(&mut optimal, m.clone(), l + 1);
+                }
+            }
+        }
+
+        // Segments en force brute, jamais accolés à un autre segment en force brute
+        update(&mut optimal, bruteforce(0, k), 1);
+        for i in 1..=k {
+            let lengths: Vec<usize> = optimal[i - 1]
+                .iter()
+                .filter(|(_, step)| step.m.pattern != Pattern::Bruteforce)
+                .map(|(&l, _)| l)
+                .collect();
+            for l in lengths {
+                update(&mut optimal, bruteforce(i, k), l + 1);
+            }
+        }
+    }
+
+    let (mut l, guesses) = optimal[n - 1]
+        .iter()
+        .map(|(&l, step)| (l, step.guesses))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap_or((1, f64::MAX));
+
+    let mut sequence = Vec::new();
+    let mut k = n;
+    while k > 0 && l > 0 {
+        let step = &optimal[k - 1][&l];
+        sequence.push(step.m.clone());
+        k = step.m.i;
+        l -= 1;
+    }
+    sequence.reverse();
+    (guesses, sequence)
+}
+
+// ---------------------------------------------------------------------------
+// Score, temps et conseils
+
+fn score_from_guesses(guesses: f64) -> u8 {
+    const DELTA: f64 = 5.0;
+    if guesses < 1e3 + DELTA {
+        0
+    } else if guesses < 1e6 + DELTA {
+        1
+    } else if guesses < 1e8 + DELTA {
+        2
+    } else if guesses < 1e10 + DELTA {
+        3
+    } else {
+        4
+    }
+}
+
+fn crack_time(seconds: f64) -> CrackTime {
+    CrackTime { seconds, display: display_time(seconds) }
+}
+
+fn display_time(seconds: f64) -> String {
+    const MINUTE: f64 = 60.0;
+    const HOUR: f64 = MINUTE * 60.0;
+    const DAY: f64 = HOUR * 24.0;
+    const MONTH: f64 = DAY * 31.0;
+    const YEAR: f64 = MONTH * 12.0;
+    const CENTURY: f64 = YEAR * 100.0;
+
+    let count = |unit: f64, singular: &str, plural: &str| {
+        let n = (seconds / unit).round();
+        format!("{} {}", n, if n <= 1.0 { singular } else { plural })
+    };
+    if seconds < 1.0 {
+        "moins d'une seconde".to_string()
+    } else if seconds < MINUTE {
+        count(1.0, "seconde", "secondes")
+    } else if seconds < HOUR {
+        count(MINUTE, "minute", "minutes")
+    } else if seconds < DAY {
+        count(HOUR, "heure", "heures")
+    } else if seconds < MONTH {
+        count(DAY, "jour", "jours")
+    } else if seconds < YEAR {
+        count(MONTH, "mois", "mois")
+    } else if seconds < CENTURY {
+        count(YEAR, "an", "ans")
+    } else {
+        "des siècles".to_string()
+    }
+}
+
+fn feedback(score: u8, sequence: &[Match]) -> StrengthFeedback {
+    if sequence.is_empty() {
+        return StrengthFeedback {
+            warning: None,
+            suggestions: vec![
+                "Utilisez plusieurs mots, en évitant les expressions courantes".to_string(),
+                "Inutile d'ajouter symboles, chiffres ou majuscules si le mot de passe est long".to_string(),
+            ],
+        };
+    }
+    if score > 2 {
+        return StrengthFeedback::default();
+    }
+
+    let mut suggestions = vec!["Ajoutez un ou deux mots, les mots peu courants sont les meilleurs".to_string()];
+    let Some(longest) = sequence
+        .iter()
+        .filter(|m| m.pattern != Pattern::Bruteforce)
+        .max_by_key(|m| m.token.len())
+    else {
+        return StrengthFeedback { warning: None, suggestions };
+    };
+
+    let warning = match &longest.pattern {
+        Pattern::Dictionary { dictionary, rank, reversed, l33t_subs } => {
+            let alone = sequence.len() == 1;
+            let warning = match *dictionary {
+                "passwords" if alone && !*reversed && l33t_subs.is_empty() => Some(if *rank <= 10 {
+                    "Ce mot de passe fait partie des 10 plus courants".to_string()
+                } else if *rank <= 100 {
+                    "Ce mot de passe fait partie des 100 plus courants".to_string()
+                } else {
+                    "Ce mot de passe est très courant".to_string()
+                }),
+                "passwords" => Some("Ce mot de passe ressemble à un mot de passe courant".to_string()),
+                "user_inputs" => Some("Le mot de passe reprend le site ou l'identifiant".to_string()),
+                _ if alone => Some("Un mot seul est facile à deviner".to_string()),
+                _ => None,
+            };
+
+            let token = &longest.token;
+            if token[0].is_uppercase() && token.iter().skip(1).all(|c| !c.is_uppercase()) {
+                suggestions.push("Une majuscule en début de mot n'aide pas beaucoup".to_string());
+            } else if token.iter().any(|c| c.is_alphabetic()) && token.iter().all(|c| !c.is_lowercase()) {
+                suggestions.push("Tout en majuscules est presque aussi facile à deviner que tout en minuscules".to_string());
+            }
+            if *reversed && token.len() >= 4 {
+                suggestions.push("Un mot à l'envers n'est pas beaucoup plus difficile à deviner".to_string());
+            }
+            if !l33t_subs.is_empty() {
+                suggestions.push("Les substitutions prévisibles comme « @ » au lieu de « a » n'aident pas beaucoup".to_string());
+            }
+            warning
+        }
+        Pattern::Spatial { turns, .. } => {
+            suggestions.push("Utilisez un motif de clavier plus long avec plus de changements de direction".to_string());
+            Some(if *turns == 1 {
+                "Les rangées de touches voisines sont faciles à deviner".to_string()
+            } else {
+                "Les motifs de clavier courts sont faciles à deviner".to_string()
+            })
+        }
+        Pattern::Repeat { base_length, .. } => {
+            suggestions.push("Évitez les mots et caractères répétés".to_string());
+            Some(if *base_length == 1 {
+                "Les répétitions comme « aaa » sont faciles à deviner".to_string()
+            } else {
+                "Les répétitions comme « abcabcabc » sont à peine plus difficiles à deviner que « abc »".to_string()
+            })
+        }
+        Pattern::Sequence { .. } => {
+            suggestions.push("Évitez les suites".to_string());
+            Some("Les suites comme « abc » ou « 6543 » sont faciles à deviner".to_string())
+        }
+        Pattern::Date { .. } => {
+            suggestions.push("Évitez les dates et années qui vous sont associées".to_string());
+            Some("Les dates sont souvent faciles à deviner".to_string())
+        }
+        Pattern::Year { .. } => {
+            suggestions.push("Évitez les années récentes".to_string());
+            Some("Les années récentes sont faciles à deviner".to_string())
+        }
+        Pattern::Bruteforce => None,
+    };
+    StrengthFeedback { warning, suggestions }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patterns(password: &str) -> Vec<Pattern> {
+        let chars: Vec<char> = password.chars().collect();
+        most_guessable_sequence(&chars, &user_inputs_dictionary(&[])).1.into_iter().map(|m| m.pattern).collect()
+    }
+
+    #[test]
+    fn test_scores_range_from_common_to_random() {
+        assert_eq!(estimate_password_strength("password", &[]).score, 0);
+        assert!(estimate_password_strength("azerty123", &[]).score <= 1);
+        assert!(estimate_password_strength("P@ssw0rd", &[]).score <= 1);
+        assert!(estimate_password_strength("Soleil1989", &[]).score <= 2);
+        assert_eq!(estimate_password_strength("xK9#mQ2$vL7!pR4&", &[]).score, 4);
+        assert_eq!(estimate_password_strength("", &[]).score, 0);
+
+        let strength = estimate_password_strength("qwerty", &[]);
+        assert_eq!(strength.crack_times.offline_fast_hashing.display, "moins d'une seconde");
+        assert_eq!(strength.feedback.warning.as_deref(), Some("Ce mot de passe fait partie des 10 plus courants"));
+        assert!(estimate_password_strength("xK9#mQ2$vL7!pR4&", &[]).feedback.warning.is_none());
+    }
+
+    #[test]
+    fn test_patterns_are_recognized() {
+        assert!(matches!(
+            patterns("p@ssw0rd").as_slice(),
+            [Pattern::Dictionary { dictionary: "passwords", l33t_subs, .. }] if !l33t_subs.is_empty()
+        ));
+        assert!(matches!(patterns("drowssap").as_slice(), [Pattern::Dictionary { reversed: true, .. }]));
+        assert!(matches!(patterns("zxcvbnm,./").as_slice(), [Pattern::Spatial { keyboard: "qwerty", .. }]));
+        assert!(matches!(patterns("wxcvbn").as_slice(), [Pattern::Spatial { keyboard: "azerty", turns: 1, .. }]));
+        assert!(matches!(patterns("jkjkjkjk").as_slice(), [Pattern::Repeat { base_length: 2, repeat_count: 4, .. }]));
+        assert!(matches!(patterns("opqrstu").as_slice(), [Pattern::Sequence { ascending: true }]));
+        assert!(matches!(patterns("14/07/1989").as_slice(), [Pattern::Date { year: 1989, separator: true }]));
+        assert!(matches!(patterns("140789").as_slice(), [Pattern::Date { year: 1989, separator: false }]));
+    }
+
+    #[test]
+    fn test_user_inputs_weaken_password() {
+        let password = "dupont.mail";
+        let alone = estimate_password_strength(password, &[]);
+        let with_inputs = estimate_password_strength(password, &["example.com", "jean.dupont@mail.fr"]);
+        assert!(with_inputs.guesses < alone.guesses);
+        assert_eq!(display_time(7200.0), "2 heures");
+        assert_eq!(display_time(1e12), "des siècles");
+    }
+}
@@ -0,0 +1,295 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::io::{Cursor, Read};
+use zeroize::Zeroizing;
+
+use crate::import_export::{extract_domain_from_url, ImportedPassword, ParsedImport};
+
+// Import des exports Proton Pass : data.json seul ou archive zip « Proton Pass/data.json »
+
+// État d'un élément placé dans la corbeille
+const STATE_TRASHED: u8 = 2;
+
+pub(crate) const EXPORT_DATA_PATH: &str = "Proton Pass/data.json";
+const ENCRYPTED_DATA_PATH: &str = "Proton Pass/data.pgp";
+
+// Protection contre les archives décompressant un export démesuré
+const MAX_EXPORT_DATA_SIZE: u64 = 256 * 1024 * 1024;
+
+#[derive(Deserialize)]
+struct Export {
+    #[serde(default)]
+    encrypted: bool,
+    #[serde(default)]
+    vaults: BTreeMap<String, Vault>,
+}
+
+#[derive(Deserialize)]
+struct Vault {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    items: Vec<Item>,
+}
+
+#[derive(Deserialize)]
+struct Item {
+    #[serde(default)]
+    state: u8,
+    data: ItemData,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ItemData {
+    metadata: Metadata,
+    #[serde(rename = "type")]
+    item_type: String,
+    #[serde(default)]
+    content: Content,
+    #[serde(default)]
+    extra_fields: Vec<ExtraField>,
+}
+
+#[derive(Deserialize)]
+struct Metadata {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    note: String,
+}
+
+// Les exports récents séparent itemEmail et itemUsername, les anciens n'ont que username
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct Content {
+    username: String,
+    item_username: String,
+    item_email: String,
+    password: String,
+    urls: Vec<String>,
+    totp_uri: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExtraField {
+    #[serde(default)]
+    field_name: String,
+}
+
+pub fn parse_protonpass_zip(data: &[u8]) -> Result<ParsedImport, String> {
+    let mut archive =
+        zip::ZipArchive::new(Cursor::new(data)).map_err(|e| format!("Archive Proton Pass invalide: {}", e))?;
+    if archive.by_name(ENCRYPTED_DATA_PATH).is_ok() {
+        return Err("Les exports Proton Pass chiffrés par PGP ne sont pas pris en charge".to_string());
+    }
+    let file = archive
+        .by_name(EXPORT_DATA_PATH)
+        .map_err(|_| format!("Archive Proton Pass sans fichier {}", EXPORT_DATA_PATH))?;
+
+    let mut content = Zeroizing::new(String::new());
+    file.take(MAX_EXPORT_DATA_SIZE)
+        .read_to_string(&mut content)
+        .map_err(|e| format!("Erreur lors de la lecture de l'archive Proton Pass: {}", e))?;
+    parse_protonpass_json(&content)
+}
+
+pub fn parse_protonpass_json(content: &str) -> Result<ParsedImport, String> {
+    let export: Export = serde_json::from_str(content)
+        .map_err(|e| format!("Le fichier n'est pas un export Proton Pass: {}", e))?;
+    if export.encrypted {
+        return Err("Les exports Proton Pass chiffrés par PGP ne sont pas pris en charge".to_string());
+    }
+
+    let mut parsed = ParsedImport::default();
+    let mut trashed = 0;
+
+    for vault in export.vaults.into_values() {
+        let folder = Some(vault.name).filter(|name| !name.trim().is_empty());
+
+        for item in vault.items {
+            if item.state == STATE_TRASHED {
+                trashed += 1;
+                continue;
+            }
+
+            let data = item.data;
+            let title = data.metadata.name.trim().to_string();
+            if data.item_type != "login" {
+                parsed.warnings.push(format!(
+                    "{}: élément de type « {} » non importé",
+                    title,
+                    type_name(&data.item_type)
+                ));
+                continue;
+            }
+
+            let content = data.content;
+            let url = content.urls.first().filter(|url| !url.trim().is_empty()).cloned();
+            let site = Some(title)
+                .filter(|title| !title.is_empty())
+                .or_else(|| url.as_deref().and_then(extract_domain_from_url))
+                .unwrap_or_default();
+
+            if content.password.is_empty() {
+                parsed.warnings.push(format!("{}: entrée sans mot de passe ignorée", site));
+                continue;
+            }
+
+            let username = [content.item_username, content.username, content.item_email.clone()]
+                .into_iter()
+                .find(|value| !value.trim().is_empty())
+                .unwrap_or_default();
+            if !content.item_email.is_empty() && content.item_email != username {
+                parsed.warnings.push(format!("{}: champ « email » non importé", site));
+            }
+            if !content.totp_uri.is_empty() {
+                parsed.warnings.push(format!("{}: champ « totp » non importé", site));
+            }
+            for field in &data.extra_fields {
+                parsed.warnings.push(format!("{}: champ « {} » non importé", site, field.field_name));
+            }
+
+            parsed.passwords.push(ImportedPassword {
+                site,
+                username,
+                password: content.password,
+                notes: Some(data.metadata.note).filter(|note| !note.trim().is_empty()),
+                folder: folder.clone(),
+                ..ImportedPassword::default()
+            }.with_uris(content.urls));
+        }
+    }
+
+    if trashed > 0 {
+        parsed
+            .warnings
+            .push(format!("{} élément(s) de la corbeille ignoré(s)", trashed));
+    }
+
+    Ok(parsed)
+}
+
+fn type_name(item_type: &str) -> &str {
+    match item_type {
+        "note" => "Note",
+        "alias" => "Alias",
+        "creditCard" => "Carte bancaire",
+        "identity" => "Identité",
+        "sshKey" => "Clé SSH",
+        "wifi" => "Réseau Wi-Fi",
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const EXPORT: &str = r#"{
+        "version": "1.21.2",
+        "userId": "user-id",
+        "encrypted": false,
+        "vaults": {
+            "share-1": {
+                "name": "Personal",
+                "description": "Personal vault",
+                "items": [
+                    {
+                        "itemId": "item-1",
+                        "shareId": "share-1",
+                        "data": {
+                            "metadata": {"name": "Example Site", "note": "Test notes", "itemUuid": "uuid-1"},
+                            "extraFields": [{"fieldName": "PIN", "type": "hidden", "data": {"content": "1234"}}],
+                            "type": "login",
+                            "content": {
+                                "itemEmail": "user@test.com",
+                                "itemUsername": "",
+                                "password": "password123",
+                                "urls": ["https://example.com", "https://login.example.com"],
+                                "totpUri": ""
+                            }
+                        },
+                        "state": 1
+                    },
+                    {
+                        "itemId": "item-2",
+                        "shareId": "share-1",
+                        "data": {
+                            "metadata": {"name": "Legacy", "note": "", "itemUuid": "uuid-2"},
+                            "type": "login",
+                            "content": {"username": "legacy", "password": "hunter2", "urls": []}
+                        },
+                        "state": 1
+                    },
+                    {
+                        "itemId": "item-3",
+                        "shareId": "share-1",
+                        "data": {
+                            "metadata": {"name": "Carte", "note": "", "itemUuid": "uuid-3"},
+                            "type": "creditCard",
+                            "content": {}
+                        },
+                        "state": 1
+                    },
+                    {
+                        "itemId": "item-4",
+                        "shareId": "share-1",
+                        "data": {
+                            "metadata": {"name": "Ancien", "note": "", "itemUuid": "uuid-4"},
+                            "type": "login",
+                            "content": {"username": "old", "password": "old", "urls": []}
+                        },
+                        "state": 2
+                    }
+                ]
+            }
+        }
+    }"#;
+
+    #[test]
+    fn test_parse_protonpass_json() {
+        let parsed = parse_protonpass_json(EXPORT).unwrap();
+        assert_eq!(parsed.passwords.len(), 2);
+
+        let password = &parsed.passwords[0];
+        assert_eq!(password.site, "Example Site");
+        assert_eq!(password.username, "user@test.com");
+        assert_eq!(password.password, "password123");
+        assert_eq!(password.url.as_deref(), Some("https://example.com"));
+        assert_eq!(password.uris.len(), 2);
+        assert_eq!(password.uris[1].uri, "https://login.example.com");
+        assert_eq!(password.notes.as_deref(), Some("Test notes"));
+        assert_eq!(password.folder.as_deref(), Some("Personal"));
+
+        assert_eq!(parsed.passwords[1].username, "legacy");
+        assert_eq!(parsed.passwords[1].notes, None);
+
+        assert_eq!(parsed.warnings, vec![
+            "Example Site: champ « PIN » non importé".to_string(),
+            "Carte: élément de type « Carte bancaire » non importé".to_string(),
+            "1 élément(s) de la corbeille ignoré(s)".to_string(),
+        ]);
+
+        let encrypted = r#"{"version": "1.21.2", "encrypted": true, "vaults": {}}"#;
+        assert!(parse_protonpass_json(encrypted).is_err());
+    }
+
+    #[test]
+    fn test_parse_protonpass_zip() {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        writer
+            .start_file(EXPORT_DATA_PATH, zip::write::SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(EXPORT.as_bytes()).unwrap();
+        let archive = writer.finish().unwrap().into_inner();
+
+        let parsed = parse_protonpass_zip(&archive).unwrap();
+        assert_eq!(parsed.passwords.len(), 2);
+        assert_eq!(parsed.passwords[0].folder.as_deref(), Some("Personal"));
+
+        assert!(parse_protonpass_zip(b"not a zip").is_err());
+    }
+}
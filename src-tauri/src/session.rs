@@ -0,0 +1,226 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use crate::vault::Vault;
+
+const MIN_MASTER_PASSWORD_LEN: usize = 8;
+
+// Réglages du verrouillage automatique, enregistrés dans le coffre
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct AutoLockSettings {
+    // 0 désactive le verrouillage après inactivité
+    pub idle_timeout_secs: u64,
+    pub lock_on_minimize: bool,
+    pub lock_on_close: bool,
+    pub lock_on_sleep: bool,
+}
+
+impl Default for AutoLockSettings {
+    fn default() -> Self {
+        AutoLockSettings {
+            idle_timeout_secs: 5 * 60,
+            lock_on_minimize: false,
+            lock_on_close: true,
+            lock_on_sleep: true,
+        }
+    }
+}
+
+// État de session géré par Tauri : le coffre déverrouillé (et donc sa clé)
+// n'existe qu'en mémoire côté Rust
+pub struct VaultSession {
+    db_path: PathBuf,
+    vault: Mutex<Option<Vault>>,
+    auto_lock: Mutex<AutoLockSettings>,
+    last_activity: Mutex<Instant>,
+}
+
+impl VaultSession {
+    pub fn new(db_path: PathBuf) -> Self {
+        VaultSession {
+            db_path,
+            vault: Mutex::new(None),
+            auto_lock: Mutex::new(AutoLockSettings::default()),
+            last_activity: Mutex::new(Instant::now()),
+        }
+    }
+
+    pub fn is_setup(&self) -> Result<bool, String> {
+        Vault::is_initialized(&self.db_path)
+    }
+
+    pub fn setup(&self, master_password: &str) -> Result<(), String> {
+        check_master_password(master_password)?;
+        let vault = Vault::create(&self.db_path, master_password)?;
+        self.open(vault)
+    }
+
+    pub fn unlock(&self, master_password: &str) -> Result<(), String> {
+        let vault = Vault::unlock(&self.db_path, master_password)?;
+        self.open(vault)
+    }
+
+    fn open(&self, vault: Vault) -> Result<(), String> {
+        let settings = vault.auto_lock_settings()?;
+        *self.auto_lock_state()? = settings;
+        *self.lock_state()? = Some(vault);
+        self.record_activity()
+    }
+
+    pub fn change_master_password(&self, old_password: &str, new_password: &str) -> Result<(), String> {
+        check_master_password(new_password)?;
+        self.with_vault_mut(|vault| vault.change_master_password(old_password, new_password))
+    }
+
+    // Ferme le coffre : la clé est effacée de la mémoire lors de sa destruction
+    pub fn lock(&self) -> Result<(), String> {
+        self.lock_state()?.take();
+        Ok(())
+    }
+
+    pub fn is_unlocked(&self) -> Result<bool, String> {
+        Ok(self.lock_state()?.is_some())
+    }
+
+    pub fn auto_lock_settings(&self) -> Result<AutoLockSettings, String> {
+        Ok(*self.auto_lock_state()?)
+    }
+
+    pub fn set_auto_lock_settings(&self, settings: AutoLockSettings) -> Result<(), String> {
+        self.with_vault(|vault| vault.set_auto_lock_settings(&settings))?;
+        *self.auto_lock_state()? = settings;
+        Ok(())
+    }
+
+    // Toute interaction de l'utilisateur repousse le verrouillage automatique
+    pub fn record_activity(&self) -> Result<(), String> {
+        *self
+            .last_activity
+            .lock()
+            .map_err(|_| "Session du coffre indisponible".to_string())? = Instant::now();
+        Ok(())
+    }
+
+    // Verrouille le coffre si le délai d'inactivité est dépassé ; renvoie true si un verrouillage a eu lieu
+    pub fn lock_if_idle(&self, now: Instant) -> Result<bool, String> {
+        let timeout = self.auto_lock_state()?.idle_timeout_secs;
+        if timeout == 0 || !self.is_unlocked()? {
+            return Ok(false);
+        }
+
+        let last_activity = *self
+            .last_activity
+            .lock()
+            .map_err(|_| "Session du coffre indisponible".to_string())?;
+        if now.saturating_duration_since(last_activity) < Duration::from_secs(timeout) {
+            return Ok(false);
+        }
+
+        self.lock()?;
+        Ok(true)
+    }
+
+    // Exécute une opération sur le coffre, refusée tant qu'il est verrouillé
+    pub fn with_vault<T>(&self, f: impl FnOnce(&Vault) -> Result<T, String>) -> Result<T, String> {
+        self.record_activity()?;
+        let guard = self.lock_state()?;
+        match guard.as_ref() {
+            Some(vault) => f(vault),
+            None => Err("Coffre verrouillé".to_string()),
+        }
+    }
+
+    pub fn with_vault_mut<T>(&self, f: impl FnOnce(&mut Vault) -> Result<T, String>) -> Result<T, String> {
+        self.record_activity()?;
+        let mut guard = self.lock_state()?;
+        match guard.as_mut() {
+            Some(vault) => f(vault),
+            None => Err("Coffre verrouillé".to_string()),
+        }
+    }
+
+    fn auto_lock_state(&self) -> Result<MutexGuard<'_, AutoLockSettings>, String> {
+        self.auto_lock.lock().map_err(|_| "Session du coffre indisponible".to_string())
+    }
+
+    fn lock_state(&self) -> Result<MutexGuard<'_, Option<Vault>>, String> {
+        self.vault.lock().map_err(|_| "Session du coffre indisponible".to_string())
+    }
+}
+
+fn check_master_password(master_password: &str) -> Result<(), String> {
+    if master_password.chars().count() < MIN_MASTER_PASSWORD_LEN {
+        return Err(format!(
+            "Le mot de passe maître doit contenir au moins {} caractères",
+            MIN_MASTER_PASSWORD_LEN
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vault::VaultEntryInput;
+
+    fn temp_session(name: &str) -> (VaultSession, PathBuf) {
+        let path = std::env::temp_dir().join(format!("vaultword-session-{}-{}.db", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        (VaultSession::new(path.clone()), path)
+    }
+
+    #[test]
+    fn test_session_lifecycle() {
+        let (session, path) = temp_session("lifecycle");
+        let entry = VaultEntryInput {
+            site: "example.com".to_string(),
+            username: "user".to_string(),
+            password: "secret".to_string(),
+            ..VaultEntryInput::default()
+        };
+
+        assert!(!session.is_setup().unwrap());
+        assert!(session.setup("court").is_err());
+        session.setup("master-password").unwrap();
+        assert!(session.is_unlocked().unwrap());
+        session.with_vault(|vault| vault.add_entry(&entry)).unwrap();
+
+        session.lock().unwrap();
+        assert!(!session.is_unlocked().unwrap());
+        assert!(session.with_vault(|vault| vault.list_entries()).is_err());
+
+        assert!(session.unlock("mauvais").is_err());
+        assert!(!session.is_unlocked().unwrap());
+        session.unlock("master-password").unwrap();
+        assert_eq!(session.with_vault(|vault| vault.list_entries()).unwrap().len(), 1);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_lock_if_idle() {
+        let (session, path) = temp_session("idle");
+        session.setup("master-password").unwrap();
+
+        let settings = AutoLockSettings { idle_timeout_secs: 60, ..AutoLockSettings::default() };
+        session.set_auto_lock_settings(settings).unwrap();
+
+        assert!(!session.lock_if_idle(Instant::now()).unwrap());
+        assert!(session.is_unlocked().unwrap());
+        assert!(session.lock_if_idle(Instant::now() + Duration::from_secs(61)).unwrap());
+        assert!(!session.is_unlocked().unwrap());
+
+        // Les réglages sont relus depuis le coffre au déverrouillage suivant
+        session.unlock("master-password").unwrap();
+        assert_eq!(session.auto_lock_settings().unwrap(), settings);
+
+        session
+            .set_auto_lock_settings(AutoLockSettings { idle_timeout_secs: 0, ..settings })
+            .unwrap();
+        assert!(!session.lock_if_idle(Instant::now() + Duration::from_secs(3600)).unwrap());
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,1015 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use zeroize::Zeroizing;
+
+use crate::crypto::{self, VaultKey};
+use crate::kdf::KdfParams;
+use crate::migrations;
+use crate::session::AutoLockSettings;
+
+// Valeur connue chiffrée à la création du coffre, pour vérifier le mot de passe maître
+const VERIFIER_PLAINTEXT: &[u8] = b"vaultword-verifier";
+
+const ENTRY_COLUMNS: &str = "id, site, username, password, url, notes, folder, tags, favorite, created_at, updated_at, custom_fields, uris, entry_type, totp, password_history, last_used_at";
+
+// Champ libre d'une entrée (code PIN, question secrète...), importé ou saisi
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct CustomField {
+    pub name: String,
+    pub value: String,
+    // Valeur masquée à l'affichage (champ « caché » de Bitwarden)
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+// Règle de correspondance d'une URI avec la page visitée, reprise de Bitwarden
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UriMatch {
+    Domain,
+    Host,
+    StartsWith,
+    Exact,
+    Regex,
+    Never,
+}
+
+// Nom sérialisé de la règle, repris par l'export CSV
+impl UriMatch {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            UriMatch::Domain => "domain",
+            UriMatch::Host => "host",
+            UriMatch::StartsWith => "starts_with",
+            UriMatch::Exact => "exact",
+            UriMatch::Regex => "regex",
+            UriMatch::Never => "never",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<UriMatch> {
+        match name {
+            "domain" => Some(UriMatch::Domain),
+            "host" => Some(UriMatch::Host),
+            "starts_with" => Some(UriMatch::StartsWith),
+            "exact" => Some(UriMatch::Exact),
+            "regex" => Some(UriMatch::Regex),
+            "never" => Some(UriMatch::Never),
+            _ => None,
+        }
+    }
+}
+
+// URI associée à une entrée ; sans règle, la correspondance par défaut s'applique
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct EntryUri {
+    pub uri: String,
+    #[serde(rename = "match", default)]
+    pub match_type: Option<UriMatch>,
+}
+
+// Nature de l'entrée : les notes, cartes et identités n'ont pas forcément de mot de passe
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryType {
+    #[default]
+    Login,
+    SecureNote,
+    Card,
+    Identity,
+}
+
+impl EntryType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EntryType::Login => "login",
+            EntryType::SecureNote => "secure_note",
+            EntryType::Card => "card",
+            EntryType::Identity => "identity",
+        }
+    }
+
+    fn from_column(value: &str) -> Self {
+        match value {
+            "secure_note" => EntryType::SecureNote,
+            "card" => EntryType::Card,
+            "identity" => EntryType::Identity,
+            _ => EntryType::Login,
+        }
+    }
+}
+
+// Ancien mot de passe d'une entrée, avec la date à laquelle il a été remplacé
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct PasswordHistoryEntry {
+    pub password: String,
+    #[serde(default)]
+    pub changed_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VaultEntry {
+    pub id: i64,
+    pub site: String,
+    pub username: String,
+    pub password: String,
+    pub url: Option<String>,
+    #[serde(default)]
+    pub uris: Vec<EntryUri>,
+    pub notes: Option<String>,
+    pub folder: Option<String>,
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub custom_fields: Vec<CustomField>,
+    #[serde(default)]
+    pub entry_type: EntryType,
+    // URI otpauth:// ou secret base32 du code à usage unique
+    pub totp: Option<String>,
+    #[serde(default)]
+    pub password_history: Vec<PasswordHistoryEntry>,
+    pub favorite: bool,
+    pub created_at: String,
+    pub updated_at: Option<String>,
+    pub last_used_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct VaultEntryInput {
+    pub site: String,
+    pub username: String,
+    pub password: String,
+    pub url: Option<String>,
+    // Toutes les URIs de l'entrée, la principale (url) en premier
+    #[serde(default)]
+    pub uris: Vec<EntryUri>,
+    pub notes: Option<String>,
+    pub folder: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub custom_fields: Vec<CustomField>,
+    #[serde(default)]
+    pub entry_type: EntryType,
+    #[serde(default)]
+    pub totp: Option<String>,
+    #[serde(default)]
+    pub password_history: Vec<PasswordHistoryEntry>,
+    #[serde(default)]
+    pub favorite: bool,
+    // Dates reprises d'un import (« AAAA-MM-JJ HH:MM:SS », UTC) ; à défaut, celles de
+    // l'enregistrement dans le coffre
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+    #[serde(default)]
+    pub last_used_at: Option<String>,
+}
+
+impl From<VaultEntry> for VaultEntryInput {
+    fn from(entry: VaultEntry) -> Self {
+        VaultEntryInput {
+            site: entry.site,
+            username: entry.username,
+            password: entry.password,
+            url: entry.url,
+            uris: entry.uris,
+            notes: entry.notes,
+            folder: entry.folder,
+            tags: entry.tags,
+            custom_fields: entry.custom_fields,
+            entry_type: entry.entry_type,
+            totp: entry.totp,
+            password_history: entry.password_history,
+            favorite: entry.favorite,
+            created_at: Some(entry.created_at),
+            updated_at: entry.updated_at,
+            last_used_at: entry.last_used_at,
+        }
+    }
+}
+
+pub struct Vault {
+    conn: Connection,
+    key: VaultKey,
+}
+
+impl Vault {
+    // Indique si un mot de passe maître a déjà été configuré pour ce coffre
+    pub fn is_initialized(path: &Path) -> Result<bool, String> {
+        let conn = open_connection(path)?;
+        Ok(read_meta(&conn, "verifier")?.is_some())
+    }
+
+    // Crée le coffre au premier lancement avec le mot de passe maître
+    pub fn create(path: &Path, master_password: &str) -> Result<Self, String> {
+        Self::create_with_connection(open_connection(path)?, master_password)
+    }
+
+    // Déverrouille un coffre existant avec le mot de passe maître
+    pub fn unlock(path: &Path, master_password: &str) -> Result<Self, String> {
+        Self::unlock_with_connection(open_connection(path)?, master_password)
+    }
+
+    fn create_with_connection(conn: Connection, master_password: &str) -> Result<Self, String> {
+        if read_meta(&conn, "verifier")?.is_some() {
+            return Err("Un mot de passe maître est déjà configuré".to_string());
+        }
+
+        let params = read_kdf_profile(&conn)?;
+        let salt = crypto::generate_salt();
+        let key = crypto::derive_key(master_password, &salt, &params)?;
+        write_key_header(&conn, &key, &salt, &params)?;
+
+        let mut vault = Vault { conn, key };
+        vault.upgrade_legacy_plaintext()?;
+        Ok(vault)
+    }
+
+    fn unlock_with_connection(conn: Connection, master_password: &str) -> Result<Self, String> {
+        let (Some(salt), Some(verifier)) = (read_meta(&conn, "kdf_salt")?, read_meta(&conn, "verifier")?) else {
+            return Err("Aucun mot de passe maître configuré".to_string());
+        };
+
+        let params = read_kdf_params(&conn)?;
+        let key = crypto::derive_key(master_password, &salt, &params)?;
+        crypto::decrypt(&key, &verifier, b"verifier")
+            .map_err(|_| "Mot de passe maître incorrect".to_string())?;
+
+        let mut vault = Vault { conn, key };
+        vault.upgrade_legacy_plaintext()?;
+
+        // Re-dérivation transparente si le profil recommandé a changé depuis la dernière dérivation
+        let profile = read_kdf_profile(&vault.conn)?;
+        if profile != params {
+            vault.rekey(master_password, &profile)?;
+        }
+
+        Ok(vault)
+    }
+
+    // Vérifie l'ancien mot de passe puis re-chiffre le coffre sous une clé dérivée du nouveau
+    pub fn change_master_password(&mut self, old_password: &str, new_password: &str) -> Result<(), String> {
+        let (Some(salt), Some(verifier)) = (read_meta(&self.conn, "kdf_salt")?, read_meta(&self.conn, "verifier")?) else {
+            return Err("Aucun mot de passe maître configuré".to_string());
+        };
+        let old_key = crypto::derive_key(old_password, &salt, &self.kdf_params()?)?;
+        crypto::decrypt(&old_key, &verifier, b"verifier")
+            .map_err(|_| "Ancien mot de passe maître incorrect".to_string())?;
+
+        let profile = read_kdf_profile(&self.conn)?;
+        self.rekey(new_password, &profile)
+    }
+
+    // Paramètres KDF avec lesquels la clé actuelle a été dérivée
+    pub fn kdf_params(&self) -> Result<KdfParams, String> {
+        read_kdf_params(&self.conn)
+    }
+
+    // Profil recommandé, appliqué au prochain déverrouillage
+    pub fn set_kdf_profile(&self, profile: &KdfParams) -> Result<(), String> {
+        profile.validate()?;
+        let json = serde_json::to_vec(profile)
+            .map_err(|e| format!("Erreur lors de la sérialisation des paramètres KDF: {}", e))?;
+        write_meta(&self.conn, "kdf_profile", &json)
+    }
+
+    pub fn auto_lock_settings(&self) -> Result<AutoLockSettings, String> {
+        match read_meta(&self.conn, "auto_lock")? {
+            Some(json) => serde_json::from_slice(&json)
+                .map_err(|e| format!("Réglages de verrouillage illisibles: {}", e)),
+            None => Ok(AutoLockSettings::default()),
+        }
+    }
+
+    pub fn set_auto_lock_settings(&self, settings: &AutoLockSettings) -> Result<(), String> {
+        let json = serde_json::to_vec(settings)
+            .map_err(|e| format!("Erreur lors de la sérialisation des réglages: {}", e))?;
+        write_meta(&self.conn, "auto_lock", &json)
+    }
+
+    // Dérive une nouvelle clé et re-chiffre toutes les entrées dans une seule transaction :
+    // en cas d'interruption, le coffre reste lisible avec l'ancienne clé
+    fn rekey(&mut self, master_password: &str, params: &KdfParams) -> Result<(), String> {
+        let salt = crypto::generate_salt();
+        let new_key = crypto::derive_key(master_password, &salt, params)?;
+
+        let tx = self
+            .conn
+            .transaction()
+            .map_err(|e| format!("Erreur lors du re-chiffrement: {}", e))?;
+
+        let rows = read_encrypted_rows(&tx, "")
+            .map_err(|e| format!("Erreur lors du re-chiffrement: {}", e))?;
+
+        for row in rows {
+            let id = row.id;
+            let entry = row.decrypt(&self.key)?;
+            let fields = EncryptedFields::encrypt(&new_key, &entry.into())?;
+            tx.execute(
+                "UPDATE vault_entries SET site = ?1, username = ?2, password = ?3, url = ?4, notes = ?5, folder = ?6, tags = ?7,
+                 custom_fields = ?8, uris = ?9, totp = ?10, password_history = ?11 WHERE id = ?12",
+                params![fields.site, fields.username, fields.password, fields.url, fields.notes, fields.folder, fields.tags, fields.custom_fields, fields.uris, fields.totp, fields.password_history, id],
+            )
+            .map_err(|e| format!("Erreur lors du re-chiffrement: {}", e))?;
+        }
+
+        write_key_header(&tx, &new_key, &salt, params)?;
+        tx.commit()
+            .map_err(|e| format!("Erreur lors du re-chiffrement: {}", e))?;
+
+        self.key = new_key;
+        Ok(())
+    }
+
+    pub fn add_entry(&self, input: &VaultEntryInput) -> Result<VaultEntry, String> {
+        let fields = EncryptedFields::encrypt(&self.key, input)?;
+        let id = insert_entry(&self.conn, &fields, input)?;
+        self.get_entry(id)
+    }
+
+    pub fn list_entries(&self) -> Result<Vec<VaultEntry>, String> {
+        read_encrypted_rows(&self.conn, "ORDER BY favorite DESC, created_at DESC, id DESC")
+            .map_err(|e| format!("Erreur lors de la lecture du coffre: {}", e))?
+            .into_iter()
+            .map(|row| row.decrypt(&self.key))
+            .collect()
+    }
+
+    pub fn update_entry(&self, id: i64, input: &VaultEntryInput) -> Result<VaultEntry, String> {
+        let fields = EncryptedFields::encrypt(&self.key, input)?;
+        update_entry(&self.conn, id, &fields, input)?;
+        self.get_entry(id)
+    }
+
+    // Ajoute et met à jour un lot d'entrées dans une seule transaction : tout ou rien
+    pub fn import_batch(&mut self, inserts: &[VaultEntryInput], updates: &[(i64, VaultEntryInput)]) -> Result<(), String> {
+        let tx = self
+            .conn
+            .transaction()
+            .map_err(|e| format!("Erreur lors de l'import: {}", e))?;
+
+        for input in inserts {
+            insert_entry(&tx, &EncryptedFields::encrypt(&self.key, input)?, input)?;
+        }
+        for (id, input) in updates {
+            update_entry(&tx, *id, &EncryptedFields::encrypt(&self.key, input)?, input)?;
+        }
+
+        tx.commit()
+            .map_err(|e| format!("Erreur lors de l'import: {}", e))
+    }
+
+    pub fn delete_entry(&self, id: i64) -> Result<(), String> {
+        let deleted = self
+            .conn
+            .execute("DELETE FROM vault_entries WHERE id = ?1", params![id])
+            .map_err(|e| format!("Erreur lors de la suppression de l'entrée: {}", e))?;
+
+        if deleted == 0 {
+            return Err(format!("Entrée introuvable: {}", id));
+        }
+
+        Ok(())
+    }
+
+    fn get_entry(&self, id: i64) -> Result<VaultEntry, String> {
+        let row = self
+            .conn
+            .query_row(
+                &format!("SELECT {} FROM vault_entries WHERE id = ?1", ENTRY_COLUMNS),
+                params![id],
+                EncryptedRow::from_row,
+            )
+            .map_err(|e| format!("Entrée introuvable: {} ({})", id, e))?;
+
+        row.decrypt(&self.key)
+    }
+
+    // Chiffre les lignes de l'ancienne table en clair créée par le frontend, puis la supprime
+    fn upgrade_legacy_plaintext(&mut self) -> Result<(), String> {
+        let has_legacy_table: bool = self
+            .conn
+            .query_row(
+                "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'passwords')",
+                [],
+                |row| row.get(0),
+            )
+            .map_err(|e| format!("Erreur lors de la lecture du coffre: {}", e))?;
+        if !has_legacy_table {
+            return Ok(());
+        }
+
+        let tx = self
+            .conn
+            .transaction()
+            .map_err(|e| format!("Erreur lors de la migration des mots de passe en clair: {}", e))?;
+
+        let legacy_rows = {
+            let mut stmt = tx
+                .prepare("SELECT site, username, password, created_at FROM passwords ORDER BY id")
+                .map_err(|e| format!("Erreur lors de la migration des mots de passe en clair: {}", e))?;
+            let rows = stmt
+                .query_map([], |row| {
+                    Ok((
+                        VaultEntryInput {
+                            site: row.get(0)?,
+                            username: row.get(1)?,
+                            password: row.get(2)?,
+                            ..VaultEntryInput::default()
+                        },
+                        row.get::<_, Option<String>>(3)?,
+                    ))
+                })
+                .map_err(|e| format!("Erreur lors de la migration des mots de passe en clair: {}", e))?
+                .collect::<rusqlite::Result<Vec<_>>>()
+                .map_err(|e| format!("Erreur lors de la migration des mots de passe en clair: {}", e))?;
+            rows
+        };
+
+        for (input, created_at) in legacy_rows {
+            let fields = EncryptedFields::encrypt(&self.key, &input)?;
+            tx.execute(
+                "INSERT INTO vault_entries (site, username, password, created_at)
+                 VALUES (?1, ?2, ?3, COALESCE(?4, CURRENT_TIMESTAMP))",
+                params![fields.site, fields.username, fields.password, created_at],
+            )
+            .map_err(|e| format!("Erreur lors de la migration des mots de passe en clair: {}", e))?;
+        }
+
+        tx.execute_batch("DROP TABLE passwords;")
+            .map_err(|e| format!("Erreur lors de la migration des mots de passe en clair: {}", e))?;
+        tx.commit()
+            .map_err(|e| format!("Erreur lors de la migration des mots de passe en clair: {}", e))?;
+
+        // Réécrit le fichier pour ne laisser aucune trace des anciennes pages en clair
+        self.conn
+            .execute_batch("VACUUM;")
+            .map_err(|e| format!("Erreur lors du nettoyage du coffre: {}", e))
+    }
+}
+
+// Champs chiffrés d'une entrée, tels qu'écrits sur disque
+struct EncryptedFields {
+    site: Vec<u8>,
+    username: Vec<u8>,
+    password: Vec<u8>,
+    url: Option<Vec<u8>>,
+    notes: Option<Vec<u8>>,
+    folder: Option<Vec<u8>>,
+    tags: Option<Vec<u8>>,
+    custom_fields: Option<Vec<u8>>,
+    uris: Option<Vec<u8>>,
+    totp: Option<Vec<u8>>,
+    password_history: Option<Vec<u8>>,
+}
+
+impl EncryptedFields {
+    fn encrypt(key: &VaultKey, input: &VaultEntryInput) -> Result<Self, String> {
+        let optional = |value: &Option<String>, aad: &[u8]| -> Result<Option<Vec<u8>>, String> {
+            value
+                .as_deref()
+                .filter(|v| !v.is_empty())
+                .map(|v| crypto::encrypt_string(key, v, aad))
+                .transpose()
+        };
+
+        let tags = if input.tags.is_empty() {
+            None
+        } else {
+            let json = serde_json::to_string(&input.tags)
+                .map_err(|e| format!("Erreur lors de la sérialisation des tags: {}", e))?;
+            Some(crypto::encrypt_string(key, &json, b"tags")?)
+        };
+
+        let custom_fields = if input.custom_fields.is_empty() {
+            None
+        } else {
+            let json = Zeroizing::new(
+                serde_json::to_string(&input.custom_fields)
+                    .map_err(|e| format!("Erreur lors de la sérialisation des champs personnalisés: {}", e))?,
+            );
+            Some(crypto::encrypt_string(key, &json, b"custom_fields")?)
+        };
+
+        let password_history = if input.password_history.is_empty() {
+            None
+        } else {
+            let json = Zeroizing::new(
+                serde_json::to_string(&input.password_history)
+                    .map_err(|e| format!("Erreur lors de la sérialisation de l'historique: {}", e))?,
+            );
+            Some(crypto::encrypt_string(key, &json, b"password_history")?)
+        };
+
+        // La colonne url porte l'URI principale ; la liste n'est stockée que si elle apporte davantage
+        let url = input.url.clone().or_else(|| {
+            input
+                .uris
+                .iter()
+                .find(|u| u.match_type != Some(UriMatch::Regex))
+                .map(|u| u.uri.clone())
+        });
+        let redundant = match input.uris.as_slice() {
+            [] => true,
+            [only] => only.match_type.is_none() && Some(&only.uri) == url.as_ref(),
+            _ => false,
+        };
+        let uris = if redundant {
+            None
+        } else {
+            let json = serde_json::to_string(&input.uris)
+                .map_err(|e| format!("Erreur lors de la sérialisation des URIs: {}", e))?;
+            Some(crypto::encrypt_string(key, &json, b"uris")?)
+        };
+
+        Ok(EncryptedFields {
+            site: crypto::encrypt_string(key, &input.site, b"site")?,
+            username: crypto::encrypt_string(key, &input.username, b"username")?,
+            password: crypto::encrypt_string(key, &input.password, b"password")?,
+            url: optional(&url, b"url")?,
+            notes: optional(&input.notes, b"notes")?,
+            folder: optional(&input.folder, b"folder")?,
+            tags,
+            custom_fields,
+            uris,
+            totp: optional(&input.totp, b"totp")?,
+            password_history,
+        })
+    }
+}
+
+// Ligne telle que stockée sur disque, avant déchiffrement
+struct EncryptedRow {
+    id: i64,
+    fields: EncryptedFields,
+    entry_type: EntryType,
+    favorite: bool,
+    created_at: String,
+    updated_at: Option<String>,
+    last_used_at: Option<String>,
+}
+
+impl EncryptedRow {
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        Ok(EncryptedRow {
+            id: row.get(0)?,
+            fields: EncryptedFields {
+                site: row.get(1)?,
+                username: row.get(2)?,
+                password: row.get(3)?,
+                url: row.get(4)?,
+                notes: row.get(5)?,
+                folder: row.get(6)?,
+                tags: row.get(7)?,
+                custom_fields: row.get(11)?,
+                uris: row.get(12)?,
+                totp: row.get(14)?,
+                password_history: row.get(15)?,
+            },
+            entry_type: EntryType::from_column(&row.get::<_, String>(13)?),
+            favorite: row.get(8)?,
+            created_at: row.get(9)?,
+            updated_at: row.get(10)?,
+            last_used_at: row.get(16)?,
+        })
+    }
+
+    fn decrypt(self, key: &VaultKey) -> Result<VaultEntry, String> {
+        let optional = |value: &Option<Vec<u8>>, aad: &[u8]| -> Result<Option<String>, String> {
+            value.as_deref().map(|v| crypto::decrypt_string(key, v, aad)).transpose()
+        };
+
+        let tags = match optional(&self.fields.tags, b"tags")? {
+            Some(json) => serde_json::from_str(&json)
+                .map_err(|e| format!("Tags illisibles: {}", e))?,
+            None => Vec::new(),
+        };
+
+        let custom_fields = match optional(&self.fields.custom_fields, b"custom_fields")?.map(Zeroizing::new) {
+            Some(json) => serde_json::from_str(&json)
+                .map_err(|e| format!("Champs personnalisés illisibles: {}", e))?,
+            None => Vec::new(),
+        };
+
+        let password_history =
+            match optional(&self.fields.password_history, b"password_history")?.map(Zeroizing::new) {
+                Some(json) => serde_json::from_str(&json)
+                    .map_err(|e| format!("Historique des mots de passe illisible: {}", e))?,
+                None => Vec::new(),
+            };
+
+        let url = optional(&self.fields.url, b"url")?;
+        let uris = match optional(&self.fields.uris, b"uris")? {
+            Some(json) => serde_json::from_str(&json)
+                .map_err(|e| format!("URIs illisibles: {}", e))?,
+            None => url.iter().map(|uri| EntryUri { uri: uri.clone(), match_type: None }).collect(),
+        };
+
+        Ok(VaultEntry {
+            id: self.id,
+            site: crypto::decrypt_string(key, &self.fields.site, b"site")?,
+            username: crypto::decrypt_string(key, &self.fields.username, b"username")?,
+            password: crypto::decrypt_string(key, &self.fields.password, b"password")?,
+            url,
+            uris,
+            notes: optional(&self.fields.notes, b"notes")?,
+            folder: optional(&self.fields.folder, b"folder")?,
+            tags,
+            custom_fields,
+            entry_type: self.entry_type,
+            totp: optional(&self.fields.totp, b"totp")?,
+            password_history,
+            favorite: self.favorite,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            last_used_at: self.last_used_at,
+        })
+    }
+}
+
+// Le type, le favori et les dates restent en clair : ils ne révèlent rien des secrets de l'entrée
+fn insert_entry(conn: &Connection, fields: &EncryptedFields, input: &VaultEntryInput) -> Result<i64, String> {
+    conn.execute(
+        "INSERT INTO vault_entries (site, username, password, url, notes, folder, tags, custom_fields, uris, totp,
+         password_history, entry_type, favorite, created_at, updated_at, last_used_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, COALESCE(?14, CURRENT_TIMESTAMP), ?15, ?16)",
+        params![fields.site, fields.username, fields.password, fields.url, fields.notes, fields.folder, fields.tags, fields.custom_fields, fields.uris, fields.totp, fields.password_history, input.entry_type.as_str(), input.favorite, input.created_at, input.updated_at, input.last_used_at],
+    )
+    .map_err(|e| format!("Erreur lors de l'ajout de l'entrée: {}", e))?;
+
+    Ok(conn.last_insert_rowid())
+}
+
+fn update_entry(conn: &Connection, id: i64, fields: &EncryptedFields, input: &VaultEntryInput) -> Result<(), String> {
+    let updated = conn
+        .execute(
+            "UPDATE vault_entries SET site = ?1, username = ?2, password = ?3, url = ?4, notes = ?5, folder = ?6,
+             tags = ?7, custom_fields = ?8, uris = ?9, totp = ?10, password_history = ?11, entry_type = ?12,
+             favorite = ?13, updated_at = COALESCE(?14, CURRENT_TIMESTAMP), last_used_at = COALESCE(?15, last_used_at)
+             WHERE id = ?16",
+            params![fields.site, fields.username, fields.password, fields.url, fields.notes, fields.folder, fields.tags, fields.custom_fields, fields.uris, fields.totp, fields.password_history, input.entry_type.as_str(), input.favorite, input.updated_at, input.last_used_at, id],
+        )
+        .map_err(|e| format!("Erreur lors de la mise à jour de l'entrée: {}", e))?;
+
+    if updated == 0 {
+        return Err(format!("Entrée introuvable: {}", id));
+    }
+
+    Ok(())
+}
+
+fn read_encrypted_rows(conn: &Connection, order_by: &str) -> rusqlite::Result<Vec<EncryptedRow>> {
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM vault_entries {}", ENTRY_COLUMNS, order_by))?;
+    let rows = stmt.query_map([], EncryptedRow::from_row)?;
+    rows.collect()
+}
+
+fn open_connection(path: &Path) -> Result<Connection, String> {
+    let mut conn = Connection::open(path)
+        .map_err(|e| format!("Impossible d'ouvrir le coffre: {}", e))?;
+    // Les pages libérées sont effacées, pour ne pas laisser de données sur disque
+    conn.pragma_update(None, "secure_delete", true)
+        .map_err(|e| format!("Impossible d'ouvrir le coffre: {}", e))?;
+    migrations::run(&mut conn)?;
+    Ok(conn)
+}
+
+// En-tête de dérivation : sel, paramètres KDF et vérificateur de la clé
+fn write_key_header(conn: &Connection, key: &VaultKey, salt: &[u8], params: &KdfParams) -> Result<(), String> {
+    let verifier = crypto::encrypt(key, VERIFIER_PLAINTEXT, b"verifier")?;
+    let params_json = serde_json::to_vec(params)
+        .map_err(|e| format!("Erreur lors de la sérialisation des paramètres KDF: {}", e))?;
+    write_meta(conn, "kdf_salt", salt)?;
+    write_meta(conn, "kdf_params", &params_json)?;
+    write_meta(conn, "verifier", &verifier)
+}
+
+fn read_kdf_params(conn: &Connection) -> Result<KdfParams, String> {
+    read_params_meta(conn, "kdf_params")
+}
+
+fn read_kdf_profile(conn: &Connection) -> Result<KdfParams, String> {
+    read_params_meta(conn, "kdf_profile")
+}
+
+// Les coffres sans paramètres stockés ont été dérivés avec le profil par défaut
+fn read_params_meta(conn: &Connection, key: &str) -> Result<KdfParams, String> {
+    match read_meta(conn, key)? {
+        Some(json) => serde_json::from_slice(&json)
+            .map_err(|e| format!("Paramètres KDF illisibles: {}", e)),
+        None => Ok(KdfParams::default()),
+    }
+}
+
+fn read_meta(conn: &Connection, key: &str) -> Result<Option<Vec<u8>>, String> {
+    conn.query_row("SELECT value FROM vault_meta WHERE key = ?1", params![key], |row| row.get(0))
+        .optional()
+        .map_err(|e| format!("Erreur lors de la lecture du coffre: {}", e))
+}
+
+fn write_meta(conn: &Connection, key: &str, value: &[u8]) -> Result<(), String> {
+    conn.execute(
+        "INSERT OR REPLACE INTO vault_meta (key, value) VALUES (?1, ?2)",
+        params![key, value],
+    )
+    .map(|_| ())
+    .map_err(|e| format!("Erreur lors de l'écriture du coffre: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_memory_vault(master_password: &str) -> Vault {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrations::run(&mut conn).unwrap();
+        Vault::create_with_connection(conn, master_password).unwrap()
+    }
+
+    fn sample_input() -> VaultEntryInput {
+        VaultEntryInput {
+            site: "example.com".to_string(),
+            username: "user@test.com".to_string(),
+            password: "password123".to_string(),
+            ..VaultEntryInput::default()
+        }
+    }
+
+    #[test]
+    fn test_entries_are_encrypted_at_rest() {
+        let vault = open_memory_vault("master");
+        let entry = vault.add_entry(&sample_input()).unwrap();
+        assert_eq!(entry.password, "password123");
+
+        let raw: Vec<u8> = vault
+            .conn
+            .query_row("SELECT password FROM vault_entries WHERE id = ?1", params![entry.id], |row| row.get(0))
+            .unwrap();
+        assert!(!raw.windows(b"password123".len()).any(|w| w == b"password123"));
+    }
+
+    #[test]
+    fn test_add_update_delete_entry() {
+        let vault = open_memory_vault("master");
+        let entry = vault.add_entry(&sample_input()).unwrap();
+
+        let mut input = sample_input();
+        input.password = "nouveau".to_string();
+        let updated = vault.update_entry(entry.id, &input).unwrap();
+        assert_eq!(updated.password, "nouveau");
+
+        let entries = vault.list_entries().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].site, "example.com");
+
+        vault.delete_entry(entry.id).unwrap();
+        assert!(vault.list_entries().unwrap().is_empty());
+        assert!(vault.delete_entry(entry.id).is_err());
+    }
+
+    #[test]
+    fn test_entry_metadata_roundtrip() {
+        let vault = open_memory_vault("master");
+        let input = VaultEntryInput {
+            url: Some("https://example.com/login".to_string()),
+            notes: Some("Compte principal".to_string()),
+            folder: Some("Travail".to_string()),
+            tags: vec!["sso".to_string(), "prod".to_string()],
+            custom_fields: vec![CustomField { name: "PIN".to_string(), value: "1234".to_string(), hidden: false }],
+            favorite: true,
+            ..sample_input()
+        };
+
+        let entry = vault.add_entry(&input).unwrap();
+        assert_eq!(entry.url.as_deref(), Some("https://example.com/login"));
+        assert_eq!(entry.folder.as_deref(), Some("Travail"));
+        assert_eq!(entry.tags, vec!["sso", "prod"]);
+        assert_eq!(entry.custom_fields, input.custom_fields);
+        assert!(entry.favorite);
+        assert!(entry.updated_at.is_none());
+
+        let updated = vault
+            .update_entry(entry.id, &VaultEntryInput { notes: None, ..input })
+            .unwrap();
+        assert!(updated.notes.is_none());
+        assert!(updated.updated_at.is_some());
+    }
+
+    #[test]
+    fn test_entry_uris_roundtrip() {
+        let vault = open_memory_vault("master");
+
+        // Une URL seule reste une liste d'une URI sans règle
+        let entry = vault
+            .add_entry(&VaultEntryInput { url: Some("https://example.com".to_string()), ..sample_input() })
+            .unwrap();
+        assert_eq!(entry.uris, vec![EntryUri { uri: "https://example.com".to_string(), match_type: None }]);
+
+        let uris = vec![
+            EntryUri { uri: "https://sso.example.com".to_string(), match_type: Some(UriMatch::Host) },
+            EntryUri { uri: "^https://.*\\.example\\.fr/".to_string(), match_type: Some(UriMatch::Regex) },
+        ];
+        let entry = vault
+            .add_entry(&VaultEntryInput { uris: uris.clone(), ..sample_input() })
+            .unwrap();
+        assert_eq!(entry.url.as_deref(), Some("https://sso.example.com"));
+        assert_eq!(entry.uris, uris);
+    }
+
+    #[test]
+    fn test_imported_dates_are_kept() {
+        let mut vault = open_memory_vault("master");
+        let entry = vault.add_entry(&sample_input()).unwrap();
+        assert!(entry.last_used_at.is_none());
+
+        let imported = VaultEntryInput {
+            created_at: Some("2023-11-14 22:13:20".to_string()),
+            updated_at: Some("2024-01-11 19:06:40".to_string()),
+            last_used_at: Some("2024-03-09 16:00:00".to_string()),
+            ..sample_input()
+        };
+        vault.import_batch(std::slice::from_ref(&imported), &[(entry.id, imported.clone())]).unwrap();
+
+        let entries = vault.list_entries().unwrap();
+        let inserted = entries.iter().find(|e| e.id != entry.id).unwrap();
+        assert_eq!(inserted.created_at, "2023-11-14 22:13:20");
+        assert_eq!(inserted.updated_at.as_deref(), Some("2024-01-11 19:06:40"));
+        assert_eq!(inserted.last_used_at.as_deref(), Some("2024-03-09 16:00:00"));
+
+        // Une mise à jour conserve la date de création du coffre
+        let updated = vault.get_entry(entry.id).unwrap();
+        assert_eq!(updated.created_at, entry.created_at);
+        assert_eq!(updated.updated_at.as_deref(), Some("2024-01-11 19:06:40"));
+
+        // Sans date importée, la modification est datée de l'enregistrement
+        let edited = vault.update_entry(entry.id, &sample_input()).unwrap();
+        assert_ne!(edited.updated_at.as_deref(), Some("2024-01-11 19:06:40"));
+        assert_eq!(edited.last_used_at.as_deref(), Some("2024-03-09 16:00:00"));
+    }
+
+    #[test]
+    fn test_entry_type_totp_and_history_roundtrip() {
+        let vault = open_memory_vault("master");
+        let input = VaultEntryInput {
+            entry_type: EntryType::Card,
+            totp: Some("otpauth://totp/Example?secret=JBSWY3DPEHPK3PXP".to_string()),
+            password_history: vec![PasswordHistoryEntry {
+                password: "ancien".to_string(),
+                changed_at: Some("2024-01-01T00:00:00Z".to_string()),
+            }],
+            ..sample_input()
+        };
+
+        let entry = vault.add_entry(&input).unwrap();
+        assert_eq!(entry.entry_type, EntryType::Card);
+        assert_eq!(entry.totp, input.totp);
+        assert_eq!(entry.password_history, input.password_history);
+
+        let updated = vault
+            .update_entry(entry.id, &VaultEntryInput { entry_type: EntryType::Login, totp: None, ..input })
+            .unwrap();
+        assert_eq!(updated.entry_type, EntryType::Login);
+        assert!(updated.totp.is_none());
+    }
+
+    #[test]
+    fn test_import_batch_is_atomic() {
+        let mut vault = open_memory_vault("master");
+        let existing = vault.add_entry(&sample_input()).unwrap();
+
+        let mut updated = sample_input();
+        updated.password = "importé".to_string();
+        vault
+            .import_batch(&[sample_input(), sample_input()], &[(existing.id, updated.clone())])
+            .unwrap();
+        assert_eq!(vault.list_entries().unwrap().len(), 3);
+        assert_eq!(vault.get_entry(existing.id).unwrap().password, "importé");
+
+        // Une mise à jour invalide annule aussi les ajouts du même lot
+        assert!(vault.import_batch(&[sample_input()], &[(9999, updated)]).is_err());
+        assert_eq!(vault.list_entries().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_legacy_plaintext_table_is_encrypted_in_place() {
+        let path = std::env::temp_dir().join(format!("vaultword-legacy-test-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        // Base créée par l'ancienne version du frontend via tauri-plugin-sql
+        Connection::open(&path)
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE passwords (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    site TEXT NOT NULL,
+                    username TEXT NOT NULL,
+                    password TEXT NOT NULL,
+                    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+                );
+                INSERT INTO passwords (site, username, password, created_at)
+                VALUES ('legacy.com', 'ancien', 'motdepasse-en-clair', '2024-01-02 03:04:05');",
+            )
+            .unwrap();
+
+        let vault = Vault::create(&path, "master").unwrap();
+        let entries = vault.list_entries().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].password, "motdepasse-en-clair");
+        assert_eq!(entries[0].created_at, "2024-01-02 03:04:05");
+        drop(vault);
+
+        let raw = std::fs::read(&path).unwrap();
+        assert!(!raw.windows(b"motdepasse-en-clair".len()).any(|w| w == b"motdepasse-en-clair"));
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_unlock_rederives_when_profile_changes() {
+        let path = std::env::temp_dir().join(format!("vaultword-kdf-test-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let vault = Vault::create(&path, "master").unwrap();
+        vault.add_entry(&sample_input()).unwrap();
+        assert_eq!(vault.kdf_params().unwrap(), KdfParams::default());
+
+        let profile = KdfParams { memory_kib: 8 * 1024, iterations: 1, parallelism: 1 };
+        vault.set_kdf_profile(&profile).unwrap();
+        drop(vault);
+
+        let vault = Vault::unlock(&path, "master").unwrap();
+        assert_eq!(vault.kdf_params().unwrap(), profile);
+        assert_eq!(vault.list_entries().unwrap()[0].password, "password123");
+        drop(vault);
+
+        assert!(Vault::unlock(&path, "mauvais").is_err());
+        assert_eq!(Vault::unlock(&path, "master").unwrap().list_entries().unwrap().len(), 1);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_change_master_password() {
+        let path = std::env::temp_dir().join(format!("vaultword-change-test-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut vault = Vault::create(&path, "master").unwrap();
+        vault.add_entry(&sample_input()).unwrap();
+        assert!(vault.change_master_password("mauvais", "nouveau").is_err());
+        vault.change_master_password("master", "nouveau").unwrap();
+        assert_eq!(vault.list_entries().unwrap().len(), 1);
+        drop(vault);
+
+        assert!(Vault::unlock(&path, "master").is_err());
+        assert_eq!(Vault::unlock(&path, "nouveau").unwrap().list_entries().unwrap()[0].password, "password123");
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_interrupted_password_change_keeps_old_password() {
+        let path = std::env::temp_dir().join(format!("vaultword-rollback-test-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut vault = Vault::create(&path, "master").unwrap();
+        vault.add_entry(&sample_input()).unwrap();
+        let broken = vault.add_entry(&sample_input()).unwrap();
+        // Une ligne illisible fait échouer le re-chiffrement au milieu de la transaction
+        vault
+            .conn
+            .execute("UPDATE vault_entries SET site = x'00' WHERE id = ?1", params![broken.id])
+            .unwrap();
+
+        assert!(vault.change_master_password("master", "nouveau").is_err());
+        drop(vault);
+
+        assert!(Vault::unlock(&path, "nouveau").is_err());
+        let vault = Vault::unlock(&path, "master").unwrap();
+        let site: Vec<u8> = vault
+            .conn
+            .query_row("SELECT site FROM vault_entries WHERE id != ?1", params![broken.id], |row| row.get(0))
+            .unwrap();
+        assert_eq!(crypto::decrypt_string(&vault.key, &site, b"site").unwrap(), "example.com");
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_create_then_unlock() {
+        let path = std::env::temp_dir().join(format!("vaultword-test-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        assert!(!Vault::is_initialized(&path).unwrap());
+        assert!(Vault::unlock(&path, "master").is_err());
+
+        Vault::create(&path, "master").unwrap().add_entry(&sample_input()).unwrap();
+        assert!(Vault::is_initialized(&path).unwrap());
+        assert!(Vault::create(&path, "autre").is_err());
+        assert!(Vault::unlock(&path, "mauvais").is_err());
+        assert_eq!(Vault::unlock(&path, "master").unwrap().list_entries().unwrap().len(), 1);
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,160 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use crate::crypto;
+use crate::import_export::ImportedPassword;
+use crate::kdf::KdfParams;
+
+// Conteneur d'export chiffré VaultWord (.vwx), document JSON en deux parties :
+//
+//   {
+//     "header": {
+//       "format": "vaultword-export",
+//       "version": 1,
+//       "kdf": { "algorithm": "argon2id", "memory_kib": .., "iterations": .., "parallelism": .., "salt": base64 },
+//       "cipher": "xchacha20poly1305"
+//     },
+//     "data": base64(nonce de 24 octets || texte chiffré || tag Poly1305 de 16 octets)
+//   }
+//
+// La clé est dérivée du mot de passe d'export par Argon2id avec les paramètres de l'en-tête.
+// L'en-tête sérialisé sert de données associées : modifier l'en-tête ou les données fait
+// échouer la vérification du tag. Le texte clair est la liste JSON des entrées, au format
+// d'export_passwords_json. Toute évolution du format incrémente `version`.
+pub(crate) const FORMAT: &str = "vaultword-export";
+const VERSION: u32 = 1;
+const KDF_ALGORITHM: &str = "argon2id";
+const CIPHER: &str = "xchacha20poly1305";
+
+#[derive(Serialize, Deserialize)]
+struct Envelope {
+    header: Header,
+    data: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Header {
+    format: String,
+    version: u32,
+    kdf: HeaderKdf,
+    cipher: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct HeaderKdf {
+    algorithm: String,
+    #[serde(flatten)]
+    params: KdfParams,
+    salt: String,
+}
+
+impl Header {
+    // Forme canonique de l'en-tête, authentifiée avec les données
+    fn associated_data(&self) -> Result<Vec<u8>, String> {
+        serde_json::to_vec(self).map_err(|e| format!("Erreur lors de la sérialisation de l'en-tête: {}", e))
+    }
+}
+
+pub fn encrypt_export(passwords: &[ImportedPassword], export_password: &str, kdf: &KdfParams) -> Result<String, String> {
+    let salt = crypto::generate_salt();
+    let key = crypto::derive_key(export_password, &salt, kdf)?;
+    let header = Header {
+        format: FORMAT.to_string(),
+        version: VERSION,
+        kdf: HeaderKdf {
+            algorithm: KDF_ALGORITHM.to_string(),
+            params: *kdf,
+            salt: STANDARD.encode(salt),
+        },
+        cipher: CIPHER.to_string(),
+    };
+
+    let plaintext = Zeroizing::new(
+        serde_json::to_vec(passwords).map_err(|e| format!("Erreur lors de la sérialisation JSON: {}", e))?,
+    );
+    let data = crypto::encrypt(&key, &plaintext, &header.associated_data()?)?;
+
+    serde_json::to_string_pretty(&Envelope { header, data: STANDARD.encode(data) })
+        .map_err(|e| format!("Erreur lors de la sérialisation JSON: {}", e))
+}
+
+pub fn decrypt_export(content: &str, export_password: &str) -> Result<Vec<ImportedPassword>, String> {
+    let envelope: Envelope =
+        serde_json::from_str(content).map_err(|_| "Le fichier n'est pas un export VaultWord chiffré".to_string())?;
+    let header = &envelope.header;
+    if header.format != FORMAT {
+        return Err("Le fichier n'est pas un export VaultWord chiffré".to_string());
+    }
+    if header.version != VERSION {
+        return Err(format!("Version d'export .vwx non prise en charge: {}", header.version));
+    }
+    if header.kdf.algorithm != KDF_ALGORITHM || header.cipher != CIPHER {
+        return Err("Algorithmes de l'export .vwx non pris en charge".to_string());
+    }
+
+    let salt = STANDARD
+        .decode(&header.kdf.salt)
+        .map_err(|_| "Sel de l'export .vwx invalide".to_string())?;
+    let data = STANDARD
+        .decode(&envelope.data)
+        .map_err(|_| "Données de l'export .vwx invalides".to_string())?;
+
+    // Les paramètres KDF sont bornés par KdfParams::validate avant toute dérivation
+    let key = crypto::derive_key(export_password, &salt, &header.kdf.params)?;
+    let plaintext = Zeroizing::new(
+        crypto::decrypt(&key, &data, &header.associated_data()?)
+            .map_err(|_| "Mot de passe d'export incorrect ou fichier altéré".to_string())?,
+    );
+
+    serde_json::from_slice(&plaintext).map_err(|e| format!("Contenu de l'export .vwx invalide: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Vec<ImportedPassword> {
+        vec![ImportedPassword {
+            site: "example.com".to_string(),
+            username: "alice".to_string(),
+            password: "password123".to_string(),
+            notes: Some("notes".to_string()),
+            url: Some("https://example.com".to_string()),
+            folder: Some("Perso".to_string()),
+            ..ImportedPassword::default()
+        }]
+    }
+
+    const TEST_KDF: KdfParams = KdfParams { memory_kib: 8 * 1024, iterations: 1, parallelism: 1 };
+
+    #[test]
+    fn test_encrypted_export_round_trip() {
+        let content = encrypt_export(&sample(), "export-password", &TEST_KDF).unwrap();
+        assert!(!content.contains("password123"));
+
+        let passwords = decrypt_export(&content, "export-password").unwrap();
+        assert_eq!(passwords.len(), 1);
+        assert_eq!(passwords[0].password, "password123");
+        assert_eq!(passwords[0].folder.as_deref(), Some("Perso"));
+
+        assert!(decrypt_export(&content, "mauvais").is_err());
+    }
+
+    #[test]
+    fn test_encrypted_export_detects_tampering() {
+        let content = encrypt_export(&sample(), "export-password", &TEST_KDF).unwrap();
+        let mut envelope: serde_json::Value = serde_json::from_str(&content).unwrap();
+
+        // Un en-tête modifié ne correspond plus aux données associées
+        envelope["header"]["kdf"]["iterations"] = serde_json::json!(2);
+        assert!(decrypt_export(&envelope.to_string(), "export-password").is_err());
+
+        envelope["header"]["kdf"]["iterations"] = serde_json::json!(1);
+        assert!(decrypt_export(&envelope.to_string(), "export-password").is_ok());
+
+        envelope["header"]["version"] = serde_json::json!(2);
+        let err = decrypt_export(&envelope.to_string(), "export-password").unwrap_err();
+        assert!(err.contains("Version"));
+    }
+}
@@ -1,5 +1,6 @@
 import { useState, useEffect } from "react";
-import Database from "@tauri-apps/plugin-sql";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { useDisclosure } from "@heroui/react";
 
 // Composants
@@ -8,13 +9,22 @@ import PasswordList from "./components/PasswordList";
 import AddPasswordModal from "./components/AddPasswordModal";
 import PasswordGeneratorModal from "./components/PasswordGeneratorModal";
 import ImportExportModal from "./components/ImportExportModal";
+import UnlockVaultScreen from "./components/UnlockVaultScreen";
 
 interface Password {
   id: number;
   site: string;
   username: string;
   password: string;
+  url?: string;
+  uris?: { uri: string; match?: string }[];
+  notes?: string;
+  folder?: string;
+  tags: string[];
+  favorite: boolean;
   created_at: string;
+  updated_at?: string;
+  last_used_at?: string;
 }
 
 interface PasswordInput {
@@ -25,7 +35,9 @@ interface PasswordInput {
 
 function App() {
   const [passwords, setPasswords] = useState<Password[]>([]);
-  const [db, setDb] = useState<Database | null>(null);
+  const [isUnlocked, setIsUnlocked] = useState(false);
+  const [isSetup, setIsSetup] = useState<boolean | null>(null);
+  const [unlockError, setUnlockError] = useState<string | null>(null);
   const [isLoading, setIsLoading] = useState(false);
   
   // Modals avec useDisclosure pour une meilleure gestion
@@ -47,75 +59,98 @@ function App() {
     onClose: onImportExportModalClose 
   } = useDisclosure();
 
-  // Initialiser la base de données
+  // Savoir si un mot de passe maître existe déjà et si la session est ouverte
   useEffect(() => {
-    const initDB = async () => {
+    const initSession = async () => {
       try {
-        console.log("🗄️ Initialisation de la base de données...");
-        setIsLoading(true);
-        const database = await Database.load("sqlite:passwords.db");
-        console.log("✅ Base de données connectée");
-        
-        // Créer la table si elle n'existe pas
-        console.log("🏗️ Création de la table si nécessaire...");
-        await database.execute(`
-          CREATE TABLE IF NOT EXISTS passwords (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            site TEXT NOT NULL,
-            username TEXT NOT NULL,
-            password TEXT NOT NULL,
-            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
-          )
-        `);
-        console.log("✅ Table créée/vérifiée");
-        
-        setDb(database);
-        await loadPasswords(database);
-        console.log("🎉 Initialisation terminée");
+        setIsSetup(await invoke<boolean>("is_master_password_set"));
+        if (await invoke<boolean>("is_unlocked")) {
+          setIsUnlocked(true);
+          await loadPasswords();
+        }
       } catch (error) {
-        console.error("❌ Erreur lors de l'initialisation de la DB:", error);
-      } finally {
-        setIsLoading(false);
+        console.error("❌ Erreur lors de l'initialisation de la session:", error);
       }
     };
 
-    initDB();
+    initSession();
   }, []);
 
+  // Le backend verrouille le coffre après inactivité, mise en veille ou réduction de la fenêtre
+  useEffect(() => {
+    const unlisten = listen<string>("vault-locked", (event) => {
+      console.log("🔒 Coffre verrouillé:", event.payload);
+      setPasswords([]);
+      setIsUnlocked(false);
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // Signaler l'activité de l'utilisateur pour repousser le verrouillage automatique
+  useEffect(() => {
+    if (!isUnlocked) return;
+
+    let lastReport = 0;
+    const reportActivity = () => {
+      const now = Date.now();
+      if (now - lastReport < 10_000) return;
+      lastReport = now;
+      invoke("record_activity").catch(() => {});
+    };
+
+    window.addEventListener("mousemove", reportActivity);
+    window.addEventListener("keydown", reportActivity);
+    return () => {
+      window.removeEventListener("mousemove", reportActivity);
+      window.removeEventListener("keydown", reportActivity);
+    };
+  }, [isUnlocked]);
+
+  // Créer ou déverrouiller le coffre chiffré côté Rust avec le mot de passe maître
+  const handleUnlock = async (masterPassword: string) => {
+    try {
+      setUnlockError(null);
+      await invoke(isSetup ? "unlock_vault" : "setup_master_password", { masterPassword });
+      setIsSetup(true);
+      setIsUnlocked(true);
+      await loadPasswords();
+    } catch (error) {
+      console.error("❌ Erreur lors de l'ouverture du coffre:", error);
+      setUnlockError(String(error));
+    }
+  };
+
+  // Verrouiller le coffre : la clé est effacée côté Rust et l'événement "vault-locked" est émis
+  const handleLock = async () => {
+    try {
+      await invoke("lock_vault");
+    } catch (error) {
+      console.error("❌ Erreur lors du verrouillage:", error);
+    }
+  };
+
   // Charger les mots de passe
-  const loadPasswords = async (database: Database) => {
+  const loadPasswords = async () => {
     try {
-      console.log("📋 Chargement des mots de passe...");
-      const result = await database.select<Password[]>("SELECT * FROM passwords ORDER BY created_at DESC");
-      console.log("📊 Mots de passe récupérés:", result.length, "entrées");
-      console.log("📄 Données:", result);
+      setIsLoading(true);
+      const result = await invoke<Password[]>("vault_list_entries");
       setPasswords(result);
     } catch (error) {
       console.error("❌ Erreur lors du chargement des mots de passe:", error);
+    } finally {
+      setIsLoading(false);
     }
   };
 
   // Ajouter un mot de passe
   const handleAddPassword = async (passwordData: PasswordInput) => {
-    console.log("🔐 Tentative d'ajout de mot de passe:", passwordData);
-    
-    if (!db) {
-      console.error("❌ Base de données non initialisée");
-      return;
-    }
-    
     try {
       setIsLoading(true);
-      console.log("📝 Insertion en base de données...");
-      
-      await db.execute(
-        "INSERT INTO passwords (site, username, password) VALUES (?, ?, ?)",
-        [passwordData.site, passwordData.username, passwordData.password]
-      );
-      
-      console.log("✅ Insertion réussie, rechargement des données...");
-      await loadPasswords(db);
-      console.log("🔄 Données rechargées, fermeture du modal");
+      await invoke("vault_add_entry", { entry: passwordData });
+      await loadPasswords();
       onAddModalClose();
     } catch (error) {
       console.error("❌ Erreur lors de l'ajout du mot de passe:", error);
@@ -126,12 +161,10 @@ function App() {
 
   // Supprimer un mot de passe
   const handleDeletePassword = async (id: number) => {
-    if (!db) return;
-    
     try {
       setIsLoading(true);
-      await db.execute("DELETE FROM passwords WHERE id = ?", [id]);
-      await loadPasswords(db);
+      await invoke("vault_delete_entry", { id });
+      await loadPasswords();
     } catch (error) {
       console.error("Erreur lors de la suppression:", error);
     } finally {
@@ -143,9 +176,7 @@ function App() {
   const handleImportComplete = async (importedCount: number) => {
     console.log(`✅ Import terminé: ${importedCount} mots de passe importés`);
     // Recharger la liste des mots de passe
-    if (db) {
-      await loadPasswords(db);
-    }
+    await loadPasswords();
     onImportExportModalClose();
   };
 
@@ -165,6 +196,20 @@ function App() {
     onImportExportModalOpen();
   };
 
+  if (isSetup === null) {
+    return null;
+  }
+
+  if (!isUnlocked) {
+    return (
+      <UnlockVaultScreen
+        mode={isSetup ? "unlock" : "setup"}
+        onSubmit={handleUnlock}
+        error={unlockError}
+      />
+    );
+  }
+
   if (isLoading && passwords.length === 0) {
     return (
       <div className="min-h-screen bg-gradient-to-br from-blue-50 via-white to-purple-50 flex items-center justify-center">
@@ -183,6 +228,7 @@ function App() {
           onAddPassword={handleOpenAddModal}
           onGeneratePassword={handleOpenGeneratorModal}
           onImportExport={handleOpenImportExportModal}
+          onLock={handleLock}
         />
         
         <main>
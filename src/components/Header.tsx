@@ -4,7 +4,8 @@ import {
   KeyIcon, 
   ShieldCheckIcon, 
   FingerPrintIcon,
-  ArrowsRightLeftIcon 
+  ArrowsRightLeftIcon,
+  LockClosedIcon
 } from "@heroicons/react/24/outline";
 import { useState } from "react";
 import { BiometricAuthModal } from "./BiometricAuthModal";
@@ -14,9 +15,10 @@ interface HeaderProps {
   onAddPassword: () => void;
   onGeneratePassword: () => void;
   onImportExport: () => void;
+  onLock: () => void;
 }
 
-export default function Header({ onAddPassword, onGeneratePassword, onImportExport }: HeaderProps) {
+export default function Header({ onAddPassword, onGeneratePassword, onImportExport, onLock }: HeaderProps) {
   const [isBiometricModalOpen, setIsBiometricModalOpen] = useState(false);
   const { isAvailable } = useBiometric();
 
@@ -81,6 +83,16 @@ export default function Header({ onAddPassword, onGeneratePassword, onImportExpo
                 Biométrie
               </Button>
             )}
+
+            <Button
+              color="default"
+              variant="flat"
+              startContent={<LockClosedIcon className="h-4 w-4" />}
+              onPress={onLock}
+              className="bg-gray-100 hover:bg-gray-200 text-gray-700 transition-colors"
+            >
+              Verrouiller
+            </Button>
           </div>
         </div>
       </header>
@@ -1,5 +1,6 @@
 import { useState, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import {
   Modal,
   ModalContent,
@@ -22,6 +23,7 @@ import {
   TableRow,
   TableCell,
   Alert,
+  Input,
 } from "@heroui/react";
 import { 
   ArrowUpTrayIcon,
@@ -32,7 +34,7 @@ import {
   XCircleIcon,
   InformationCircleIcon
 } from "@heroicons/react/24/outline";
-import { IMPORT_SOURCES, ImportedPassword, ImportResult, ImportOptions, ExportOptions } from "../types/import-export";
+import { IMPORT_SOURCES, AUTO_IMPORT_SOURCE, PASS_IMPORT_SOURCE, BINARY_IMPORT_EXTENSIONS, PROTECTED_IMPORT_EXTENSIONS, ImportedPassword, ImportIssue, ImportResult, ImportOptions, ExportOptions, ImportProgress, ParsedImport, SourceCandidate } from "../types/import-export";
 
 interface ImportExportModalProps {
   isOpen: boolean;
@@ -41,6 +43,18 @@ interface ImportExportModalProps {
   existingPasswords?: any[];
 }
 
+const findImportSource = (id: string) =>
+  [AUTO_IMPORT_SOURCE, ...IMPORT_SOURCES, PASS_IMPORT_SOURCE].find(s => s.id === id);
+
+// Export JSON Bitwarden protégé par un mot de passe de fichier
+const isPasswordProtectedJson = (content: string) => {
+  try {
+    return JSON.parse(content)?.passwordProtected === true;
+  } catch {
+    return false;
+  }
+};
+
 type ModalStep = 'choice' | 'import-source' | 'import-file' | 'import-preview' | 'import-result' | 'export-options' | 'export-result';
 
 export default function ImportExportModal({ 
@@ -54,6 +68,15 @@ export default function ImportExportModal({
   const [selectedSource, setSelectedSource] = useState<string>('');
   const [importedPasswords, setImportedPasswords] = useState<ImportedPassword[]>([]);
   const [importWarnings, setImportWarnings] = useState<string[]>([]);
+  const [importIssues, setImportIssues] = useState<ImportIssue[]>([]);
+  const [detectedSource, setDetectedSource] = useState<SourceCandidate | null>(null);
+  const [importFile, setImportFile] = useState<
+    | { binary: false; content: string; extension: string; filePassword: string | null }
+    | { binary: true; content: number[]; extension: string; filePassword: string | null }
+    | { binary: false; path: string; extension: string; filePassword: string | null }
+    | null
+  >(null);
+  const [importProgress, setImportProgress] = useState<ImportProgress | null>(null);
   const [importResult, setImportResult] = useState<ImportResult | null>(null);
   const [importOptions, setImportOptions] = useState<ImportOptions>({
     skipDuplicates: true,
@@ -74,24 +97,68 @@ export default function ImportExportModal({
 
     setIsLoading(true);
     try {
-      const content = await file.text();
       const extension = file.name.split('.').pop()?.toLowerCase() || '';
       
       console.log("📁 Fichier sélectionné:", file.name, "Extension:", extension);
       
-      const passwords = await invoke<ImportedPassword[]>('parse_import_file', {
+      // En détection automatique, le backend retient le même candidat à l'aperçu et à l'import
+      if (selectedSource === AUTO_IMPORT_SOURCE.id) {
+        const candidates = await invoke<SourceCandidate[]>('detect_import_source', {
+          content: Array.from(new Uint8Array(await file.arrayBuffer())),
+          fileExtension: extension,
+        });
+        if (!candidates.length) {
+          throw new Error("Impossible de reconnaître la source du fichier");
+        }
+        setDetectedSource(candidates[0]);
+      } else {
+        setDetectedSource(null);
+      }
+
+      const isProtected = PROTECTED_IMPORT_EXTENSIONS.includes(extension)
+        || (extension === 'json' && isPasswordProtectedJson(await file.text()));
+      const filePassword = isProtected
+        ? window.prompt(`Mot de passe de ${file.name}`)
+        : null;
+
+      if (BINARY_IMPORT_EXTENSIONS.includes(extension)) {
+        const content = Array.from(new Uint8Array(await file.arrayBuffer()));
+        const parsed = await invoke<ParsedImport>('parse_import_binary', {
+          content,
+          source: selectedSource,
+          fileExtension: extension,
+          filePassword,
+        });
+
+        console.log("✅ Mots de passe parsés:", parsed.passwords.length);
+
+        const warnings = await invoke<string[]>('validate_import_data', { passwords: parsed.passwords });
+
+        setImportedPasswords(parsed.passwords);
+        setImportWarnings([...parsed.warnings, ...warnings]);
+        setImportIssues(parsed.issues ?? []);
+        setImportFile({ binary: true, content, extension, filePassword });
+        setCurrentStep('import-preview');
+        return;
+      }
+
+      const content = await file.text();
+      const parsed = await invoke<ParsedImport>('parse_import_file', {
         content,
         source: selectedSource,
-        fileExtension: extension
+        fileExtension: extension,
+        filePassword,
       });
 
-      console.log("✅ Mots de passe parsés:", passwords.length);
+      console.log("✅ Mots de passe parsés:", parsed.passwords.length);
       
       // Valider les données
-      const warnings = await invoke<string[]>('validate_import_data', { passwords });
+      const warnings = await invoke<string[]>('validate_import_data', { passwords: parsed.passwords });
       
-      setImportedPasswords(passwords);
-      setImportWarnings(warnings);
+      setImportedPasswords(parsed.passwords);
+      setImportWarnings([...parsed.warnings, ...warnings]);
+      setImportIssues(parsed.issues ?? []);
+      setImportFile({ binary: false, content, extension, filePassword });
       setCurrentStep('import-preview');
     } catch (error) {
       console.error("❌ Erreur lors de l'import:", error);
@@ -101,57 +168,89 @@ export default function ImportExportModal({
     }
   };
 
-  const handleImportConfirm = async () => {
-    if (!importedPasswords.length) return;
+  // Les fichiers volumineux sont lus par le backend depuis leur chemin, avec suivi de progression
+  const withImportProgress = async <T,>(task: () => Promise<T>): Promise<T> => {
+    const unlisten = await listen<ImportProgress>('import-progress', (event) => setImportProgress(event.payload));
+    try {
+      return await task();
+    } finally {
+      unlisten();
+      setImportProgress(null);
+    }
+  };
+
+  // Fichier volumineux, ou dossier d'un store pass déchiffré
+  const handlePathSelect = async (command: 'pick_import_file' | 'pick_import_directory') => {
+    const path = await invoke<string | null>(command);
+    if (!path) return;
 
     setIsLoading(true);
     try {
-      // Simuler l'import dans la base de données
-      // En réalité, cela devrait utiliser une commande Tauri pour insérer en base
-      let imported = 0;
-      let skipped = 0;
-      const errors: string[] = [];
-
-      for (const password of importedPasswords) {
-        try {
-          // Vérifier les doublons si l'option est activée
-          if (importOptions.skipDuplicates) {
-            const isDuplicate = existingPasswords.some(existing => 
-              existing.site.toLowerCase() === password.site.toLowerCase() &&
-              existing.username.toLowerCase() === password.username.toLowerCase()
-            );
-            
-            if (isDuplicate) {
-              skipped++;
-              continue;
-            }
-          }
-
-          // Simuler l'insertion (à remplacer par un appel à la base de données)
-          await new Promise(resolve => setTimeout(resolve, 50));
-          imported++;
-        } catch (error) {
-          errors.push(`Erreur pour ${password.site}: ${error}`);
-        }
-      }
+      const extension = command === 'pick_import_file' ? path.split('.').pop()?.toLowerCase() || '' : '';
+      const filePassword = PROTECTED_IMPORT_EXTENSIONS.includes(extension)
+        ? window.prompt(`Mot de passe de ${path.split(/[\\/]/).pop()}`)
+        : null;
+
+      const parsed = await withImportProgress(() => invoke<ParsedImport>('parse_import_path', {
+        path,
+        source: selectedSource,
+        filePassword,
+      }));
 
-      const result: ImportResult = {
-        success: true,
-        imported,
-        skipped,
-        errors,
-        warnings: importWarnings,
-        duplicates: []
-      };
+      setDetectedSource(null);
+      setImportedPasswords(parsed.passwords);
+      setImportWarnings(parsed.warnings);
+      setImportIssues(parsed.issues ?? []);
+      setImportFile({ binary: false, path, extension, filePassword });
+      setCurrentStep('import-preview');
+    } catch (error) {
+      console.error("❌ Erreur lors de l'import:", error);
+      alert(`Erreur lors de l'import: ${error}`);
+    } finally {
+      setIsLoading(false);
+    }
+  };
+
+  const handleCancelImport = () => invoke('cancel_import');
+
+  const handleImportConfirm = async () => {
+    if (!importedPasswords.length || !importFile) return;
+
+    setIsLoading(true);
+    try {
+      // Le backend re-parse le fichier, applique les options et enregistre tout en une transaction
+      const result = 'path' in importFile
+        ? await withImportProgress(() => invoke<ImportResult>('import_path_into_vault', {
+            path: importFile.path,
+            source: selectedSource,
+            options: importOptions,
+            filePassword: importFile.filePassword,
+          }))
+        : importFile.binary
+        ? await invoke<ImportResult>('import_binary_into_vault', {
+            content: importFile.content,
+            source: selectedSource,
+            fileExtension: importFile.extension,
+            options: importOptions,
+            filePassword: importFile.filePassword,
+          })
+        : await invoke<ImportResult>('import_into_vault', {
+            content: importFile.content,
+            source: selectedSource,
+            fileExtension: importFile.extension,
+            options: importOptions,
+            filePassword: importFile.filePassword,
+          });
 
       setImportResult(result);
       setCurrentStep('import-result');
       
       if (onImportComplete) {
-        onImportComplete(imported);
+        onImportComplete(result.imported);
       }
     } catch (error) {
       console.error("❌ Erreur lors de l'import:", error);
+      alert(`Erreur lors de l'import: ${error}`);
     } finally {
       setIsLoading(false);
     }
@@ -165,12 +264,22 @@ export default function ImportExportModal({
         site: p.site,
         username: p.username,
         password: p.password,
-        notes: undefined,
-        url: undefined,
-        folder: undefined,
+        notes: p.notes ?? undefined,
+        url: p.url ?? undefined,
+        uris: p.uris ?? undefined,
+        folder: p.folder ?? undefined,
+        tags: p.tags ?? undefined,
+        custom_fields: p.custom_fields ?? undefined,
+        entry_type: p.entry_type ?? undefined,
+        totp: p.totp ?? undefined,
+        favorite: p.favorite ?? undefined,
+        password_history: p.password_history ?? undefined,
+        created_at: p.created_at ?? undefined,
+        updated_at: p.updated_at ?? undefined,
+        last_used_at: p.last_used_at ?? undefined,
       }));
 
-      let exportContent: string;
+      let exportContent: string | number[];
       let filename: string;
       let mimeType: string;
 
@@ -192,15 +301,49 @@ export default function ImportExportModal({
           mimeType = 'application/json';
           break;
         
+        case 'bitwarden':
+          exportContent = await invoke<string>('export_passwords_bitwarden_json', {
+            passwords: passwordsToExport
+          });
+          filename = `vaultword-bitwarden-${new Date().toISOString().split('T')[0]}.json`;
+          mimeType = 'application/json';
+          break;
+        
+        case 'pass':
+          // L'arborescence est écrite directement dans le dossier choisi
+          console.log('✅ Store pass écrit dans:', await invoke<string>('export_passwords_pass', {
+            passwords: passwordsToExport
+          }));
+          setCurrentStep('export-result');
+          return;
+        
+        case 'encrypted':
+          exportContent = await invoke<string>('export_passwords_encrypted', {
+            passwords: passwordsToExport,
+            exportPassword: exportOptions.exportPassword ?? ''
+          });
+          filename = `vaultword-export-${new Date().toISOString().split('T')[0]}.vwx`;
+          mimeType = 'application/json';
+          break;
+        
+        case 'kdbx':
+          exportContent = await invoke<number[]>('export_passwords_kdbx', {
+            passwords: passwordsToExport,
+            exportPassword: exportOptions.exportPassword ?? ''
+          });
+          filename = `vaultword-export-${new Date().toISOString().split('T')[0]}.kdbx`;
+          mimeType = 'application/x-keepass2';
+          break;
+        
         default:
           throw new Error('Format d\'export non supporté');
       }
 
       // Utiliser l'API Tauri pour sauvegarder le fichier
-      const savedPath = await invoke<string>('save_export_file', {
-        content: exportContent,
-        filename: filename
-      });
+      const savedPath = await invoke<string>(
+        Array.isArray(exportContent) ? 'save_export_binary_file' : 'save_export_file',
+        { content: exportContent, filename: filename }
+      );
       
       console.log('✅ Fichier sauvegardé à:', savedPath);
 
@@ -218,6 +361,8 @@ export default function ImportExportModal({
     setSelectedSource('');
     setImportedPasswords([]);
     setImportWarnings([]);
+    setImportIssues([]);
+    setDetectedSource(null);
     setImportResult(null);
     setIsLoading(false);
     if (fileInputRef.current) {
@@ -230,6 +375,50 @@ export default function ImportExportModal({
     onClose();
   };
 
+  const renderImportProgress = () =>
+    importProgress && (
+      <div className="space-y-2">
+        <Progress
+          aria-label="Progression de l'import"
+          isIndeterminate={importProgress.stage !== 'parsing'}
+          value={importProgress.totalBytes ? (importProgress.bytesRead / importProgress.totalBytes) * 100 : 0}
+        />
+        <div className="flex items-center justify-between text-sm text-gray-600">
+          <span>
+            {importProgress.stage === 'parsing'
+              ? `${importProgress.rows} lignes lues`
+              : `Enregistrement de ${importProgress.rows} entrées...`}
+          </span>
+          {importProgress.stage === 'parsing' && (
+            <Button size="sm" variant="flat" color="danger" onPress={handleCancelImport}>
+              Annuler
+            </Button>
+          )}
+        </div>
+      </div>
+    );
+
+  // Lignes du fichier écartées à la lecture, chacune avec sa raison
+  const renderImportIssues = (issues: ImportIssue[]) =>
+    issues.length > 0 && (
+      <Alert color="danger" variant="flat">
+        <ExclamationTriangleIcon className="h-5 w-5" />
+        <div className="text-left">
+          <p className="font-medium">{issues.length} ligne(s) ignorée(s)</p>
+          <ul className="text-sm mt-1 space-y-1">
+            {issues.slice(0, 5).map((issue) => (
+              <li key={issue.line}>
+                • Ligne {issue.line}{issue.column ? ` (${issue.column})` : ''} : {issue.message}
+              </li>
+            ))}
+            {issues.length > 5 && (
+              <li>• ... et {issues.length - 5} autres</li>
+            )}
+          </ul>
+        </div>
+      </Alert>
+    );
+
   const renderStepContent = () => {
     switch (currentStep) {
       case 'choice':
@@ -289,7 +478,7 @@ export default function ImportExportModal({
             </div>
             
             <div className="grid grid-cols-1 md:grid-cols-2 gap-3">
-              {IMPORT_SOURCES.map((source) => (
+              {[AUTO_IMPORT_SOURCE, ...IMPORT_SOURCES, PASS_IMPORT_SOURCE].map((source) => (
                 <Card 
                   key={source.id}
                   isPressable
@@ -328,7 +517,7 @@ export default function ImportExportModal({
                 Sélectionnez votre fichier d'export
               </h3>
               <p className="text-gray-600">
-                Choisissez le fichier exporté depuis {IMPORT_SOURCES.find(s => s.id === selectedSource)?.name}
+                Choisissez le fichier exporté depuis {findImportSource(selectedSource)?.name}
               </p>
             </div>
             
@@ -337,7 +526,7 @@ export default function ImportExportModal({
                 <input
                   ref={fileInputRef}
                   type="file"
-                  accept=".csv,.json,.1pux,.xml"
+                  accept=".csv,.json,.1pux,.1pif,.xml,.txt,.kdbx,.vwx,.zip"
                   onChange={handleFileSelect}
                   className="hidden"
                 />
@@ -352,10 +541,32 @@ export default function ImportExportModal({
                   {isLoading ? "Analyse en cours..." : "Choisir un fichier"}
                 </Button>
                 <p className="text-sm text-gray-500 mt-2">
-                  Formats supportés: {IMPORT_SOURCES.find(s => s.id === selectedSource)?.supportedFormats.join(', ')}
+                  Formats supportés: {findImportSource(selectedSource)?.supportedFormats.join(', ')}
                 </p>
+                <Button
+                  variant="light"
+                  size="sm"
+                  className="mt-4"
+                  onPress={() => handlePathSelect('pick_import_file')}
+                  isDisabled={isLoading}
+                >
+                  Fichier volumineux (lecture directe)
+                </Button>
+                {selectedSource === PASS_IMPORT_SOURCE.id && (
+                  <Button
+                    variant="light"
+                    size="sm"
+                    className="mt-4"
+                    onPress={() => handlePathSelect('pick_import_directory')}
+                    isDisabled={isLoading}
+                  >
+                    Choisir le dossier du store
+                  </Button>
+                )}
               </CardBody>
             </Card>
+
+            {renderImportProgress()}
           </div>
         );
 
@@ -376,6 +587,16 @@ export default function ImportExportModal({
               </Chip>
             </div>
 
+            {detectedSource && (
+              <Alert color="primary" variant="flat">
+                Source détectée : {findImportSource(detectedSource.source)?.name ?? detectedSource.source} ({Math.round(detectedSource.confidence * 100)} %)
+              </Alert>
+            )}
+
+            {renderImportProgress()}
+
+            {renderImportIssues(importIssues)}
+
             {importWarnings.length > 0 && (
               <Alert color="warning" variant="flat">
                 <ExclamationTriangleIcon className="h-5 w-5" />
@@ -473,6 +694,8 @@ export default function ImportExportModal({
                 </div>
               </div>
             )}
+
+            {importResult && renderImportIssues(importResult.issues ?? [])}
           </div>
         );
 
@@ -496,15 +719,28 @@ export default function ImportExportModal({
                 <Select
                   selectedKeys={[exportOptions.format]}
                   onSelectionChange={(keys) => {
-                    const format = Array.from(keys)[0] as 'csv' | 'json';
-                    setExportOptions({...exportOptions, format});
+                    const format = Array.from(keys)[0] as ExportOptions['format'];
+                    setExportOptions({...exportOptions, format, passwordProtected: format === 'kdbx' || format === 'encrypted'});
                   }}
                 >
                   <SelectItem key="csv">CSV (Compatible Excel)</SelectItem>
                   <SelectItem key="json">JSON (Format VaultWord)</SelectItem>
+                  <SelectItem key="bitwarden">JSON Bitwarden (autres gestionnaires)</SelectItem>
+                  <SelectItem key="pass">Store pass en clair (pour pass insert -m)</SelectItem>
+                  <SelectItem key="encrypted">VaultWord chiffré (.vwx)</SelectItem>
+                  <SelectItem key="kdbx">KeePass KDBX 4 (chiffré)</SelectItem>
                 </Select>
               </div>
 
+              {exportOptions.passwordProtected && (
+                <Input
+                  type="password"
+                  label="Mot de passe de la base exportée"
+                  value={exportOptions.exportPassword ?? ''}
+                  onValueChange={(value) => setExportOptions({...exportOptions, exportPassword: value})}
+                />
+              )}
+
               <div className="space-y-3">
                 <Checkbox 
                   isSelected={exportOptions.includeMetadata}
@@ -512,26 +748,21 @@ export default function ImportExportModal({
                 >
                   Inclure les métadonnées (dates, notes)
                 </Checkbox>
-                <Checkbox 
-                  isSelected={exportOptions.passwordProtected}
-                  onValueChange={(checked) => setExportOptions({...exportOptions, passwordProtected: checked})}
-                  isDisabled
-                >
-                  Protection par mot de passe (bientôt disponible)
-                </Checkbox>
               </div>
             </div>
 
-            <Alert color="primary" variant="flat">
-              <InformationCircleIcon className="h-5 w-5" />
-              <div>
-                <p className="font-medium">Sécurité</p>
-                <p className="text-sm">
-                  Le fichier exporté contiendra vos mots de passe en clair. 
-                  Stockez-le dans un endroit sécurisé et supprimez-le après utilisation.
-                </p>
-              </div>
-            </Alert>
+            {!exportOptions.passwordProtected && (
+              <Alert color="primary" variant="flat">
+                <InformationCircleIcon className="h-5 w-5" />
+                <div>
+                  <p className="font-medium">Sécurité</p>
+                  <p className="text-sm">
+                    Le fichier exporté contiendra vos mots de passe en clair. 
+                    Stockez-le dans un endroit sécurisé et supprimez-le après utilisation.
+                  </p>
+                </div>
+              </Alert>
+            )}
           </div>
         );
 
@@ -22,6 +22,7 @@ import {
   ShieldCheckIcon,
   ExclamationTriangleIcon
 } from "@heroicons/react/24/outline";
+import type { PasswordStrength } from "../types/password-strength";
 
 interface PasswordGeneratorOptions {
   length: number;
@@ -46,12 +47,14 @@ export default function PasswordGeneratorModal({ isOpen, onClose }: PasswordGene
   });
   const [generatedPassword, setGeneratedPassword] = useState("");
   const [isGenerating, setIsGenerating] = useState(false);
+  const [strength, setStrength] = useState<PasswordStrength | null>(null);
 
   const generatePassword = async () => {
     setIsGenerating(true);
     try {
       const password = await invoke<string>("generate_password", { options });
       setGeneratedPassword(password);
+      setStrength(await invoke<PasswordStrength>("estimate_password_strength", { password }));
     } catch (error) {
       console.error("Erreur lors de la génération:", error);
     } finally {
@@ -70,32 +73,13 @@ export default function PasswordGeneratorModal({ isOpen, onClose }: PasswordGene
     }
   };
 
-  const getPasswordStrength = () => {
-    let score = 0;
-    let feedback = [];
-
-    if (options.length >= 12) score += 2;
-    else if (options.length >= 8) score += 1;
-    else feedback.push("Utilisez au moins 8 caractères");
-
-    if (options.include_uppercase) score += 1;
-    else feedback.push("Ajoutez des majuscules");
-
-    if (options.include_lowercase) score += 1;
-    else feedback.push("Ajoutez des minuscules");
-
-    if (options.include_numbers) score += 1;
-    else feedback.push("Ajoutez des chiffres");
-
-    if (options.include_symbols) score += 2;
-    else feedback.push("Ajoutez des symboles");
-
-    if (score >= 6) return { level: "Fort", color: "success", icon: ShieldCheckIcon };
-    if (score >= 4) return { level: "Moyen", color: "warning", icon: ExclamationTriangleIcon };
+  const getStrengthLevel = (strength: PasswordStrength) => {
+    if (strength.score >= 3) return { level: "Fort", color: "success", icon: ShieldCheckIcon };
+    if (strength.score === 2) return { level: "Moyen", color: "warning", icon: ExclamationTriangleIcon };
     return { level: "Faible", color: "danger", icon: ExclamationTriangleIcon };
   };
 
-  const strength = getPasswordStrength();
+  const strengthLevel = strength ? getStrengthLevel(strength) : null;
   const hasAtLeastOneOption = options.include_uppercase || options.include_lowercase || 
                              options.include_numbers || options.include_symbols;
 
@@ -135,14 +119,16 @@ export default function PasswordGeneratorModal({ isOpen, onClose }: PasswordGene
                   <div className="space-y-3">
                     <div className="flex items-center justify-between">
                       <span className="text-sm font-medium text-gray-600">Mot de passe généré</span>
-                      <Chip 
-                        color={strength.color as any} 
-                        variant="flat" 
-                        startContent={<strength.icon className="h-3 w-3" />}
-                        size="sm"
-                      >
-                        {strength.level}
-                      </Chip>
+                      {strengthLevel && (
+                        <Chip 
+                          color={strengthLevel.color as any} 
+                          variant="flat" 
+                          startContent={<strengthLevel.icon className="h-3 w-3" />}
+                          size="sm"
+                        >
+                          {strengthLevel.level}
+                        </Chip>
+                      )}
                     </div>
                     <div className="flex items-center space-x-2">
                       <Input
@@ -164,6 +150,19 @@ export default function PasswordGeneratorModal({ isOpen, onClose }: PasswordGene
                         <DocumentDuplicateIcon className="h-4 w-4" />
                       </Button>
                     </div>
+                    {strength && (
+                      <div className="text-xs text-gray-500 space-y-1">
+                        <p>
+                          Temps estimé pour le deviner hors ligne : {strength.crack_times.offline_slow_hashing.display}
+                        </p>
+                        {strength.feedback.warning && (
+                          <p className="text-orange-600">{strength.feedback.warning}</p>
+                        )}
+                        {strength.feedback.suggestions.map((suggestion) => (
+                          <p key={suggestion}>{suggestion}</p>
+                        ))}
+                      </div>
+                    )}
                   </div>
                 </CardBody>
               </Card>
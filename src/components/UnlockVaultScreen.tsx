@@ -0,0 +1,107 @@
+import { useState } from "react";
+import { Button, Card, CardBody, Input } from "@heroui/react";
+import { LockClosedIcon, EyeIcon, EyeSlashIcon } from "@heroicons/react/24/outline";
+
+interface UnlockVaultScreenProps {
+  // "setup" au premier lancement, "unlock" ensuite
+  mode: "setup" | "unlock";
+  onSubmit: (masterPassword: string) => Promise<void>;
+  error?: string | null;
+}
+
+export default function UnlockVaultScreen({ mode, onSubmit, error }: UnlockVaultScreenProps) {
+  const [masterPassword, setMasterPassword] = useState("");
+  const [confirmation, setConfirmation] = useState("");
+  const [isPasswordVisible, setIsPasswordVisible] = useState(false);
+  const [isLoading, setIsLoading] = useState(false);
+
+  const isSetup = mode === "setup";
+  const isFormValid = masterPassword && (!isSetup || masterPassword === confirmation);
+
+  const handleSubmit = async () => {
+    if (!isFormValid) return;
+
+    setIsLoading(true);
+    try {
+      await onSubmit(masterPassword);
+      setMasterPassword("");
+      setConfirmation("");
+    } finally {
+      setIsLoading(false);
+    }
+  };
+
+  return (
+    <div className="min-h-screen bg-gradient-to-br from-blue-50 via-white to-purple-50 flex items-center justify-center">
+      <Card className="w-full max-w-md">
+        <CardBody className="space-y-6 p-8">
+          <div className="flex items-center space-x-3">
+            <div className="bg-blue-100 rounded-full p-2">
+              <LockClosedIcon className="h-6 w-6 text-blue-600" />
+            </div>
+            <div>
+              <h2 className="text-xl font-semibold text-gray-800">
+                {isSetup ? "Créer votre coffre" : "Déverrouiller VaultWord"}
+              </h2>
+              <p className="text-sm text-gray-500">
+                {isSetup
+                  ? "Choisissez un mot de passe maître (8 caractères minimum)"
+                  : "Entrez votre mot de passe maître pour accéder au coffre"}
+              </p>
+            </div>
+          </div>
+
+          <Input
+            label="Mot de passe maître"
+            value={masterPassword}
+            onValueChange={setMasterPassword}
+            onKeyDown={(e) => e.key === "Enter" && handleSubmit()}
+            endContent={
+              <button
+                className="focus:outline-none"
+                type="button"
+                onClick={() => setIsPasswordVisible(!isPasswordVisible)}
+              >
+                {isPasswordVisible ? (
+                  <EyeSlashIcon className="h-4 w-4 text-gray-400" />
+                ) : (
+                  <EyeIcon className="h-4 w-4 text-gray-400" />
+                )}
+              </button>
+            }
+            type={isPasswordVisible ? "text" : "password"}
+            variant="bordered"
+            size="lg"
+            isInvalid={!!error}
+            errorMessage={error ?? undefined}
+          />
+
+          {isSetup && (
+            <Input
+              label="Confirmer le mot de passe maître"
+              value={confirmation}
+              onValueChange={setConfirmation}
+              onKeyDown={(e) => e.key === "Enter" && handleSubmit()}
+              type={isPasswordVisible ? "text" : "password"}
+              variant="bordered"
+              size="lg"
+              isInvalid={!!confirmation && confirmation !== masterPassword}
+              errorMessage="Les mots de passe ne correspondent pas"
+            />
+          )}
+
+          <Button
+            color="primary"
+            onPress={handleSubmit}
+            isDisabled={!isFormValid}
+            isLoading={isLoading}
+            size="lg"
+            className="w-full bg-gradient-to-r from-blue-500 to-purple-600 text-white"
+          >
+            {isSetup ? "Créer le coffre" : "Déverrouiller"}
+          </Button>
+        </CardBody>
+      </Card>
+    </div>
+  );
+}
@@ -77,8 +77,9 @@ describe('ImportExportModal', () => {
       expect(screen.getByText('Bitwarden')).toBeDefined();
       expect(screen.getByText('1Password')).toBeDefined();
       expect(screen.getByText('Google Chrome')).toBeDefined();
+      expect(screen.getByText('Microsoft Edge')).toBeDefined();
       expect(screen.getByText('Mozilla Firefox')).toBeDefined();
-      expect(screen.getByText('Safari')).toBeDefined();
+      expect(screen.getByText('Safari / Mots de passe Apple')).toBeDefined();
       expect(screen.getByText('KeePass')).toBeDefined();
       expect(screen.getByText('Dashlane')).toBeDefined();
     });
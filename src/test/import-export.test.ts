@@ -1,15 +1,17 @@
 import { describe, it, expect, vi, beforeEach } from 'vitest';
 import { mockInvoke } from './setup';
-import { IMPORT_SOURCES, ImportSource, ImportResult } from '../types/import-export';
+import { IMPORT_SOURCES, ImportSource, ImportResult, ParsedImport } from '../types/import-export';
 
 // Tests des types et constantes
 describe('Import/Export Types', () => {
   it('devrait avoir tous les gestionnaires de mots de passe supportés', () => {
-    expect(IMPORT_SOURCES).toHaveLength(8);
+    expect(IMPORT_SOURCES).toHaveLength(15);
     
     const expectedSources = [
       'lastpass', 'bitwarden', '1password', 'chrome', 
-      'firefox', 'safari', 'keepass', 'dashlane'
+      'edge', 'opera', 'brave', 'vivaldi',
+      'firefox', 'safari', 'keepass', 'dashlane',
+      'nordpass', 'protonpass', 'keeper'
     ];
     
     expectedSources.forEach(source => {
@@ -130,6 +132,30 @@ https://example.com,user@test.com,password123,notes,Example Site,Work,0`;
     expect(duplicates[0].site).toBe('example.com');
   });
 
+  it('devrait signaler les lignes ignorées sans interrompre le parsing', async () => {
+    mockInvoke.mockResolvedValueOnce({
+      passwords: [
+        { site: 'example.com', username: 'user', password: 'password123', notes: null, url: null, folder: null }
+      ],
+      warnings: [],
+      issues: [
+        { line: 3, column: null, kind: 'malformed_row', message: '2 colonne(s) au lieu de 4, ligne ignorée' },
+        { line: 4, column: 'password', kind: 'missing_field', message: 'Champ « password » manquant, ligne ignorée' }
+      ]
+    });
+
+    const { invoke } = await import('@tauri-apps/api/core');
+    const result = await invoke('parse_import_file', {
+      content: 'name,url,username,password',
+      source: 'chrome',
+      fileExtension: 'csv'
+    }) as ParsedImport;
+
+    expect(result.passwords).toHaveLength(1);
+    expect(result.issues.map((issue) => issue.line)).toEqual([3, 4]);
+    expect(result.issues[1].column).toBe('password');
+  });
+
   it('devrait valider les données importées', async () => {
     const invalidPasswords = [
       { site: '', username: 'user', password: 'pass', notes: null, url: null, folder: null },
@@ -214,6 +240,40 @@ example.com,user@test.com,password123,2023-01-01`;
     expect(() => JSON.parse(result)).not.toThrow();
   });
 
+  it('devrait exporter au format JSON Bitwarden', async () => {
+    const passwords = [
+      { site: 'example.com', username: 'user@test.com', password: 'password123', folder: 'Travail' }
+    ];
+
+    const expectedJson = JSON.stringify({
+      encrypted: false,
+      folders: [{ id: 'f-1', name: 'Travail' }],
+      items: [{ type: 1, name: 'example.com', folderId: 'f-1', login: { username: 'user@test.com', password: 'password123', uris: [] } }]
+    });
+
+    mockInvoke.mockResolvedValueOnce(expectedJson);
+
+    const { invoke } = await import('@tauri-apps/api/core');
+    const result = await invoke<string>('export_passwords_bitwarden_json', { passwords });
+
+    expect(JSON.parse(result).items[0].folderId).toBe('f-1');
+    expect(mockInvoke).toHaveBeenCalledWith('export_passwords_bitwarden_json', { passwords });
+  });
+
+  it('devrait exporter un store pass dans le dossier choisi', async () => {
+    const passwords = [
+      { site: 'github.com', username: 'alice', password: 'ghp-secret', folder: 'Work/Dev' }
+    ];
+
+    mockInvoke.mockResolvedValueOnce('/home/alice/pass-export');
+
+    const { invoke } = await import('@tauri-apps/api/core');
+    const result = await invoke<string>('export_passwords_pass', { passwords });
+
+    expect(result).toBe('/home/alice/pass-export');
+    expect(mockInvoke).toHaveBeenCalledWith('export_passwords_pass', { passwords });
+  });
+
   it('devrait sauvegarder un fichier exporté', async () => {
     const content = 'test,content,here';
     const filename = 'passwords.csv';
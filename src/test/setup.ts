@@ -25,6 +25,10 @@ vi.mock('@tauri-apps/api/core', () => ({
   invoke: mockInvoke,
 }));
 
+vi.mock('@tauri-apps/api/event', () => ({
+  listen: vi.fn(() => Promise.resolve(() => {})),
+}));
+
 vi.mock('@tauri-apps/plugin-dialog', () => ({
   save: mockDialog.save,
 }));
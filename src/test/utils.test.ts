@@ -4,17 +4,24 @@ import { IMPORT_SOURCES } from '../types/import-export';
 describe('Import/Export Utils', () => {
   describe('IMPORT_SOURCES', () => {
     it('devrait contenir tous les gestionnaires supportés', () => {
-      expect(IMPORT_SOURCES).toHaveLength(8);
+      expect(IMPORT_SOURCES).toHaveLength(15);
       
       const sourceIds = IMPORT_SOURCES.map(s => s.id);
       expect(sourceIds).toContain('lastpass');
       expect(sourceIds).toContain('bitwarden');
       expect(sourceIds).toContain('1password');
       expect(sourceIds).toContain('chrome');
+      expect(sourceIds).toContain('edge');
+      expect(sourceIds).toContain('opera');
+      expect(sourceIds).toContain('brave');
+      expect(sourceIds).toContain('vivaldi');
       expect(sourceIds).toContain('firefox');
       expect(sourceIds).toContain('safari');
       expect(sourceIds).toContain('keepass');
       expect(sourceIds).toContain('dashlane');
+      expect(sourceIds).toContain('nordpass');
+      expect(sourceIds).toContain('protonpass');
+      expect(sourceIds).toContain('keeper');
     });
 
     it('devrait avoir des propriétés requises pour chaque source', () => {
@@ -63,7 +70,7 @@ describe('Import/Export Utils', () => {
 
   describe('Validation des formats', () => {
     it('devrait valider les extensions de fichier', () => {
-      const validExtensions = ['.csv', '.json', '.1pux', '.xml'];
+      const validExtensions = ['.csv', '.json', '.1pux', '.1pif', '.xml', '.kdbx', '.zip'];
       
       IMPORT_SOURCES.forEach(source => {
         source.supportedFormats.forEach(format => {
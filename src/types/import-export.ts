@@ -6,8 +6,57 @@ export interface ImportedPassword {
   password: string;
   notes?: string;
   url?: string;
+  uris?: EntryUri[];
   folder?: string;
   tags?: string[];
+  custom_fields?: CustomField[];
+  entry_type?: EntryType;
+  totp?: string;
+  favorite?: boolean;
+  password_history?: PasswordHistoryEntry[];
+  // Dates du gestionnaire d'origine (« AAAA-MM-JJ HH:MM:SS », UTC)
+  created_at?: string;
+  updated_at?: string;
+  last_used_at?: string;
+  // Ligne du fichier d'origine, en-tête compris
+  source_line?: number;
+}
+
+export type EntryType = 'login' | 'secure_note' | 'card' | 'identity';
+
+export interface PasswordHistoryEntry {
+  password: string;
+  changed_at?: string;
+}
+
+// Règle de correspondance d'une URI, reprise de Bitwarden
+export type UriMatch = 'domain' | 'host' | 'starts_with' | 'exact' | 'regex' | 'never';
+
+export interface EntryUri {
+  uri: string;
+  match?: UriMatch;
+}
+
+export interface CustomField {
+  name: string;
+  value: string;
+  hidden?: boolean;
+}
+
+export type ImportIssueKind = 'malformed_row' | 'missing_field' | 'unsupported_type' | 'duplicate';
+
+// Ligne écartée à l'import (numéro de ligne dans le fichier, en-tête compris)
+export interface ImportIssue {
+  line: number;
+  column: string | null;
+  kind: ImportIssueKind;
+  message: string;
+}
+
+export interface ParsedImport {
+  passwords: ImportedPassword[];
+  warnings: string[];
+  issues: ImportIssue[];
 }
 
 export interface ImportResult {
@@ -16,6 +65,7 @@ export interface ImportResult {
   skipped: number;
   errors: string[];
   warnings: string[];
+  issues: ImportIssue[];
   duplicates: ImportedPassword[];
 }
 
@@ -27,7 +77,7 @@ export interface ImportOptions {
 }
 
 export interface ExportOptions {
-  format: 'csv' | 'json' | 'encrypted' | 'pdf';
+  format: 'csv' | 'json' | 'bitwarden' | 'kdbx' | 'encrypted' | 'pass' | 'pdf';
   includeMetadata: boolean;
   passwordProtected: boolean;
   exportPassword?: string;
@@ -59,6 +109,74 @@ export interface ValidationWarning {
   password: ImportedPassword;
 }
 
+// Mapping des colonnes d'un CSV quelconque (preview_csv_import / import_mapped_csv_into_vault)
+export type CsvField = 'site' | 'username' | 'password' | 'url' | 'notes' | 'folder' | 'tags' | 'custom';
+
+export interface CsvColumnMapping {
+  // Position (à partir de 0) ou nom d'en-tête
+  column: number | string;
+  field: CsvField;
+  // Nom du champ personnalisé, l'en-tête de la colonne à défaut
+  name?: string;
+}
+
+export interface CsvMapping {
+  delimiter: string;
+  quote: string;
+  hasHeader: boolean;
+  encoding: string;
+  columns: CsvColumnMapping[];
+}
+
+export interface CsvPreview {
+  headers: string[];
+  passwords: ImportedPassword[];
+  warnings: string[];
+  issues: ImportIssue[];
+  totalRows: number;
+}
+
+// Source candidate renvoyée par detect_import_source (confiance entre 0 et 1)
+export interface SourceCandidate {
+  source: string;
+  confidence: number;
+}
+
+// Événement « import-progress » émis pendant la lecture d'un fichier volumineux
+export interface ImportProgress {
+  stage: 'parsing' | 'saving' | 'done';
+  rows: number;
+  bytesRead: number;
+  totalBytes: number;
+}
+
+// Formats lus en binaire
+export const BINARY_IMPORT_EXTENSIONS = ['kdbx', '1pux', 'zip'];
+
+// Formats protégés par le mot de passe du fichier
+export const PROTECTED_IMPORT_EXTENSIONS = ['kdbx', 'vwx'];
+
+// Détection de la source d'après le contenu du fichier
+export const AUTO_IMPORT_SOURCE: ImportSource = {
+  id: 'auto',
+  name: 'Détection automatique',
+  description: 'Reconnaître le gestionnaire d\'après le contenu du fichier',
+  supportedFormats: ['.csv', '.json', '.xml', '.kdbx', '.1pux', '.1pif', '.zip', '.vwx'],
+  icon: '✨',
+  color: 'default'
+};
+
+// Store pass (password-store) déjà déchiffré : un dossier, ou un dump de `pass show`
+// dont chaque entrée commence par une ligne « === chemin === »
+export const PASS_IMPORT_SOURCE: ImportSource = {
+  id: 'pass',
+  name: 'pass (password-store)',
+  description: 'Importer un store pass déchiffré (dossier) ou un dump de pass show (.txt)',
+  supportedFormats: ['dossier', '.txt'],
+  icon: '🐧',
+  color: 'default'
+};
+
 // Sources d'import supportées
 export const IMPORT_SOURCES: ImportSource[] = [
   {
@@ -80,8 +198,8 @@ export const IMPORT_SOURCES: ImportSource[] = [
   {
     id: '1password',
     name: '1Password',
-    description: 'Importer depuis 1Password (.1pux, .csv)',
-    supportedFormats: ['.1pux', '.csv'],
+    description: 'Importer depuis 1Password (.1pux, .1pif, .csv)',
+    supportedFormats: ['.1pux', '.1pif', '.csv'],
     icon: '🔑',
     color: 'indigo'
   },
@@ -93,6 +211,38 @@ export const IMPORT_SOURCES: ImportSource[] = [
     icon: '🌐',
     color: 'yellow'
   },
+  {
+    id: 'edge',
+    name: 'Microsoft Edge',
+    description: 'Importer depuis Edge (.csv)',
+    supportedFormats: ['.csv'],
+    icon: '🌊',
+    color: 'cyan'
+  },
+  {
+    id: 'opera',
+    name: 'Opera',
+    description: 'Importer depuis Opera (.csv)',
+    supportedFormats: ['.csv'],
+    icon: '⭕',
+    color: 'red'
+  },
+  {
+    id: 'brave',
+    name: 'Brave',
+    description: 'Importer depuis Brave (.csv)',
+    supportedFormats: ['.csv'],
+    icon: '🦁',
+    color: 'orange'
+  },
+  {
+    id: 'vivaldi',
+    name: 'Vivaldi',
+    description: 'Importer depuis Vivaldi (.csv)',
+    supportedFormats: ['.csv'],
+    icon: '🎻',
+    color: 'rose'
+  },
   {
     id: 'firefox',
     name: 'Mozilla Firefox',
@@ -103,8 +253,8 @@ export const IMPORT_SOURCES: ImportSource[] = [
   },
   {
     id: 'safari',
-    name: 'Safari',
-    description: 'Importer depuis Safari (.csv)',
+    name: 'Safari / Mots de passe Apple',
+    description: 'Importer depuis Safari ou l\'app Mots de passe (.csv)',
     supportedFormats: ['.csv'],
     icon: '🧭',
     color: 'blue'
@@ -112,17 +262,41 @@ export const IMPORT_SOURCES: ImportSource[] = [
   {
     id: 'keepass',
     name: 'KeePass',
-    description: 'Importer depuis KeePass (.xml, .csv)',
-    supportedFormats: ['.xml', '.csv'],
+    description: 'Importer depuis KeePass ou KeePassXC (.kdbx, .xml, .csv)',
+    supportedFormats: ['.kdbx', '.xml', '.csv'],
     icon: '🔒',
     color: 'green'
   },
   {
     id: 'dashlane',
     name: 'Dashlane',
-    description: 'Importer depuis Dashlane (.csv)',
-    supportedFormats: ['.csv'],
+    description: 'Importer depuis Dashlane (.csv, .json)',
+    supportedFormats: ['.csv', '.json'],
     icon: '🎯',
     color: 'emerald'
+  },
+  {
+    id: 'nordpass',
+    name: 'NordPass',
+    description: 'Importer depuis NordPass (.csv)',
+    supportedFormats: ['.csv'],
+    icon: '🧊',
+    color: 'teal'
+  },
+  {
+    id: 'protonpass',
+    name: 'Proton Pass',
+    description: 'Importer depuis Proton Pass (.zip, .json, .csv)',
+    supportedFormats: ['.zip', '.json', '.csv'],
+    icon: '🟣',
+    color: 'purple'
+  },
+  {
+    id: 'keeper',
+    name: 'Keeper',
+    description: 'Importer depuis Keeper (.csv, .json)',
+    supportedFormats: ['.csv', '.json'],
+    icon: '🗝️',
+    color: 'amber'
   }
 ]; 
\ No newline at end of file
@@ -0,0 +1,23 @@
+// Types de l'estimation de robustesse (commande estimate_password_strength)
+
+export interface CrackTime {
+  seconds: number;
+  display: string;
+}
+
+export interface PasswordStrength {
+  // De 0 (trop facile à deviner) à 4 (très difficile à deviner)
+  score: 0 | 1 | 2 | 3 | 4;
+  guesses: number;
+  guesses_log10: number;
+  crack_times: {
+    online_throttling: CrackTime;
+    online_no_throttling: CrackTime;
+    offline_slow_hashing: CrackTime;
+    offline_fast_hashing: CrackTime;
+  };
+  feedback: {
+    warning: string | null;
+    suggestions: string[];
+  };
+}